@@ -0,0 +1,473 @@
+//! A scriptable, non-interactive zombiesplit client.
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::PathBuf;
+use zombiesplit::{
+    cli,
+    config::{self, tls},
+    model::{
+        game::{category::AttemptInfo, config::lint},
+        session::{self, action, event},
+        short,
+        timing::{
+            self, aggregate,
+            comparison::Pace,
+            time::{frame, human},
+        },
+    },
+    net,
+};
+
+#[tokio::main]
+async fn main() {
+    cli::handle_error(run().await)
+}
+
+/// Scriptable command-line client for zombiesplit.
+#[derive(Parser, Debug)]
+#[clap(name = "zsctl", about, version, author)]
+struct Args {
+    /// Use this client config file
+    #[clap(short, long)]
+    config: Option<PathBuf>,
+
+    /// Address of the server (defaults to the configured, or else the default, server address)
+    #[clap(short, long)]
+    address: Option<String>,
+
+    /// Connect over TLS, checking the server certificate against this CA certificate
+    #[clap(long)]
+    ca: Option<PathBuf>,
+
+    /// Connect over TLS, expecting this domain name on the server certificate
+    #[clap(long)]
+    domain: Option<String>,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Push a time (for example, "1m6s60", "1:06.6", or "1:06:36" for games timed in frames) to a split
+    Push {
+        /// The split, as either a position (from 0) or a short name
+        split: String,
+        /// The time to push
+        time: String,
+    },
+    /// Pop the most recent time from a split
+    Pop {
+        /// The split, as either a position (from 0) or a short name
+        split: String,
+    },
+    /// Remove all times from a split
+    Clear {
+        /// The split, as either a position (from 0) or a short name
+        split: String,
+    },
+    /// Start a new attempt, saving or discarding the current one
+    Reset {
+        /// Save the current attempt
+        #[clap(long, required_unless_present = "discard", conflicts_with = "discard")]
+        save: bool,
+        /// Discard the current attempt
+        #[clap(long)]
+        discard: bool,
+    },
+    /// Try again to save any runs that the server failed to save
+    RetrySave,
+    /// Print the current session state
+    Dump {
+        /// Print the state as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Print each event from the server as it arrives, until interrupted
+    Watch {
+        /// Only print events of this kind (can be given more than once)
+        #[clap(long = "kind", value_enum)]
+        kinds: Vec<EventKind>,
+        /// Only print events for the split or segment with this short name (can be given more
+        /// than once)
+        #[clap(long = "split")]
+        splits: Vec<String>,
+        /// Only print aggregate times and totals from this source (can be given more than once)
+        #[clap(long = "source", value_enum)]
+        sources: Vec<Source>,
+    },
+    /// Check a game configuration file for problems, without contacting the server
+    Check {
+        /// The game configuration file to check
+        path: PathBuf,
+    },
+}
+
+/// Kinds of event that `watch` can be restricted to.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum EventKind {
+    Total,
+    Reset,
+    Time,
+    Notes,
+    Pop,
+    Save,
+}
+
+impl From<EventKind> for event::filter::Kind {
+    fn from(kind: EventKind) -> Self {
+        match kind {
+            EventKind::Total => Self::Total,
+            EventKind::Reset => Self::Reset,
+            EventKind::Time => Self::Time,
+            EventKind::Notes => Self::Notes,
+            EventKind::Pop => Self::Pop,
+            EventKind::Save => Self::Save,
+        }
+    }
+}
+
+/// Sources of aggregate times that `watch` can be restricted to.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Source {
+    Attempt,
+    Comparison,
+}
+
+impl From<Source> for aggregate::Source {
+    fn from(source: Source) -> Self {
+        match source {
+            Source::Attempt => Self::Attempt,
+            Source::Comparison => Self::Comparison,
+        }
+    }
+}
+
+async fn run() -> anyhow::Result<()> {
+    env_logger::try_init()?;
+
+    let args = Args::parse();
+    if let Command::Check { path } = &args.command {
+        return check(path);
+    }
+    let mut client = connect(&args).await?;
+
+    match args.command {
+        Command::Push { split, time } => {
+            let index = locate(&mut client, &split).await?;
+            let time = parse_time(&mut client, &time).await?;
+            client
+                .handle_action(session::Action::Push(index, time))
+                .await?;
+        }
+        Command::Pop { split } => {
+            let index = locate(&mut client, &split).await?;
+            client
+                .handle_action(session::Action::Pop(index, action::Pop::One))
+                .await?;
+        }
+        Command::Clear { split } => {
+            let index = locate(&mut client, &split).await?;
+            client
+                .handle_action(session::Action::Pop(index, action::Pop::All))
+                .await?;
+        }
+        Command::Reset { save, .. } => {
+            let dest = if save {
+                action::OldDestination::Save
+            } else {
+                action::OldDestination::Discard
+            };
+            let outcome = client.handle_action(session::Action::NewRun(dest)).await?;
+            print_outcome(outcome);
+        }
+        Command::RetrySave => {
+            let outcome = client.handle_action(session::Action::RetrySave).await?;
+            print_outcome(outcome);
+        }
+        Command::Dump { json } => {
+            let state = client.dump().await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&Dump::new(&state))?);
+            } else {
+                print_dump(&state);
+            }
+        }
+        Command::Watch {
+            kinds,
+            splits,
+            sources,
+        } => {
+            let filter = event::Filter {
+                kinds: kinds.into_iter().map(Into::into).collect(),
+                splits: splits.iter().map(short::Name::from).collect(),
+                sources: sources.into_iter().map(Into::into).collect(),
+            };
+            watch(&mut client.with_filter(filter)).await?;
+        }
+        Command::Check { .. } => unreachable!("checks don't connect to the server"),
+    }
+
+    Ok(())
+}
+
+fn check(path: &std::path::Path) -> anyhow::Result<()> {
+    let report = lint::source(&std::fs::read_to_string(path)?);
+    for issue in &report.issues {
+        let severity = match issue.severity() {
+            lint::Severity::Warning => "warning",
+            lint::Severity::Error => "error",
+        };
+        println!("{}: {severity}: {issue}", path.display());
+    }
+
+    if report.has_errors() {
+        anyhow::bail!("{} has errors", path.display());
+    }
+    Ok(())
+}
+
+async fn connect(args: &Args) -> anyhow::Result<net::Client<Printer>> {
+    // Command-line arguments override the client configuration.
+    let mut cfg = config::Client::load(args.config.clone())?.net;
+    if args.address.is_some() {
+        cfg.address = args.address.clone();
+    }
+    if args.ca.is_some() || args.domain.is_some() {
+        cfg.tls = Some(tls::Client {
+            ca: args.ca.clone(),
+            domain: args.domain.clone(),
+        });
+    }
+
+    let address = cfg.address();
+    Ok(if let Some(tls) = &cfg.tls {
+        net::Client::new_tls(address, tls, Printer).await?
+    } else {
+        net::Client::new(address, Printer).await?
+    })
+}
+
+/// Resolves `split` into a split position, looking up short names in the server's state.
+async fn locate(client: &mut net::Client<Printer>, split: &str) -> anyhow::Result<usize> {
+    if let Ok(index) = split.parse() {
+        return Ok(index);
+    }
+
+    let name: short::Name = split.into();
+    let state = client.dump().await?;
+    let index = state
+        .attempt
+        .splits
+        .iter()
+        .position(|s| s.info.short == name)
+        .ok_or_else(|| anyhow::anyhow!("no split named {split:?}"));
+    index
+}
+
+/// Parses `time`.
+///
+/// Times with frame delimiters but no fraction of a second (such as `1:02:15`) are ambiguous;
+/// these are read in frame notation if the game is timed in frames, and as hours, minutes and
+/// seconds otherwise.
+async fn parse_time(client: &mut net::Client<Printer>, time: &str) -> anyhow::Result<timing::Time> {
+    if time.contains(frame::DELIMITER) && !time.contains('.') {
+        let state = client.dump().await?;
+        if let Some(rate) = state.attempt.category.frame_rate {
+            return Ok(frame::parse(time, rate)?);
+        }
+    }
+    Ok(time.parse::<human::Time>()?.try_into()?)
+}
+
+async fn watch(client: &mut net::Client<Printer>) -> anyhow::Result<()> {
+    let (cancel_send, cancel_recv) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _res = cancel_send.send(());
+        }
+    });
+    client.observe(cancel_recv).await?;
+    Ok(())
+}
+
+fn print_outcome(outcome: Option<session::sink::Outcome>) {
+    use session::sink::Outcome;
+    match outcome {
+        Some(Outcome::Saved) => println!("run saved"),
+        Some(Outcome::Ignored) => println!("run not saved: the server isn't saving runs"),
+        Some(Outcome::Queued) => println!("run queued for saving"),
+        None => {}
+    }
+}
+
+fn print_dump(state: &session::State) {
+    let attempt = &state.attempt;
+    println!(
+        "{}: {} (attempt {}, {} completed)",
+        attempt.category.game,
+        attempt.category.category,
+        attempt.info.total + 1,
+        attempt.info.completed
+    );
+
+    for (index, split) in attempt.splits.iter().enumerate() {
+        if let Some(segment) = attempt
+            .splits
+            .segments()
+            .iter()
+            .find(|s| s.splits.start == index)
+        {
+            print_segment(state, segment);
+        }
+        let times: Vec<String> = split.times.iter().map(|t| human(*t)).collect();
+        let cumulative = state
+            .notes
+            .get(&split.info.short)
+            .and_then(session::state::SplitNote::paced_cumulative)
+            .map_or_else(String::new, |p| format!("{} ({:?})", human(p.time), p.pace));
+        println!(
+            "{index:>3} {:<8} {:<24} {:<24} {cumulative}",
+            split.info.short,
+            split.name(),
+            times.join(" + ")
+        );
+    }
+
+    if let Some(total) = &state.total {
+        println!("total: {}", human(total.time));
+    }
+}
+
+fn print_segment(state: &session::State, segment: &session::split::Segment) {
+    let note = state.segment_notes.get(&segment.info.short);
+    let time = note
+        .filter(|n| n.delta.is_some())
+        .map_or_else(String::new, |n| human(n.aggregates.split));
+    let cumulative = note
+        .and_then(session::state::SplitNote::paced_cumulative)
+        .map_or_else(String::new, |p| format!("{} ({:?})", human(p.time), p.pace));
+    println!(
+        "    {:<8} {:<24} {time:<24} {cumulative}",
+        segment.info.short, segment.info.name
+    );
+}
+
+fn human(time: timing::Time) -> String {
+    human::Time::from(time).to_string()
+}
+
+/// JSON view of a session state dump.
+#[derive(Serialize)]
+struct Dump<'a> {
+    game: &'a str,
+    category: &'a str,
+    attempt: AttemptInfo,
+    segments: Vec<Segment<'a>>,
+    splits: Vec<Split<'a>>,
+    total: Option<human::Time>,
+}
+
+impl<'a> Dump<'a> {
+    fn new(state: &'a session::State) -> Self {
+        let attempt = &state.attempt;
+        Self {
+            game: &attempt.category.game,
+            category: &attempt.category.category,
+            attempt: attempt.info,
+            segments: attempt
+                .splits
+                .segments()
+                .iter()
+                .map(|s| Segment::new(state, s))
+                .collect(),
+            splits: attempt
+                .splits
+                .iter()
+                .map(|s| Split::new(state, s))
+                .collect(),
+            total: state.total.as_ref().map(|t| t.time.into()),
+        }
+    }
+}
+
+/// JSON view of a segment in a session state dump.
+#[derive(Serialize)]
+struct Segment<'a> {
+    short: short::Name,
+    name: &'a str,
+    splits: Vec<short::Name>,
+    is_complete: bool,
+    time: Option<human::Time>,
+    cumulative: Option<human::Time>,
+    pace: Option<Pace>,
+}
+
+impl<'a> Segment<'a> {
+    fn new(state: &session::State, segment: &'a session::split::Segment) -> Self {
+        let splits = &state.attempt.splits;
+        let note = state.segment_notes.get(&segment.info.short);
+        let is_complete = segment.is_complete(splits);
+        let paced = note.and_then(session::state::SplitNote::paced_cumulative);
+        Self {
+            short: segment.info.short,
+            name: &segment.info.name,
+            splits: segment.splits_in(splits).map(|s| s.info.short).collect(),
+            is_complete,
+            time: note
+                .filter(|_| is_complete)
+                .map(|n| n.aggregates.split.into()),
+            cumulative: paced.map(|p| p.time.into()),
+            pace: paced.map(|p| p.pace),
+        }
+    }
+}
+
+/// JSON view of a split in a session state dump.
+#[derive(Serialize)]
+struct Split<'a> {
+    short: short::Name,
+    name: &'a str,
+    times: Vec<human::Time>,
+    cumulative: Option<human::Time>,
+    pace: Option<Pace>,
+}
+
+impl<'a> Split<'a> {
+    fn new(state: &session::State, split: &'a session::Split) -> Self {
+        let paced = state
+            .notes
+            .get(&split.info.short)
+            .and_then(session::state::SplitNote::paced_cumulative);
+        Self {
+            short: split.info.short,
+            name: split.name(),
+            times: split.times.iter().map(|t| human::Time::from(*t)).collect(),
+            cumulative: paced.map(|p| p.time.into()),
+            pace: paced.map(|p| p.pace),
+        }
+    }
+}
+
+/// Observer that prints each event it sees on its own line.
+#[derive(Clone, Copy)]
+struct Printer;
+
+impl session::Observer for Printer {
+    fn observe(&self, evt: session::event::Event) {
+        println!("{evt:?}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks that the clap app works properly.
+    #[test]
+    fn verify_app() {
+        use clap::IntoApp;
+        Args::command().debug_assert();
+    }
+}
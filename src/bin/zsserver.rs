@@ -19,21 +19,51 @@ struct Args {
     /// Use this system config file
     #[clap(short, long)]
     config: Option<std::path::PathBuf>,
+
+    /// Generate a self-signed certificate at the configured TLS paths, if none exists yet
+    #[clap(long)]
+    generate_cert: bool,
+
+    /// Also make the generated certificate valid for this host name or address (can be repeated)
+    #[clap(long = "cert-name", value_name = "NAME", requires = "generate-cert")]
+    cert_names: Vec<String>,
 }
 
 async fn run() -> anyhow::Result<()> {
     env_logger::try_init()?;
 
     let args = Args::parse();
-    let cfg = Config::load(args.config)?;
+    let cfg = Config::load(args.config.clone())?;
+
+    if args.generate_cert {
+        generate_cert(&cfg, args.cert_names)?;
+    }
 
-    let manager = net::server::Manager::new(cfg)?;
+    let manager = net::server::Manager::new(cfg)?.with_config_path(args.config);
     let server = manager.server(&args.target)?;
 
     server.run().await;
     Ok(())
 }
 
+fn generate_cert(cfg: &Config, extra_names: Vec<String>) -> anyhow::Result<()> {
+    let tls = cfg
+        .net
+        .tls
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("no TLS paths configured in [net.tls]"))?;
+    let mut names = vec!["localhost".to_string()];
+    // Clients can't connect to an unspecified address, such as 0.0.0.0, so it isn't a name for
+    // the server; the user needs to give the server's real names with --cert-name instead.
+    let ip = cfg.net.address.ip();
+    if !ip.is_unspecified() {
+        names.push(ip.to_string());
+    }
+    names.extend(extra_names);
+    net::tls::ensure_self_signed(tls, names)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
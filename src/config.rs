@@ -1,6 +1,10 @@
 //! Configuring zombiesplit.
 
+pub mod client;
 pub mod server;
+pub mod theme;
+pub mod tls;
 pub mod util;
 
+pub use client::Client;
 pub use server::Server;
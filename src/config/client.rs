@@ -0,0 +1,93 @@
+//! Client configuration.
+
+pub mod keymap;
+
+pub use keymap::Keymap;
+use serde::{Deserialize, Serialize};
+
+use super::tls;
+use crate::model::timing::time::Format;
+
+/// Client configuration for zombiesplit.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Client {
+    /// Network configuration.
+    pub net: Net,
+    /// Formats for displaying times.
+    pub time: Time,
+    /// The name of the theme with which to draw the UI (see [`super::theme`]).
+    pub theme: String,
+    /// Bindings from keys to editor commands.
+    pub keymap: Keymap,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self {
+            net: Net::default(),
+            time: Time::default(),
+            theme: DEFAULT_THEME.to_string(),
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+impl Client {
+    /// Loads client configuration.
+    ///
+    /// The standard client configuration path is
+    /// `(system config dir)/xyz.ashikun.zombiesplit/client.toml`.  If this file exists, it is
+    /// loaded first.
+    ///
+    /// If `custom_path` is given, it will be loaded afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Fails if we can't load any of the files needed for the configuration, there is a problem
+    /// deserialising the configuration, or the keymap binds a key to more than one command.
+    pub fn load(custom_path: Option<std::path::PathBuf>) -> Result<Self, config::ConfigError> {
+        super::util::base_config("client", custom_path)
+            .build()?
+            .try_deserialize()
+    }
+}
+
+/// Client network configuration.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+#[serde(default)]
+pub struct Net {
+    /// Address of the server; if absent, the client connects to the default server address.
+    pub address: Option<String>,
+    /// TLS configuration; if absent, the client connects over plaintext.
+    pub tls: Option<tls::Client>,
+}
+
+impl Net {
+    /// Gets the address of the server, as a URI.
+    ///
+    /// If no address is configured, this is the default server address, with a scheme that
+    /// depends on whether TLS is configured.
+    #[must_use]
+    pub fn address(&self) -> String {
+        self.address.clone().unwrap_or_else(|| {
+            let scheme = if self.tls.is_some() { "https" } else { "http" };
+            format!("{scheme}://{}", super::server::default_addr())
+        })
+    }
+}
+
+/// Formats for displaying times in each part of the UI.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Time {
+    /// The format for times on splits.
+    pub splits: Format,
+    /// The format for deltas against the comparison.
+    pub deltas: Format,
+    /// The format for attempt and comparison totals.
+    pub totals: Format,
+}
+
+/// The name of the theme used if none is configured.
+pub const DEFAULT_THEME: &str = "edg64";
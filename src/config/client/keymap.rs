@@ -0,0 +1,275 @@
+/*! Keymaps binding keys to editor commands.
+
+Keys are named as SDL names them (for instance, `Return`, `Down`, `J`, or `.`).  A keymap in
+configuration only needs to list the commands being rebound; every other command keeps its default
+keys.  For example:
+
+```toml
+[keymap]
+cursor-down = ["N", "Down"]
+cursor-up = ["E", "Up"]
+```
+
+Each key can be bound to at most one command, counting the defaults of commands that aren't
+rebound, and so moving a key from one command to another means rebinding both.
+*/
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+/// An editor command that can be bound to keys.
+///
+/// Commands are named in keymaps in kebab-case (for instance, `cursor-down`).
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, DeserializeFromStr, SerializeDisplay,
+)]
+pub enum Command {
+    /// Start a new attempt, or reset the current one.
+    NewRun,
+    /// Move the cursor down, committing any edit in progress.
+    CursorDown,
+    /// Move the cursor up, committing any edit in progress.
+    CursorUp,
+    /// If editing, drop the current field; otherwise, pop a split time for editing.
+    Discard,
+    /// Commit the edit in progress.
+    Commit,
+    /// If editing, drop the edit; otherwise, drop all times for the split.
+    Delete,
+    /// Edit the minutes field.
+    EditMinutes,
+    /// Edit the seconds field.
+    EditSeconds,
+    /// Edit the milliseconds field.
+    EditMilliseconds,
+}
+
+impl Command {
+    /// Every command, in the order in which they are listed in keymaps.
+    pub const ALL: [Self; 9] = [
+        Self::NewRun,
+        Self::CursorDown,
+        Self::CursorUp,
+        Self::Discard,
+        Self::Commit,
+        Self::Delete,
+        Self::EditMinutes,
+        Self::EditSeconds,
+        Self::EditMilliseconds,
+    ];
+
+    /// Gets the name of this command in keymaps.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NewRun => "new-run",
+            Self::CursorDown => "cursor-down",
+            Self::CursorUp => "cursor-up",
+            Self::Discard => "discard",
+            Self::Commit => "commit",
+            Self::Delete => "delete",
+            Self::EditMinutes => "edit-minutes",
+            Self::EditSeconds => "edit-seconds",
+            Self::EditMilliseconds => "edit-milliseconds",
+        }
+    }
+
+    /// Gets the keys bound to this command by default.
+    #[must_use]
+    pub fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Self::NewRun => &["Return"],
+            Self::CursorDown => &["J", "Down"],
+            Self::CursorUp => &["K", "Up"],
+            Self::Discard => &["H"],
+            Self::Commit => &["L"],
+            Self::Delete => &["X"],
+            Self::EditMinutes => &["M"],
+            Self::EditSeconds => &["S"],
+            Self::EditMilliseconds => &["."],
+        }
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|cmd| cmd.name() == s)
+            .ok_or_else(|| Error::UnknownCommand(s.to_string()))
+    }
+}
+
+/// A map from keys to the editor commands they trigger.
+///
+/// In configuration, keymaps are tables from command names to lists of keys.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    into = "BTreeMap<String, Vec<String>>",
+    try_from = "BTreeMap<String, Vec<String>>"
+)]
+pub struct Keymap {
+    /// The keys bound to each command, including defaults.
+    bindings: BTreeMap<Command, Vec<String>>,
+    /// The command bound to each key.
+    commands: HashMap<String, Command>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(BTreeMap::new()).expect("default keymap should not have conflicts")
+    }
+}
+
+impl Keymap {
+    /// Constructs a keymap from `overrides`, giving every command not in it its default keys.
+    ///
+    /// A command overridden with no keys is unbound.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any key is bound to more than one command.
+    pub fn new(mut overrides: BTreeMap<Command, Vec<String>>) -> Result<Self> {
+        let bindings: BTreeMap<Command, Vec<String>> = Command::ALL
+            .into_iter()
+            .map(|cmd| {
+                let keys = overrides.remove(&cmd).unwrap_or_else(|| {
+                    cmd.default_keys().iter().map(ToString::to_string).collect()
+                });
+                (cmd, keys)
+            })
+            .collect();
+
+        let mut commands = HashMap::new();
+        for (cmd, keys) in &bindings {
+            for key in keys {
+                if let Some(first) = commands.insert(key.clone(), *cmd) {
+                    if first != *cmd {
+                        return Err(Error::Conflict {
+                            key: key.clone(),
+                            first,
+                            second: *cmd,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self { bindings, commands })
+    }
+
+    /// Gets the command bound to `key`, if any.
+    #[must_use]
+    pub fn command(&self, key: &str) -> Option<Command> {
+        self.commands.get(key).copied()
+    }
+
+    /// Gets the keys bound to `cmd`.
+    #[must_use]
+    pub fn keys(&self, cmd: Command) -> &[String] {
+        self.bindings.get(&cmd).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl From<Keymap> for BTreeMap<String, Vec<String>> {
+    fn from(keymap: Keymap) -> Self {
+        keymap
+            .bindings
+            .into_iter()
+            .map(|(cmd, keys)| (cmd.to_string(), keys))
+            .collect()
+    }
+}
+
+impl TryFrom<BTreeMap<String, Vec<String>>> for Keymap {
+    type Error = Error;
+
+    fn try_from(overrides: BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let overrides = overrides
+            .into_iter()
+            .map(|(cmd, keys)| Ok((cmd.parse()?, keys)))
+            .collect::<Result<_>>()?;
+        Self::new(overrides)
+    }
+}
+
+/// Errors that can occur when building a keymap.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A key was bound to two commands.
+    #[error("key {key} is bound to both {first} and {second}")]
+    Conflict {
+        key: String,
+        first: Command,
+        second: Command,
+    },
+    /// A keymap named a command that doesn't exist.
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the default keymap binds every command, and the README's keys.
+    #[test]
+    fn defaults() {
+        let keymap = Keymap::default();
+        for cmd in Command::ALL {
+            assert!(!keymap.keys(cmd).is_empty(), "{cmd:?} unbound");
+        }
+        assert_eq!(Some(Command::NewRun), keymap.command("Return"));
+        assert_eq!(Some(Command::CursorDown), keymap.command("J"));
+        assert_eq!(None, keymap.command("Q"));
+    }
+
+    /// Tests that overriding a command leaves the others alone.
+    #[test]
+    fn override_keeps_defaults() {
+        let keymap: Keymap = toml::from_str(r#"cursor-down = ["N"]"#).expect("keymap should parse");
+        assert_eq!(Some(Command::CursorDown), keymap.command("N"));
+        assert_eq!(None, keymap.command("J"));
+        assert_eq!(Some(Command::CursorUp), keymap.command("K"));
+    }
+
+    /// Tests that unknown commands are rejected.
+    #[test]
+    fn unknown_command() {
+        let err = toml::from_str::<Keymap>(r#"fly = ["F"]"#).expect_err("fly isn't a command");
+        assert!(err.to_string().contains("unknown command"));
+    }
+
+    /// Tests that binding a key to two commands is an error.
+    #[test]
+    fn conflict() {
+        let err = Keymap::new(BTreeMap::from([(Command::Commit, vec!["H".to_string()])]))
+            .expect_err("H should conflict with discard");
+        assert_eq!(
+            Error::Conflict {
+                key: "H".to_string(),
+                first: Command::Discard,
+                second: Command::Commit,
+            },
+            err
+        );
+    }
+}
@@ -1,7 +1,10 @@
 //! Main system configuration.
 
 pub mod comparison;
+pub mod control;
 mod db;
+pub mod hook;
+pub mod sink;
 
 use db::Database;
 use serde::{Deserialize, Serialize};
@@ -12,10 +15,23 @@ use serde::{Deserialize, Serialize};
 pub struct Server {
     /// The comparison configuration.
     pub comparison: comparison::Comparison,
+    /// Control-plane configuration.
+    pub control: control::Control,
     /// Database configuration.
     pub db: Database,
+    /// Path to a journal file recording every action the session handles, and the events they
+    /// cause; if absent, no journal is kept.
+    pub journal: Option<std::path::PathBuf>,
+    /// Hooks to run on session events.
+    #[serde(rename = "hook")]
+    pub hooks: Vec<hook::Hook>,
     /// Network configuration.
     pub net: Net,
+    /// Sinks receiving saved runs; if absent, runs are saved into the database.
+    ///
+    /// An empty list turns off saving runs altogether.
+    #[serde(rename = "sink")]
+    pub sinks: Option<Vec<sink::Sink>>,
 }
 
 impl Server {
@@ -39,17 +55,20 @@ impl Server {
 }
 
 /// Server network configuration.
-#[derive(Copy, Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 #[serde(default)]
 pub struct Net {
     /// Address to which the server should be bound.
     pub address: std::net::SocketAddr,
+    /// TLS configuration; if absent, the server runs over plaintext.
+    pub tls: Option<super::tls::Server>,
 }
 
 impl Default for Net {
     fn default() -> Self {
         Self {
             address: default_addr(),
+            tls: None,
         }
     }
 }
@@ -0,0 +1,29 @@
+//! Control-plane configuration for the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Server configuration for the control plane.
+#[derive(Copy, Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[serde(default)]
+pub struct Control {
+    /// What to do with the current attempt when the server is interrupted by a signal.
+    pub on_signal: OnSignal,
+}
+
+/// What the server should do with the current attempt when shutting down on a signal.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnSignal {
+    /// Save the attempt, as if a new attempt had been started.
+    Save,
+    /// Discard the attempt.
+    Discard,
+}
+
+/// By default, we save the attempt, as losing a run to an errant Ctrl-C is worse than saving a
+/// run that wasn't wanted.
+impl Default for OnSignal {
+    fn default() -> Self {
+        Self::Save
+    }
+}
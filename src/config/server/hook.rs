@@ -0,0 +1,41 @@
+//! Hook configuration for the server.
+//!
+//! Hooks let the server tell other programs (stream overlays, chat bots, and so on) about
+//! interesting things that happen during a session, by either running a command or posting to a
+//! local URL.
+
+use serde::{Deserialize, Serialize};
+
+/// Configuration for a single hook.
+///
+/// A hook with both a command and a URL does both; a hook with neither does nothing.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+#[serde(default)]
+pub struct Hook {
+    /// The events on which this hook fires.
+    pub on: Vec<Trigger>,
+    /// A command (program followed by its arguments) to run when the hook fires.
+    ///
+    /// The command receives a JSON description of the event on its standard input.
+    pub command: Vec<String>,
+    /// A plain `http` URL to which a JSON description of the event is `POST`ed when the hook
+    /// fires.
+    pub url: Option<String>,
+}
+
+/// Enumerates the events on which a hook can fire.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[serde(rename_all = "kebab-case")]
+pub enum Trigger {
+    /// The attempt was reset.
+    Reset,
+    /// The attempt was completed with a new personal best.
+    ///
+    /// If there is no personal best to compare against, every completed attempt counts.
+    PersonalBest,
+    /// A split beat its personal best ('gold split').
+    GoldSplit,
+    /// Every split in the attempt now has a time.
+    RunCompleted,
+}
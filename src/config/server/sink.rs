@@ -0,0 +1,33 @@
+//! Run sink configuration for the server.
+//!
+//! Sinks decide where runs go when they are saved.  By default, runs are saved into the
+//! database; configuring sinks replaces this default, so that runs can also (or instead) be
+//! written to files, and so that only some runs are kept.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Configuration for a single run sink.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct Sink {
+    /// Where the sink sends runs.
+    #[serde(flatten)]
+    pub target: Target,
+    /// Whether the sink should only receive completed runs.
+    #[serde(default)]
+    pub only_completed: bool,
+    /// The number of splits that must have times for the sink to receive a run.
+    #[serde(default)]
+    pub min_splits: usize,
+}
+
+/// Enumerates the places to which a sink can send runs.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[serde(tag = "to", rename_all = "kebab-case")]
+pub enum Target {
+    /// Save runs into the database.
+    Database,
+    /// Write each run, as a run file, into the directory at `path`.
+    File { path: PathBuf },
+}
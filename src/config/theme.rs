@@ -0,0 +1,273 @@
+/*! Themes, which give the client its colours.
+
+Themes live in the `themes` directory inside zombiesplit's data directory (alongside `assets`),
+with one directory per theme; the theme's name is the name of its directory.  Each theme has a
+`palette.toml` giving its colours.
+
+A palette can start with `extends = "NAME"`, in which case it only needs to give the colours that
+differ from those of the theme `NAME`.  Chains of extension are followed all the way down, and
+every colour must be given somewhere along the chain.
+*/
+
+pub mod colour;
+pub mod palette;
+
+pub use colour::Colour;
+pub use palette::Palette;
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// A loaded theme.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    /// The name of the theme.
+    pub name: String,
+    /// The theme's colour palette, with any inheritance resolved.
+    pub palette: Palette,
+}
+
+/// A directory containing themes.
+#[derive(Clone, Debug)]
+pub struct Library {
+    /// The directory.
+    root: PathBuf,
+}
+
+impl Library {
+    /// Constructs a library over the themes in `root`.
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Constructs a library over the themes in zombiesplit's data directory, if there is one.
+    #[must_use]
+    pub fn in_data_dir() -> Option<Self> {
+        super::util::dir().map(|d| Self::new(d.data_dir().join(DIR)))
+    }
+
+    /// Lists the names of all themes in the library, in alphabetical order.
+    ///
+    /// Directories without a palette aren't counted as themes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the library directory can't be read.
+    pub fn names(&self) -> Result<Vec<String>> {
+        let io_err = |source| Error::Io {
+            path: self.root.clone(),
+            source,
+        };
+        let mut names = vec![];
+        for entry in std::fs::read_dir(&self.root).map_err(io_err)? {
+            let entry = entry.map_err(io_err)?;
+            if entry.path().join(PALETTE_FILE).is_file() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Loads the theme `name`, resolving any themes it extends.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the theme, or any theme it extends, doesn't exist or can't be read; if the
+    /// themes extend each other in a cycle; or if the resolved palette has missing, unknown, or
+    /// malformed colours.
+    pub fn load(&self, name: &str) -> Result<Theme> {
+        let mut chain: Vec<String> = vec![];
+        let mut value = toml::value::Table::new();
+        let mut next = Some(name.to_string());
+
+        // Load from the theme down to its root, merging each base underneath what we have so far.
+        while let Some(name) = next {
+            if chain.contains(&name) {
+                chain.push(name);
+                return Err(Error::Cycle(chain));
+            }
+            let mut base = self.read_palette(&name)?;
+            next = match base.remove(EXTENDS_KEY) {
+                None => None,
+                Some(toml::Value::String(s)) => Some(s),
+                Some(_) => return Err(Error::BadExtends(name)),
+            };
+            merge(&mut base, value);
+            value = base;
+            chain.push(name);
+        }
+
+        let palette = toml::Value::Table(value)
+            .try_into()
+            .map_err(|source| Error::Palette {
+                name: name.to_string(),
+                source,
+            })?;
+        Ok(Theme {
+            name: name.to_string(),
+            palette,
+        })
+    }
+
+    fn read_palette(&self, name: &str) -> Result<toml::value::Table> {
+        let path = self.root.join(name).join(PALETTE_FILE);
+        if !path.is_file() {
+            return Err(Error::NotFound(name.to_string()));
+        }
+        let text = std::fs::read_to_string(&path).map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        })?;
+        toml::from_str(&text).map_err(|source| Error::Parse { path, source })
+    }
+}
+
+/// Merges `over` into `under`, so that `over` wins wherever both have a value.
+///
+/// Tables merge key by key; anything else in `over` replaces what is in `under`.
+fn merge(under: &mut toml::value::Table, over: toml::value::Table) {
+    for (key, value) in over {
+        match (under.get_mut(&key), value) {
+            (Some(toml::Value::Table(u)), toml::Value::Table(o)) => merge(u, o),
+            (_, value) => {
+                under.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The name of the themes directory inside the data directory.
+pub const DIR: &str = "themes";
+
+/// The name of the palette file inside a theme directory.
+pub const PALETTE_FILE: &str = "palette.toml";
+
+/// The key in a palette naming the theme it extends.
+const EXTENDS_KEY: &str = "extends";
+
+/// Errors that can occur when loading themes.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// There is no theme with the given name.
+    #[error("couldn't find theme {0}")]
+    NotFound(String),
+    /// A theme directory or palette couldn't be read.
+    #[error("couldn't read {path}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A palette file isn't valid TOML.
+    #[error("couldn't parse {path}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// A palette's `extends` key isn't a theme name.
+    #[error("theme {0} has a bad `extends` key (expected a theme name)")]
+    BadExtends(String),
+    /// Themes extend each other in a cycle.
+    #[error("themes extend each other in a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    /// The resolved palette has missing, unknown, or malformed colours.
+    #[error("bad palette in theme {name}")]
+    Palette {
+        name: String,
+        source: toml::de::Error,
+    },
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::timing::comparison::pace::SplitInRun;
+
+    fn shipped() -> Library {
+        Library::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DIR))
+    }
+
+    fn edg64_palette() -> String {
+        std::fs::read_to_string(shipped().root.join("edg64").join(PALETTE_FILE))
+            .expect("couldn't read edg64")
+    }
+
+    fn write_palette(root: &std::path::Path, name: &str, text: &str) {
+        let dir = root.join(name);
+        std::fs::create_dir(&dir).expect("couldn't make theme dir");
+        std::fs::write(dir.join(PALETTE_FILE), text).expect("couldn't write palette");
+    }
+
+    /// Tests that the shipped `edg64` theme loads and is discoverable.
+    #[test]
+    fn edg64() {
+        let lib = shipped();
+        assert!(lib
+            .names()
+            .expect("couldn't list themes")
+            .contains(&"edg64".to_string()));
+
+        let theme = lib.load("edg64").expect("couldn't load edg64");
+        assert_eq!(
+            Colour::rgb(0x5a, 0xc5, 0x4f),
+            theme.palette.split_in_run(SplitInRun::AheadAndLosing)
+        );
+    }
+
+    /// Tests that a theme extending another only overrides the colours it gives.
+    #[test]
+    fn extends() {
+        let dir = tempfile::tempdir().expect("couldn't open dir");
+        write_palette(dir.path(), "base", &edg64_palette());
+        write_palette(
+            dir.path(),
+            "child",
+            "extends = \"base\"\n[fg.pace]\nbehind = \"red\"\n",
+        );
+
+        let lib = Library::new(dir.path());
+        let base = lib.load("base").expect("couldn't load base").palette;
+        let child = lib.load("child").expect("couldn't load child").palette;
+
+        assert_eq!(Colour::rgb(255, 0, 0), child.fg.pace.behind);
+        assert_eq!(base.fg.pace.ahead, child.fg.pace.ahead);
+        assert_eq!(base.bg, child.bg);
+    }
+
+    /// Tests that cyclic inheritance is an error rather than a hang.
+    #[test]
+    fn cycle() {
+        let dir = tempfile::tempdir().expect("couldn't open dir");
+        write_palette(dir.path(), "a", "extends = \"b\"");
+        write_palette(dir.path(), "b", "extends = \"a\"");
+
+        let err = Library::new(dir.path())
+            .load("a")
+            .expect_err("should cycle");
+        assert!(matches!(err, Error::Cycle(chain) if chain == ["a", "b", "a"]));
+    }
+
+    /// Tests that bad colours are reported as palette errors.
+    #[test]
+    fn bad_colour() {
+        let dir = tempfile::tempdir().expect("couldn't open dir");
+        write_palette(dir.path(), "base", &edg64_palette());
+        write_palette(
+            dir.path(),
+            "child",
+            "extends = \"base\"\n[bg]\nwindow = \"#2a2f4\"",
+        );
+
+        let err = Library::new(dir.path())
+            .load("child")
+            .expect_err("should fail");
+        assert!(
+            matches!(&err, Error::Palette { source, .. } if source.to_string().contains("bad hex colour")),
+            "unexpected error: {err:?}"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Colours, as written in theme palettes.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+/// A colour, with an alpha channel.
+///
+/// Colours are written either as one of the basic web colour names (such as `white` or `navy`),
+/// or in hex notation as `#rgb`, `#rrggbb`, or `#rrggbbaa`.
+///
+/// ```
+/// use zombiesplit::config::theme::Colour;
+///
+/// let c: Colour = "#2a2f4e".parse().unwrap();
+/// assert_eq!(Colour::rgb(0x2a, 0x2f, 0x4e), c);
+/// assert_eq!(Colour::rgb(255, 255, 255), "white".parse().unwrap());
+/// assert_eq!(Colour::rgb(255, 0, 0), "#f00".parse().unwrap());
+/// assert!("#2a2f4".parse::<Colour>().is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, DeserializeFromStr, SerializeDisplay)]
+pub struct Colour {
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+    /// The alpha component, where 255 is opaque.
+    pub a: u8,
+}
+
+impl Colour {
+    /// Constructs an opaque colour from its red, green, and blue components.
+    #[must_use]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, c)| *c)
+    }
+
+    fn from_hex(hex: &str) -> Result<Self> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::BadHex(hex.to_string()));
+        }
+        // All-hex-digit strings are ASCII, so we can slice them bytewise.
+        let byte = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16);
+        let nybble = |i: usize| u8::from_str_radix(&hex[i..=i], 16).map(|n| n * 0x11);
+        let parsed = match hex.len() {
+            3 => (nybble(0), nybble(1), nybble(2), Ok(255)),
+            6 => (byte(0), byte(1), byte(2), Ok(255)),
+            8 => (byte(0), byte(1), byte(2), byte(3)),
+            _ => return Err(Error::BadHex(hex.to_string())),
+        };
+        match parsed {
+            (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(Self { r, g, b, a }),
+            _ => Err(Error::BadHex(hex.to_string())),
+        }
+    }
+}
+
+impl FromStr for Colour {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(hex) = s.strip_prefix('#') {
+            Self::from_hex(hex)
+        } else {
+            Self::from_name(s).ok_or_else(|| Error::UnknownName(s.to_string()))
+        }
+    }
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+/// The colour names we understand, which are the basic web colours.
+const NAMES: [(&str, Colour); 18] = [
+    ("black", Colour::rgb(0, 0, 0)),
+    ("silver", Colour::rgb(192, 192, 192)),
+    ("gray", Colour::rgb(128, 128, 128)),
+    ("grey", Colour::rgb(128, 128, 128)),
+    ("white", Colour::rgb(255, 255, 255)),
+    ("maroon", Colour::rgb(128, 0, 0)),
+    ("red", Colour::rgb(255, 0, 0)),
+    ("purple", Colour::rgb(128, 0, 128)),
+    ("fuchsia", Colour::rgb(255, 0, 255)),
+    ("magenta", Colour::rgb(255, 0, 255)),
+    ("green", Colour::rgb(0, 128, 0)),
+    ("lime", Colour::rgb(0, 255, 0)),
+    ("olive", Colour::rgb(128, 128, 0)),
+    ("yellow", Colour::rgb(255, 255, 0)),
+    ("navy", Colour::rgb(0, 0, 128)),
+    ("blue", Colour::rgb(0, 0, 255)),
+    ("teal", Colour::rgb(0, 128, 128)),
+    ("aqua", Colour::rgb(0, 255, 255)),
+];
+
+/// Errors that can occur when parsing a colour.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The colour was in hex notation, but not a valid hex colour.
+    #[error("bad hex colour: #{0} (expected #rgb, #rrggbb, or #rrggbbaa)")]
+    BadHex(String),
+    /// The colour was a name we don't know.
+    #[error("unknown colour name: {0}")]
+    UnknownName(String),
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that colours survive a round trip through their string form.
+    #[test]
+    fn round_trip() {
+        for s in ["#2a2f4e", "#ffc82580", "#000000"] {
+            let c: Colour = s.parse().expect("colour should parse");
+            assert_eq!(s, c.to_string());
+        }
+    }
+
+    /// Tests that malformed colours are rejected.
+    #[test]
+    fn bad() {
+        for s in [
+            "#", "#12345", "#gggggg", "#ffc8258", "2a2f4e", "whit", "#ééé",
+        ] {
+            assert!(s.parse::<Colour>().is_err(), "{s} should not parse");
+        }
+    }
+}
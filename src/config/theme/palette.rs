@@ -0,0 +1,162 @@
+//! Typed colour palettes.
+
+use serde::{Deserialize, Serialize};
+
+use super::Colour;
+use crate::model::timing::comparison::pace::{Pace, SplitInRun};
+
+/// A theme's full colour palette.
+///
+/// This mirrors the structure of `palette.toml`, in which every colour must be given (either in
+/// the file itself, or in the theme it extends).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Palette {
+    /// Background colours.
+    pub bg: Background,
+    /// Foreground colours.
+    pub fg: Foreground,
+}
+
+impl Palette {
+    /// Gets the background colour for `role`.
+    #[must_use]
+    pub fn bg(&self, role: Role) -> Colour {
+        match role {
+            Role::Window | Role::Header => self.bg.window,
+            Role::Editor => self.bg.editor,
+            Role::FieldEditor => self.bg.field_editor,
+        }
+    }
+
+    /// Gets the foreground colour for `role`.
+    #[must_use]
+    pub fn fg(&self, role: Role) -> Colour {
+        match role {
+            Role::Window => self.fg.normal,
+            Role::Header => self.fg.header,
+            Role::Editor => self.fg.editor,
+            Role::FieldEditor => self.fg.field_editor,
+        }
+    }
+
+    /// Gets the colour for a split name in the given `position` relative to the cursor.
+    #[must_use]
+    pub fn name(&self, position: NamePosition) -> Colour {
+        match position {
+            NamePosition::Done => self.fg.name.done,
+            NamePosition::Cursor => self.fg.name.cursor,
+            NamePosition::Coming => self.fg.name.coming,
+        }
+    }
+
+    /// Gets the colour for a time whose split and run are on pace `pace`.
+    #[must_use]
+    pub fn split_in_run(&self, pace: SplitInRun) -> Colour {
+        let p = &self.fg.pace;
+        match pace {
+            SplitInRun::Inconclusive => p.inconclusive,
+            SplitInRun::SplitPersonalBest => p.personal_best,
+            SplitInRun::BehindAndLosing => p.behind,
+            SplitInRun::BehindAndGaining => p.behind_but_gaining,
+            SplitInRun::AheadAndLosing => p.ahead_but_losing,
+            SplitInRun::AheadAndGaining => p.ahead,
+        }
+    }
+
+    /// Gets the colour for a time on pace `pace`, where there is no finer pace information.
+    #[must_use]
+    pub fn pace(&self, pace: Pace) -> Colour {
+        let p = &self.fg.pace;
+        match pace {
+            Pace::Inconclusive => p.inconclusive,
+            Pace::Behind => p.behind,
+            Pace::Ahead => p.ahead,
+            Pace::PersonalBest => p.personal_best,
+        }
+    }
+}
+
+/// The parts of the UI that have their own colours.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The main window, including normal text.
+    Window,
+    /// The header, which shares the window background.
+    Header,
+    /// The split editor.
+    Editor,
+    /// The field currently being edited in the split editor.
+    FieldEditor,
+}
+
+/// Positions of a split's name relative to the cursor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NamePosition {
+    /// The split is before the cursor.
+    Done,
+    /// The split is under the cursor.
+    Cursor,
+    /// The split is after the cursor.
+    Coming,
+}
+
+/// Background colours.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Background {
+    /// The window background.
+    pub window: Colour,
+    /// The split editor background.
+    pub editor: Colour,
+    /// The background of the field being edited.
+    pub field_editor: Colour,
+}
+
+/// Foreground colours.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Foreground {
+    /// Normal text.
+    pub normal: Colour,
+    /// Text in the split editor.
+    pub editor: Colour,
+    /// Text in the field being edited.
+    pub field_editor: Colour,
+    /// Text in the header.
+    pub header: Colour,
+    /// Split names.
+    pub name: Names,
+    /// Times, by pace.
+    pub pace: Paces,
+}
+
+/// Colours for split names.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Names {
+    /// Names of splits before the cursor.
+    pub done: Colour,
+    /// The name of the split under the cursor.
+    pub cursor: Colour,
+    /// Names of splits after the cursor.
+    pub coming: Colour,
+}
+
+/// Colours for times, by pace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Paces {
+    /// Times with no comparison.
+    pub inconclusive: Colour,
+    /// Times ahead of the comparison, gaining time.
+    pub ahead: Colour,
+    /// Times ahead of the comparison, but losing time on the split.
+    pub ahead_but_losing: Colour,
+    /// Times behind the comparison, but gaining time on the split.
+    pub behind_but_gaining: Colour,
+    /// Times behind the comparison, losing time.
+    pub behind: Colour,
+    /// Personal bests.
+    pub personal_best: Colour,
+}
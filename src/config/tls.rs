@@ -0,0 +1,31 @@
+//! TLS configuration, shared between the server and its clients.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Server-side TLS configuration.
+///
+/// If this is present in the server's network configuration, the server will only accept TLS
+/// connections, presenting the certificate at `cert`.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct Server {
+    /// Path to the PEM-encoded certificate (chain) the server presents to clients.
+    pub cert: PathBuf,
+    /// Path to the PEM-encoded private key for `cert`.
+    pub key: PathBuf,
+}
+
+/// Client-side TLS configuration.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+#[serde(default)]
+pub struct Client {
+    /// Path to a PEM-encoded CA certificate against which the server certificate is checked.
+    ///
+    /// For self-signed server certificates, this will be the server certificate itself; this pins
+    /// the client to that particular certificate.
+    pub ca: Option<PathBuf>,
+    /// The domain name expected on the server certificate.
+    ///
+    /// If missing, this is taken from the host part of the server address.
+    pub domain: Option<String>,
+}
@@ -5,7 +5,7 @@ use std::path::PathBuf;
 
 /// Gets the base config from folding in the user's custom file and the standard file (if any).
 ///
-/// This is exposed publicly so it can be used in `zsclient`, but this may change in future.
+/// Both the [server](super::Server) and [client](super::Client) configurations are loaded this way.
 #[must_use]
 pub fn base_config(
     name: &str,
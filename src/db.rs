@@ -3,9 +3,11 @@
 pub mod category;
 pub mod comparison;
 pub mod error;
-mod game;
+pub mod game;
+pub mod getters;
 mod init;
 pub mod inspect;
+pub mod memory;
 pub mod run;
 pub mod util;
 
@@ -13,31 +15,53 @@ use crate::model::{self, game::Config, history};
 use std::path::Path;
 
 pub use error::{Error, Result};
-use r2d2::ManageConnection;
 use r2d2_sqlite::SqliteConnectionManager;
 pub use run::Sink;
 
 use self::{
-    category::{GcID, Locator},
+    category::{GcID, Locator, Source as _},
+    getters::Getters,
     inspect::Inspector,
+    run::Source as _,
 };
 
-/// A root connection to zombiesplit's database.
+/// A pooled connection to zombiesplit's database.
+pub type Connection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+/// A root handle to zombiesplit's database.
+///
+/// The handle holds a pool of connections, and is cheap to clone; clones share the pool, and can
+/// be sent to other threads.
+#[derive(Clone)]
 pub struct Db {
-    manager: r2d2_sqlite::SqliteConnectionManager,
+    pool: r2d2::Pool<SqliteConnectionManager>,
 }
 
 impl Db {
-    /// Opens a database connection to a given file.
+    /// Opens a connection pool on a given file.
+    ///
+    /// Connections use write-ahead logging, so that reads can carry on while runs are being
+    /// saved.  If the database was initialised by an older version of zombiesplit, this migrates
+    /// it to the current schema.
     ///
     /// # Errors
     ///
     /// Returns errors from the underlying database library if the connection
-    /// opening failed.
+    /// opening, or a migration, failed.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        Ok(Self {
-            manager: SqliteConnectionManager::file(path), // TODO(@MattWindsor91): r2d2 connection pool
-        })
+        let manager = SqliteConnectionManager::file(path).with_init(init::on_connect);
+        let pool = r2d2::Pool::new(manager)?;
+        init::migrate(pool.get()?)?;
+        Ok(Self { pool })
+    }
+
+    /// Takes a connection from the pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no connection became free before the pool timed out.
+    pub fn conn(&self) -> Result<Connection> {
+        Ok(self.pool.get()?)
     }
 
     /// Gets a read handle to this database.
@@ -48,9 +72,7 @@ impl Db {
     /// some reason.
     pub fn reader(&self) -> Result<Reader> {
         // TODO(@MattWindsor91): refactor all db reads to go through this
-        Ok(Reader {
-            conn: self.manager.connect()?,
-        })
+        Ok(Reader { conn: self.conn()? })
     }
 
     /// Initialises the database for first use.
@@ -59,7 +81,7 @@ impl Db {
     ///
     /// Propagates errors from the database if anything goes wrong.
     pub fn init(&self) -> Result<()> {
-        init::on_db(&self.manager.connect()?)
+        init::on_db(self.conn()?)
     }
 
     /// Adds the game `game` to the database, assigning it shortname `short`.
@@ -69,12 +91,50 @@ impl Db {
     /// Raises an error if any of the SQL queries relating to inserting a game
     /// fail.
     pub fn add_game(&self, short: &str, game: &Config) -> Result<()> {
-        let mut conn = self.manager.connect()?;
+        let mut conn = self.conn()?;
         let tx = conn.transaction()?;
         game::Inserter::new(&tx)?.add_game(short, game)?;
         Ok(tx.commit()?)
     }
 
+    /// Works out the changes [`Db::update_game`] would make to the stored game `short`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the game isn't in the database, `game` doesn't resolve, or any of the SQL queries
+    /// fail.
+    pub fn plan_game_update(&self, short: &str, game: &Config) -> Result<game::update::Plan> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        // Planning doesn't write anything, and dropping the transaction rolls it back regardless.
+        let updater = game::update::Updater::new(&tx, short)?;
+        updater.plan(game)
+    }
+
+    /// Updates the stored game `short` to match `game`, returning the changes made.
+    ///
+    /// New splits, segments, and categories are added, and display names and structure are
+    /// brought up to date.  Changes that would leave times in stored runs unreachable, such as
+    /// removing a category or taking splits out of it, are refused unless `allow_destructive`
+    /// is set.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the game isn't in the database, the update is destructive and not allowed, or any
+    /// of the SQL queries fail; in all of these cases, the database is left unchanged.
+    pub fn update_game(
+        &self,
+        short: &str,
+        game: &Config,
+        allow_destructive: bool,
+    ) -> Result<game::update::Plan> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let plan = game::update::Updater::new(&tx, short)?.update(game, allow_destructive)?;
+        tx.commit()?;
+        Ok(plan)
+    }
+
     /// Adds the historic run `run` to the database.
     ///
     /// # Errors
@@ -83,7 +143,7 @@ impl Db {
     /// fail.
     pub fn add_run<L: Locator>(&self, run: &history::run::FullyTimed<L>) -> Result<()> {
         let run = run.with_locator(self.resolve_gcid(&run.category_locator)?);
-        let mut conn = self.manager.connect()?;
+        let mut conn = self.conn()?;
         let tx = conn.transaction()?;
         run::Inserter::new(&tx)?.add(&run)?;
         Ok(tx.commit()?)
@@ -95,7 +155,7 @@ impl Db {
     ///
     /// Raises an error if the underlying SQL query fails.
     pub fn game_categories(&self) -> Result<Vec<model::game::category::Target>> {
-        let conn = self.manager.connect()?;
+        let conn = self.conn()?;
         let mut getter = category::Getter::new(&conn)?;
         getter.all_game_category_info()
     }
@@ -109,7 +169,8 @@ impl Db {
     /// fail.
     pub fn runs_for<L: Locator>(&self, loc: &L) -> Result<Vec<history::run::Summary<GcID>>> {
         let id = self.resolve_gcid(loc)?;
-        let runs = run::Getter::new(&self.manager.connect()?)?.runs_for(id)?;
+        let conn = self.conn()?;
+        let runs = run::Getter::new(&conn)?.runs_for(id)?;
         Ok(runs.into_iter().map(|x| x.item).collect())
     }
 
@@ -118,7 +179,7 @@ impl Db {
         if let Some(x) = loc.as_game_category_id() {
             Ok(x)
         } else {
-            let conn = self.manager.connect()?;
+            let conn = self.conn()?;
             let mut getter = category::Getter::new(&conn)?;
 
             loc.locate_gcid(&mut getter)
@@ -128,7 +189,7 @@ impl Db {
 
 /// A handle used to perform read operations on the database.
 pub struct Reader {
-    conn: rusqlite::Connection,
+    conn: Connection,
 }
 
 impl Reader {
@@ -137,7 +198,7 @@ impl Reader {
     /// # Errors
     ///
     /// Errors if we can't construct the database queries.
-    pub fn categories(&self) -> Result<category::Getter> {
+    pub fn categories(&self) -> Result<category::Getter<'_>> {
         category::Getter::new(&self.conn)
     }
 
@@ -146,7 +207,7 @@ impl Reader {
     /// # Errors
     ///
     /// Errors if we can't construct the database queries.
-    pub fn runs(&self) -> Result<run::Getter> {
+    pub fn runs(&self) -> Result<run::Getter<'_>> {
         run::Getter::new(&self.conn)
     }
 
@@ -155,22 +216,26 @@ impl Reader {
     /// # Errors
     ///
     /// Errors if we can't construct the database queries.
-    pub fn comparison(&self) -> Result<comparison::Getter> {
+    pub fn comparison(&self) -> Result<comparison::Getter<'_>> {
         comparison::Getter::new(&self.conn)
     }
 
+    /// Gets all of the low-level getters at once.
+    ///
+    /// # Errors
+    ///
+    /// Errors if we can't construct the database queries.
+    pub fn getters(&self) -> Result<Getters<'_>> {
+        Getters::new(&self.conn)
+    }
+
     /// Gets an inspector over the database.
     ///
     /// # Errors
     ///
-    /// Errors if we can't construct the low-level getters.
-    pub fn inspect(&self, loc: &impl Locator) -> Result<Inspector> {
-        let mut cat = self.categories()?;
-        Ok(Inspector {
-            info: loc.locate(&mut cat)?,
-            run: self.runs()?,
-            cat,
-            comparison: self.comparison()?,
-        })
+    /// Errors if we can't construct the low-level getters, or `loc` doesn't locate a
+    /// game-category.
+    pub fn inspect(&self, loc: &impl Locator) -> Result<Inspector<Getters<'_>>> {
+        Inspector::new(self.getters()?, loc)
     }
 }
@@ -3,5 +3,5 @@
 pub mod get;
 pub mod id;
 
-pub use get::Getter;
+pub use get::{Getter, Source};
 pub use id::{GcID, Locator};
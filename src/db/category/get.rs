@@ -1,21 +1,18 @@
 //! SQL operations for getting category information.
 
-use super::{
-    super::error::Result,
-    id::{InfoWithID, Locator},
-    GcID,
-};
+use super::{super::error::Result, id::InfoWithID, GcID};
 use rusqlite::named_params;
 
 use crate::model::{
     game::{
         category::{AttemptInfo, ShortDescriptor, Target},
-        Split,
+        Segment, Split,
     },
     session, short,
 };
 
 /// Object for getting category information from the database.
+#[allow(clippy::struct_field_names)] // each field is a prepared query
 pub struct Getter<'conn> {
     query_info_all: rusqlite::Statement<'conn>,
     query_attempt_info: rusqlite::Statement<'conn>,
@@ -24,9 +21,60 @@ pub struct Getter<'conn> {
     query_splits: rusqlite::Statement<'conn>,
 }
 
-impl<'conn> AsMut<Getter<'conn>> for Getter<'conn> {
-    fn as_mut(&mut self) -> &mut Getter<'conn> {
-        self
+/// Trait of things from which we can get category information.
+///
+/// [Getter] gets this information from the database; other sources, such as
+/// [`memory::Store`](super::super::memory::Store), can stand in for the database in tests.
+pub trait Source {
+    /// Gets information records for all game-category pairs in the source.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source.
+    fn all_game_category_info(&mut self) -> Result<Vec<Target>>;
+
+    /// Resolves a short descriptor `short` to a category info record.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source, including the category not being found.
+    fn info_from_short(&mut self, short: &ShortDescriptor) -> Result<InfoWithID>;
+
+    /// Resolves a game-category ID `gcid` to a category info record.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source, including the category not being found.
+    fn info_from_id(&mut self, gcid: GcID) -> Result<InfoWithID>;
+
+    /// Gets attempt information for the game-category `gcid`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source.
+    fn attempt_info(&mut self, gcid: GcID) -> Result<AttemptInfo>;
+
+    /// Gets split information for the game-category `gcid`.
+    ///
+    /// The splits are grouped into their segments.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source.
+    fn splits(&mut self, gcid: GcID) -> Result<session::split::Set>;
+
+    /// Constructs an initial attempt for the category described by `info`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source.
+    fn attempt(&mut self, info: &InfoWithID) -> Result<session::Attempt> {
+        let splits = self.splits(info.id)?.with_frame_rate(info.info.frame_rate);
+        Ok(session::Attempt {
+            category: info.info.clone(),
+            info: self.attempt_info(info.id)?,
+            splits,
+        })
     }
 }
 
@@ -40,13 +88,10 @@ impl<'conn> Getter<'conn> {
             query_splits: conn.prepare(SQL_SPLITS)?,
         })
     }
+}
 
-    /// Gets information records for all game-category pairs on the database.
-    ///
-    /// # Errors
-    ///
-    /// Propagates any errors from the database.
-    pub fn all_game_category_info(&mut self) -> Result<Vec<Target>> {
+impl Source for Getter<'_> {
+    fn all_game_category_info(&mut self) -> Result<Vec<Target>> {
         self.query_info_all
             .query_and_then([], |row| {
                 let g_short: short::Name = row.get("gshort")?;
@@ -55,17 +100,13 @@ impl<'conn> Getter<'conn> {
                     game: row.get("gname")?,
                     category: row.get("cname")?,
                     short: ShortDescriptor::new(g_short, c_short),
+                    frame_rate: row.get("frame_rate")?,
                 })
             })?
             .collect()
     }
 
-    /// Resolves a short descriptor `short` to a category info record.
-    ///
-    /// # Errors
-    ///
-    /// Propagates any errors from the database.
-    pub fn info_from_short(&mut self, short: &ShortDescriptor) -> Result<InfoWithID> {
+    fn info_from_short(&mut self, short: &ShortDescriptor) -> Result<InfoWithID> {
         Ok(self.query_info_by_short.query_row(
             named_params![":game": short.game, ":category": short.category],
             |row| {
@@ -75,18 +116,14 @@ impl<'conn> Getter<'conn> {
                         game: row.get("gname")?,
                         category: row.get("cname")?,
                         short: *short,
+                        frame_rate: row.get("frame_rate")?,
                     },
                 })
             },
         )?)
     }
 
-    /// Resolves a game-category ID `gcid` to a category info record.
-    ///
-    /// # Errors
-    ///
-    /// Propagates any errors from the database.
-    pub fn info_from_id(&mut self, gcid: GcID) -> Result<InfoWithID> {
+    fn info_from_id(&mut self, gcid: GcID) -> Result<InfoWithID> {
         Ok(self
             .query_info_by_id
             .query_row(named_params![":game_category": gcid], |row| {
@@ -99,31 +136,13 @@ impl<'conn> Getter<'conn> {
                             game: row.get("gshort")?,
                             category: row.get("cshort")?,
                         },
+                        frame_rate: row.get("frame_rate")?,
                     },
                 })
             })?)
     }
 
-    /// Constructs an initial attempt for the category located by `locator`.
-    ///
-    /// # Errors
-    ///
-    /// Propagates any errors from the database.
-    pub fn run<L: Locator>(&mut self, locator: &L) -> Result<session::Attempt> {
-        Ok(session::Attempt {
-            category: locator.locate(self)?.info,
-            info: self.attempt_info(locator)?,
-            splits: self.splits(locator)?,
-        })
-    }
-
-    /// Gets attempt information for a game/category located by `locator`.
-    ///
-    /// # Errors
-    ///
-    /// Propagates any errors from the database.
-    pub fn attempt_info<L: Locator>(&mut self, locator: &L) -> Result<AttemptInfo> {
-        let game_category = locator.locate_gcid(self)?;
+    fn attempt_info(&mut self, game_category: GcID) -> Result<AttemptInfo> {
         Ok(self.query_attempt_info.query_row(
             named_params![":game_category": game_category],
             |row| {
@@ -135,56 +154,74 @@ impl<'conn> Getter<'conn> {
         )?)
     }
 
-    /// Gets split information for a game/category located by `locator`.
-    ///
-    /// Splits are currently returned as a flat vector.  This may change
-    /// eventually, to support segment display.
-    ///
-    /// # Errors
-    ///
-    /// Propagates any errors from the database.
-    pub fn splits<L: Locator>(&mut self, locator: &L) -> Result<session::split::Set> {
-        let game_category = locator.locate_gcid(self)?;
-        // TODO(@MattWindsor91): get the segments too.
-        self.query_splits
+    fn splits(&mut self, game_category: GcID) -> Result<session::split::Set> {
+        let rows: Vec<(Segment, Split)> = self
+            .query_splits
             .query_and_then(named_params![":game_category": game_category], |row| {
-                Ok(Split::new(
+                let segment = Segment::new(
+                    row.get::<_, short::Name>("segshort")?,
+                    &row.get::<_, String>("segname")?,
+                );
+                let split = Split::new(
                     row.get::<_, short::Name>("sshort")?,
                     &row.get::<_, String>("sname")?,
-                ))
+                );
+                Ok((segment, split))
             })?
-            .collect()
+            .collect::<Result<_>>()?;
+
+        // Rows come out in segment order, so each segment's splits are consecutive.
+        let mut segments: Vec<(Segment, usize)> = Vec::new();
+        for (segment, _) in &rows {
+            match segments.last_mut() {
+                Some((last, len)) if last.short == segment.short => *len += 1,
+                _ => segments.push((segment.clone(), 1)),
+            }
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|(_, split)| split)
+            .collect::<session::split::Set>()
+            .with_segments(segments))
     }
 }
 
 const SQL_INFO_ALL: &str = "
-SELECT game.short     AS gshort
-     , game.name      AS gname
-     , category.short AS cshort
-     , category.name  AS cname
+SELECT game.short      AS gshort
+     , game.name       AS gname
+     , game.frame_rate AS frame_rate
+     , category.short  AS cshort
+     , category.name   AS cname
   FROM game
        INNER JOIN game_category USING(game_id)
        INNER JOIN category      USING(category_id)
+ WHERE category.game_id = game.game_id
  ORDER BY gshort ASC, cshort ASC;";
 
 const SQL_INFO_BY_SHORT: &str = "
 SELECT game_category_id AS gcid
      , game.name        AS gname
+     , game.frame_rate  AS frame_rate
      , category.name    AS cname
   FROM game
        INNER JOIN game_category USING(game_id)
        INNER JOIN category      USING(category_id)
- WHERE game.short = :game AND category.short = :category;";
+ WHERE game.short = :game
+   AND category.short = :category
+   AND category.game_id = game.game_id;";
 
 const SQL_INFO_BY_ID: &str = "
-SELECT game.short     AS gshort
-     , game.name      AS gname
-     , category.short AS cshort
-     , category.name  AS cname
+SELECT game.short      AS gshort
+     , game.name       AS gname
+     , game.frame_rate AS frame_rate
+     , category.short  AS cshort
+     , category.name   AS cname
   FROM game
        INNER JOIN game_category USING(game_id)
        INNER JOIN category      USING(category_id)
- WHERE game_category_id = :game_category;";
+ WHERE game_category_id = :game_category
+   AND category.game_id = game.game_id;";
 
 const SQL_ATTEMPT_INFO: &str = "
 SELECT COUNT(*)                     AS total,
@@ -193,13 +230,18 @@ SELECT COUNT(*)                     AS total,
  WHERE game_category_id = :game_category;";
 
 const SQL_SPLITS: &str = "
-SELECT split_id    AS sid
-     , split.short AS sshort
-     , split.name  AS sname
+SELECT split_id      AS sid
+     , split.short   AS sshort
+     , split.name    AS sname
+     , segment.short AS segshort
+     , segment.name  AS segname
   FROM split
        INNER JOIN segment_split    USING(split_id)
+       INNER JOIN segment          USING(segment_id)
        INNER JOIN category_segment USING(segment_id)
        INNER JOIN game_category    USING(category_id)
  WHERE game_category_id = :game_category
+   AND segment.game_id = game_category.game_id
+   AND split.game_id   = game_category.game_id
  ORDER BY category_segment.position ASC
         , segment_split.position    ASC;";
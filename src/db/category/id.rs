@@ -5,7 +5,7 @@ use rusqlite::{
     ToSql,
 };
 
-use super::{super::error::Result, Getter};
+use super::{super::error::Result, get::Source};
 use crate::model::game::category::{ShortDescriptor, Target};
 
 /// A game-category ID.
@@ -26,24 +26,24 @@ impl FromSql for GcID {
 
 /// Trait for anything that can be used to find a game-category ID.
 ///
-/// Some locators will result in database queries.
+/// Some locators will result in queries on the category source.
 pub trait Locator {
     /// Locates the game-category using this locator, potentially using
-    /// the given `getter` to resolve database queries.
+    /// the given `source` to resolve queries.
     ///
     /// # Errors
     ///
     /// Typically, errors returned will be database errors.
-    fn locate<'a>(&self, getter: &mut impl AsMut<Getter<'a>>) -> Result<InfoWithID>;
+    fn locate(&self, source: &mut impl Source) -> Result<InfoWithID>;
 
     /// Locates the game-category ID using this locator, potentially using
-    /// the given `getter` to resolve database queries.
+    /// the given `source` to resolve queries.
     ///
     /// # Errors
     ///
     /// Typically, errors returned will be database errors.
-    fn locate_gcid<'a>(&self, getter: &mut impl AsMut<Getter<'a>>) -> Result<GcID> {
-        self.locate(getter).map(|x| x.id)
+    fn locate_gcid(&self, source: &mut impl Source) -> Result<GcID> {
+        self.locate(source).map(|x| x.id)
     }
 
     /// Tries to extract a game-category ID directly from this locator.
@@ -54,11 +54,11 @@ pub trait Locator {
 
 /// Signed 64-bit integers are treated as game-category IDs natively.
 impl Locator for GcID {
-    fn locate<'a>(&self, getter: &mut impl AsMut<Getter<'a>>) -> Result<InfoWithID> {
-        getter.as_mut().info_from_id(*self)
+    fn locate(&self, source: &mut impl Source) -> Result<InfoWithID> {
+        source.info_from_id(*self)
     }
 
-    fn locate_gcid<'a>(&self, _: &mut impl AsMut<Getter<'a>>) -> Result<GcID> {
+    fn locate_gcid(&self, _: &mut impl Source) -> Result<GcID> {
         Ok(*self)
     }
 
@@ -76,11 +76,11 @@ pub struct InfoWithID {
 
 /// Category info implicitly contains a game-category ID.
 impl Locator for InfoWithID {
-    fn locate<'a>(&self, _: &mut impl AsMut<Getter<'a>>) -> Result<InfoWithID> {
+    fn locate(&self, _: &mut impl Source) -> Result<InfoWithID> {
         Ok(self.clone())
     }
 
-    fn locate_gcid<'a>(&self, _: &mut impl AsMut<Getter<'a>>) -> Result<GcID> {
+    fn locate_gcid(&self, _: &mut impl Source) -> Result<GcID> {
         Ok(self.id)
     }
 
@@ -90,7 +90,7 @@ impl Locator for InfoWithID {
 }
 
 impl Locator for ShortDescriptor {
-    fn locate<'a>(&self, getter: &mut impl AsMut<Getter<'a>>) -> Result<InfoWithID> {
-        getter.as_mut().info_from_short(self)
+    fn locate(&self, source: &mut impl Source) -> Result<InfoWithID> {
+        source.info_from_short(self)
     }
 }
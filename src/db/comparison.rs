@@ -5,6 +5,8 @@ indirectly pulling out run and category data.
 */
 
 pub mod get;
+pub mod provider;
 mod sql;
 
-pub use get::Getter;
+pub use get::{build, Getter, Source};
+pub use provider::Provider;
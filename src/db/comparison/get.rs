@@ -1,4 +1,4 @@
-//! The [Getter] struct.
+//! The [Getter] struct and the [build] function.
 
 use super::{
     super::{category, category::GcID, error::Result, run, util::WithID},
@@ -11,11 +11,14 @@ use crate::model::{
 use rusqlite::{named_params, Connection, Statement};
 
 /// Low-level interface for getting comparison data.
+#[allow(clippy::struct_field_names)] // each field is a prepared query
 pub struct Getter<'conn> {
     /// SQL query for getting the personal-best run.
     run_pb_query: Statement<'conn>,
     /// SQL query for getting split personal-bests.
     split_pbs_query: Statement<'conn>,
+    /// SQL query for getting segment personal-bests.
+    segment_pbs_query: Statement<'conn>,
     /// SQL query for getting the sum of best.
     sum_of_best_query: Statement<'conn>,
 }
@@ -30,50 +33,50 @@ impl<'conn> Getter<'conn> {
         Ok(Self {
             run_pb_query: conn.prepare(sql::RUN_PB)?,
             split_pbs_query: conn.prepare(sql::SPLIT_PBS)?,
+            segment_pbs_query: conn.prepare(sql::SEGMENT_PBS)?,
             sum_of_best_query: conn.prepare(sql::SUM_OF_BEST)?,
         })
     }
+}
 
-    /// Gets a comparison for a game-category ID.
+/// Trait of things from which we can get the personal bests that make up a comparison.
+///
+/// [Getter] gets these from the database's comparison views; other sources, such as
+/// [`memory::Store`](super::super::memory::Store), can stand in for the database in tests.
+pub trait Source {
+    /// Gets the PB run for a game-category ID, if one exists.
+    ///
+    /// # Errors
     ///
-    /// We need the category getter to pull the split ordering for the game-category (so that we can
-    /// get an ordering on the splits), and the run getter to pull details about the PB run.
+    /// Errors if the run can't be retrieved from the source.
+    fn run_pb(&mut self, id: GcID) -> Result<Option<WithID<history::run::Summary<GcID>>>>;
+
+    /// Gets PBs for each split on a given game-category ID.
     ///
     /// # Errors
     ///
-    /// Errors if the database query fails.
-    pub fn get(
-        &mut self,
-        cat_get: &mut category::Getter<'conn>,
-        run_get: &mut run::Getter<'conn>,
-        gcid: GcID,
-    ) -> Result<Comparison> {
-        let pb_summary = self.run_pb(gcid)?;
-
-        let total_in_pb_run = pb_summary.as_ref().map(|x| x.item.timing.total);
-        let sum_of_best = self.sum_of_best(gcid)?;
-        let run = comparison::Run {
-            total_in_pb_run,
-            sum_of_best,
-        };
-
-        let pb_full = pb_summary
-            .map(|x| run_get.add_split_totals(x))
-            .transpose()?;
-
-        let splits = cat_get.splits(&gcid)?;
-        Ok(Comparison {
-            splits: self.splits(gcid, &splits, pb_full)?,
-            run,
-        })
-    }
+    /// Errors if the PBs can't be retrieved from the source.
+    fn split_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>>;
 
-    /// Gets the PB run for a game-category ID, if one exists.
+    /// Gets PBs for each segment on a given game-category ID.
+    ///
+    /// A segment only has a PB if some run has times for every split in it.
     ///
     /// # Errors
     ///
-    /// Errors if the database query fails.
-    pub fn run_pb(&mut self, id: GcID) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
+    /// Errors if the PBs can't be retrieved from the source.
+    fn segment_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>>;
+
+    /// Gets the sum-of-best for a game-category ID, if one exists.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the sum can't be retrieved from the source.
+    fn sum_of_best(&mut self, id: GcID) -> Result<Option<time::Time>>;
+}
+
+impl Source for Getter<'_> {
+    fn run_pb(&mut self, id: GcID) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
         self.run_pb_query
             .query_and_then(named_params![":game_category": id], |r| {
                 WithID::from_row(id, r)
@@ -82,12 +85,7 @@ impl<'conn> Getter<'conn> {
             .transpose()
     }
 
-    /// Gets PBs for each split on a given game-category ID.
-    ///
-    /// # Errors
-    ///
-    /// Errors if the database query fails.
-    pub fn split_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
+    fn split_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
         self.split_pbs_query
             .query_and_then(named_params![":game_category": id], |row| {
                 Ok((row.get("short")?, row.get("total")?))
@@ -95,45 +93,75 @@ impl<'conn> Getter<'conn> {
             .collect()
     }
 
-    /// Gets the sum-of-best for a game-category ID, if one exists.
-    ///
-    /// # Errors
-    ///
-    /// Errors if the database query fails.
-    pub fn sum_of_best(&mut self, id: GcID) -> Result<Option<time::Time>> {
+    fn segment_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
+        self.segment_pbs_query
+            .query_and_then(named_params![":game_category": id], |row| {
+                Ok((row.get("short")?, row.get("total")?))
+            })?
+            .collect()
+    }
+
+    fn sum_of_best(&mut self, id: GcID) -> Result<Option<time::Time>> {
         Ok(self
             .sum_of_best_query
             .query_row(named_params![":game_category": id], |r| r.get("total"))?)
     }
+}
 
-    fn splits(
-        &mut self,
-        gcid: GcID,
-        splits: &session::split::Set,
-        pb_run: Option<WithID<history::run::WithTotals<GcID>>>,
-    ) -> Result<short::Map<comparison::Segment>> {
-        let split_pbs = self.split_pbs(gcid)?;
-        let run_pb_splits = pb_run.map_or_else(Default::default, |x| {
-            aggregate(splits, x.item.timing.totals)
-        });
-
-        Ok(merge_split_data(splits, &split_pbs, &run_pb_splits))
-    }
+/// Builds a comparison for the game-category `gcid` out of `source`.
+///
+/// As well as the personal bests, we need category information to get an ordering on the splits,
+/// and run information to pull details about the PB run.
+///
+/// # Errors
+///
+/// Errors if any query on `source` fails, or the PB run's aggregates overflow.
+pub fn build<S>(source: &mut S, gcid: GcID) -> Result<Comparison>
+where
+    S: category::Source + run::Source + Source + ?Sized,
+{
+    let pb_summary = source.run_pb(gcid)?;
+
+    let total_in_pb_run = pb_summary.as_ref().map(|x| x.item.timing.total);
+    let sum_of_best = source.sum_of_best(gcid)?;
+    let run = comparison::Run {
+        total_in_pb_run,
+        sum_of_best,
+    };
+
+    let pb_full = pb_summary.map(|x| source.add_split_totals(x)).transpose()?;
+
+    let splits = source.splits(gcid)?;
+    let run_pb_splits = pb_full.map_or_else(
+        || Ok(short::Map::default()),
+        |x| aggregate(&splits, x.item.timing.totals),
+    )?;
+    Ok(Comparison {
+        segments: merge_segment_data(&splits, &source.segment_pbs(gcid)?, &run_pb_splits),
+        splits: merge_split_data(&splits, &source.split_pbs(gcid)?, &run_pb_splits),
+        run,
+    })
 }
 
 /// Lifts a split time map to one over aggregates by summing across the splits in `split`.
+///
+/// Fails if the aggregates overflow.
 fn aggregate(
     splits: &session::split::Set,
     totals: short::Map<time::Time>,
-) -> short::Map<aggregate::Set> {
+) -> Result<short::Map<aggregate::Set>> {
     // TODO(@MattWindsor91): decouple this for testing.
-    aggregate::Set::accumulate_pairs(splits.iter().map(move |s| {
-        (
-            s.info.short,
-            totals.get(&s.info.short).copied().unwrap_or_default(),
-        )
-    }))
-    .collect()
+    let aggregates = aggregate::Set::accumulate_pairs_at(
+        splits.iter().map(move |s| {
+            (
+                s.info.short,
+                totals.get(&s.info.short).copied().unwrap_or_default(),
+            )
+        }),
+        splits.frame_rate(),
+    )
+    .collect::<time::Result<_>>()?;
+    Ok(aggregates)
 }
 
 fn merge_split_data(
@@ -157,3 +185,26 @@ fn merge_split_data(
         })
         .collect()
 }
+
+fn merge_segment_data(
+    splits: &session::split::Set,
+    segment_pbs: &short::Map<time::Time>,
+    run_pb_splits: &short::Map<aggregate::Set>,
+) -> short::Map<comparison::Segment> {
+    splits
+        .segments()
+        .iter()
+        .filter_map(|x| {
+            x.aggregate(splits, |s| run_pb_splits.get(&s).copied())
+                .map(|in_run| {
+                    (
+                        x.info.short,
+                        comparison::Segment {
+                            split_pb: segment_pbs.get(&x.info.short).copied().unwrap_or_default(),
+                            in_pb_run: in_run,
+                        },
+                    )
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+//! The [Provider] struct.
+
+use super::super::{category::GcID, Db, Result};
+use crate::model::timing::{comparison::provider, Comparison};
+
+/// A comparison provider that reads comparisons for one game-category out of the database.
+///
+/// Unlike [`Inspector`](super::super::inspect::Inspector), which borrows a single connection, this provider
+/// holds its own handle to the database, takes a pooled connection each time it is asked for a
+/// comparison, and can be moved between threads.
+#[derive(Clone)]
+pub struct Provider {
+    /// A handle to the database.
+    db: Db,
+    /// The game-category whose comparisons are being provided.
+    id: GcID,
+}
+
+impl Provider {
+    /// Constructs a provider for comparisons on game-category `id` in `db`.
+    #[must_use]
+    pub fn new(db: Db, id: GcID) -> Self {
+        Self { db, id }
+    }
+
+    /// Gets the comparison for this provider's game-category.
+    ///
+    /// # Errors
+    ///
+    /// Errors if we can't get a connection, or the database query fails.
+    pub fn get(&self) -> Result<Comparison> {
+        let reader = self.db.reader()?;
+        let mut getters = reader.getters()?;
+        super::build(&mut getters, self.id)
+    }
+}
+
+impl provider::Provider for Provider {
+    fn comparison(&mut self) -> provider::Result {
+        Ok(Some(self.get().map_err(anyhow::Error::from)?))
+    }
+}
@@ -31,6 +31,31 @@ SELECT s.short AS short, total
  GROUP BY split_id, cs.position, ss.position
  ORDER BY cs.position, ss.position;";
 
+/// SQL for getting a segment PB set for a game category.
+///
+/// A segment PB is the smallest total, across all runs, of the split totals in a segment.  Only runs
+/// with times for every split in the segment count.
+pub(super) const SEGMENT_PBS: &str = "
+SELECT segment.short            AS short
+     , MIN(run_segment.total)   AS total
+  FROM (SELECT ss.segment_id      AS segment_id
+             , SUM(rst.total)     AS total
+             , COUNT(*)           AS num_splits
+          FROM run
+               INNER JOIN run_split        AS rs  ON rs.run_id = run.run_id
+               INNER JOIN run_split_total  AS rst ON rst.run_split_id = rs.run_split_id
+               INNER JOIN segment_split    AS ss  ON ss.split_id = rs.split_id
+               INNER JOIN game_category    AS gc  ON gc.game_category_id = run.game_category_id
+               INNER JOIN category_segment AS cs  ON cs.category_id = gc.category_id
+                                                 AND cs.segment_id = ss.segment_id
+         WHERE run.game_category_id = :game_category
+         GROUP BY run.run_id, ss.segment_id) AS run_segment
+       INNER JOIN segment USING (segment_id)
+ WHERE run_segment.num_splits = (SELECT COUNT(*)
+                                   FROM segment_split
+                                  WHERE segment_split.segment_id = run_segment.segment_id)
+ GROUP BY segment_id;";
+
 /// SQL for getting the sum of best for a game category.
 pub(super) const SUM_OF_BEST: &str = "
 SELECT SUM(total) AS total
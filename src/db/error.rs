@@ -7,10 +7,30 @@ use crate::model::short;
 /// Database errors.
 #[derive(Debug, Error)]
 pub enum Error {
-    /// A wrapped SQLite error.
+    /// A wrapped `SQLite` error.
     #[error("sqlite error: {0}")]
     SQLite(#[from] rusqlite::Error),
 
+    /// No pooled connection could be acquired.
+    #[error("connection pool error: {0}")]
+    Pool(#[from] r2d2::Error),
+
+    /// A game's configuration couldn't be resolved.
+    #[error("game config error: {0}")]
+    Config(#[from] crate::model::game::config::Error),
+
+    /// A game to update wasn't in the database.
+    #[error("couldn't find game {short}")]
+    MissingGame { short: String },
+
+    /// Updating a game would leave stored run times unreachable, and this wasn't allowed.
+    #[error("updating game {short} would orphan stored runs: {}", changes.join("; "))]
+    DestructiveUpdate { short: String, changes: Vec<String> },
+
+    /// A game-category wasn't in an in-memory store.
+    #[error("couldn't find game-category {0}")]
+    MissingCategory(String),
+
     /// A category referenced a segment not inserted in the database yet.
     #[error("couldn't find segment {short} requested by category {in_category}")]
     MissingSegment {
@@ -32,6 +52,10 @@ pub enum Error {
     #[error("bad run timestamp: {0}")]
     BadRunTimestamp(i64),
 
+    /// Times stored in the database overflowed when combined.
+    #[error("time error: {0}")]
+    Time(#[from] crate::model::timing::time::Error),
+
     #[error("couldn't get lock on connection")]
     Lock,
 }
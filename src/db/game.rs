@@ -1,5 +1,7 @@
 //! Database functionality for loading and storing games.
 
+pub mod update;
+
 use std::collections::HashMap;
 
 use rusqlite::{named_params, Transaction};
@@ -21,10 +23,14 @@ pub(super) struct Inserter<'conn, 'tx> {
     queries: HashMap<Query, rusqlite::Statement<'tx>>,
 }
 
-const SQL_GAME: &str = "INSERT INTO game (short, name) VALUES (:short, :name);";
-const SQL_CATEGORY: &str = "INSERT INTO category (short, name) VALUES (:short, :name);";
-const SQL_SEGMENT: &str = "INSERT INTO segment (short, name) VALUES (:short, :name);";
-const SQL_SPLIT: &str = "INSERT INTO split (short, name) VALUES (:short, :name);";
+const SQL_GAME: &str =
+    "INSERT INTO game (short, name, frame_rate) VALUES (:short, :name, :frame_rate);";
+const SQL_CATEGORY: &str =
+    "INSERT INTO category (game_id, short, name) VALUES (:game_id, :short, :name);";
+const SQL_SEGMENT: &str =
+    "INSERT INTO segment (game_id, short, name) VALUES (:game_id, :short, :name);";
+const SQL_SPLIT: &str =
+    "INSERT INTO split (game_id, short, name) VALUES (:game_id, :short, :name);";
 const SQL_GAME_CATEGORY: &str =
     "INSERT INTO game_category (game_id, category_id) VALUES (:game_id, :category_id);";
 const SQL_CATEGORY_SEGMENT: &str = "INSERT INTO category_segment (category_id, segment_id, position) VALUES (:category_id, :segment_id, :position);";
@@ -100,8 +106,11 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
     fn add_main(&mut self, short: &str, game: &game::Config) -> Result<()> {
         log::info!("adding game {short}");
 
-        self.query(Query::Game)
-            .execute(named_params![":short": short, ":name": game.name])?;
+        self.query(Query::Game).execute(named_params![
+            ":short": short,
+            ":name": game.name,
+            ":frame_rate": game.frame_rate
+        ])?;
         self.game_id = self.tx.last_insert_rowid();
 
         log::info!("game {short} -> ID {}", self.game_id);
@@ -118,8 +127,9 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
     fn add_split(&mut self, short: short::Name, split: &game::config::Split) -> Result<()> {
         log::info!("adding split {short} ('{}')", split.name);
 
+        let game_id = self.game_id;
         self.query(Query::Split)
-            .execute(named_params![":short": short, ":name": split.name])?;
+            .execute(named_params![":game_id": game_id, ":short": short, ":name": split.name])?;
 
         let split_id = self.tx.last_insert_rowid();
         log::info!("split {short} -> ID {split_id}");
@@ -146,8 +156,9 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         segment: &game::config::Segment,
     ) -> Result<i64> {
         log::info!("adding segment {short} ('{}')", segment.name);
+        let game_id = self.game_id;
         self.query(Query::Segment)
-            .execute(named_params![":short": short, ":name": segment.name])?;
+            .execute(named_params![":game_id": game_id, ":short": short, ":name": segment.name])?;
 
         let segment_id = self.tx.last_insert_rowid();
         log::info!("segment {short} -> ID {segment_id}");
@@ -190,7 +201,7 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
 
     fn add_categories(&mut self, game: &game::Config) -> Result<()> {
         for (short, category) in &game.categories {
-            self.add_category(*short, category)?;
+            self.add_category(*short, category, game)?;
         }
 
         Ok(())
@@ -200,10 +211,11 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         &mut self,
         short: short::Name,
         category: &game::config::Category,
+        game: &game::Config,
     ) -> Result<()> {
         let category_id = self.add_category_main(short, category)?;
         self.add_category_to_game(category_id)?;
-        self.add_segments_to_category(category_id, category)
+        self.add_segments_to_category(category_id, category, game)
     }
 
     fn add_category_main(
@@ -212,8 +224,9 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         category: &game::config::Category,
     ) -> Result<i64> {
         log::info!("adding category {} for game ID {}", short, self.game_id);
+        let game_id = self.game_id;
         self.query(Query::Category)
-            .execute(named_params![":short": short, ":name": category.name])?;
+            .execute(named_params![":game_id": game_id, ":short": short, ":name": category.name])?;
 
         let categoryid = self.tx.last_insert_rowid();
         log::info!("category {short} -> ID {categoryid}");
@@ -233,8 +246,13 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         &mut self,
         category_id: i64,
         category: &game::config::Category,
+        game: &game::Config,
     ) -> Result<()> {
-        for (position, segment_id) in self.category_segment_ids(category)?.iter().enumerate() {
+        for (position, segment_id) in self
+            .category_segment_ids(category, game)?
+            .iter()
+            .enumerate()
+        {
             log::info!(
                 "adding segment ID {segment_id} for category {}",
                 category.name
@@ -248,9 +266,13 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         Ok(())
     }
 
-    fn category_segment_ids(&self, category: &game::config::Category) -> Result<Vec<i64>> {
+    fn category_segment_ids(
+        &self,
+        category: &game::config::Category,
+        game: &game::Config,
+    ) -> Result<Vec<i64>> {
         category
-            .segments
+            .segment_names(game)?
             .iter()
             .map(|short| {
                 self.segment_ids
@@ -0,0 +1,600 @@
+//! Updating games already stored in the database to match new configuration.
+//!
+//! Updating adds any new splits, segments, and categories, renames anything whose display name
+//! changed, and restructures segments and categories whose contents changed.  Stored runs refer
+//! to splits and game-categories, so anything that takes splits away from a category can leave
+//! stored times unreachable; such changes are reported, and only made on request.
+
+use std::{collections::HashSet, fmt};
+
+use rusqlite::{named_params, OptionalExtension, Transaction};
+
+use super::{
+    super::error::{Error, Result},
+    Inserter,
+};
+use crate::model::{game, short, timing::time::frame};
+
+/// The kinds of stored game component that an update can change.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// The game itself.
+    Game,
+    /// A category of the game.
+    Category,
+    /// A segment of one or more categories.
+    Segment,
+    /// A split of one or more segments.
+    Split,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Game => "game",
+            Self::Category => "category",
+            Self::Segment => "segment",
+            Self::Split => "split",
+        })
+    }
+}
+
+/// A change made, or to be made, to a stored game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A category, segment, or split was added.
+    Add { kind: Kind, short: short::Name },
+    /// The display name of the game, or of one of its components, changed.
+    Rename {
+        kind: Kind,
+        short: short::Name,
+        from: String,
+        to: String,
+    },
+    /// The frame rate of the game changed.
+    FrameRate {
+        from: Option<frame::Rate>,
+        to: Option<frame::Rate>,
+    },
+    /// The segments of a category, or the splits of a segment, changed or were reordered.
+    Restructure { kind: Kind, short: short::Name },
+    /// A category lost a split, or (if `split` is `None`) was removed from the game.
+    ///
+    /// `runs` counts the stored runs of the category with times that the change leaves
+    /// unreachable.
+    Drop {
+        category: short::Name,
+        split: Option<short::Name>,
+        runs: usize,
+    },
+}
+
+impl Change {
+    /// Gets whether this change would leave stored run times unreachable.
+    #[must_use]
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Self::Drop { runs, .. } if 0 < *runs)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add { kind, short } => write!(f, "add {kind} {short}"),
+            Self::Rename {
+                kind,
+                short,
+                from,
+                to,
+            } => write!(f, "rename {kind} {short} from '{from}' to '{to}'"),
+            Self::FrameRate { from, to } => write!(
+                f,
+                "change frame rate from {} to {}",
+                rate_or_none(*from),
+                rate_or_none(*to)
+            ),
+            Self::Restructure { kind, short } => write!(f, "restructure {kind} {short}"),
+            Self::Drop {
+                category,
+                split: Some(split),
+                runs,
+            } => write!(
+                f,
+                "drop split {split} from category {category} ({runs} run(s) affected)"
+            ),
+            Self::Drop {
+                category,
+                split: None,
+                runs,
+            } => write!(f, "remove category {category} ({runs} run(s) affected)"),
+        }
+    }
+}
+
+fn rate_or_none(rate: Option<frame::Rate>) -> String {
+    rate.map_or_else(|| "none".to_owned(), |r| r.to_string())
+}
+
+/// The set of changes an update makes to a stored game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// The changes, in the order in which they are applied.
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    /// Gets whether the update changes nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Iterates over the changes that would leave stored run times unreachable.
+    pub fn destructive(&self) -> impl Iterator<Item = &Change> + '_ {
+        self.changes.iter().filter(|c| c.is_destructive())
+    }
+
+    /// Gets whether any change would leave stored run times unreachable.
+    #[must_use]
+    pub fn is_destructive(&self) -> bool {
+        self.destructive().next().is_some()
+    }
+}
+
+/// A component of a stored game with a primary key and display name.
+struct Named {
+    id: i64,
+    name: String,
+}
+
+/// A category as stored in the database.
+struct StoredCategory {
+    info: Named,
+    game_category_id: i64,
+    segments: Vec<short::Name>,
+}
+
+/// A segment as stored in the database.
+struct StoredSegment {
+    info: Named,
+    splits: Vec<short::Name>,
+}
+
+/// A game as stored in the database.
+struct Stored {
+    name: String,
+    frame_rate: Option<frame::Rate>,
+    categories: short::Map<StoredCategory>,
+    segments: short::Map<StoredSegment>,
+    splits: short::Map<Named>,
+}
+
+impl Stored {
+    /// Gets every split in the stored category `category`, in order.
+    fn category_splits(&self, category: &StoredCategory) -> Vec<short::Name> {
+        category
+            .segments
+            .iter()
+            .filter_map(|s| self.segments.get(s))
+            .flat_map(|s| s.splits.iter().copied())
+            .collect()
+    }
+}
+
+/// Updates a stored game to match its configuration.
+pub(in super::super) struct Updater<'conn, 'tx> {
+    /// Used to insert new components, primed with the IDs of the stored ones.
+    inserter: Inserter<'conn, 'tx>,
+    /// The game's short name.
+    short: String,
+    /// The game as stored before the update.
+    stored: Stored,
+}
+
+impl<'conn, 'tx> Updater<'conn, 'tx> {
+    /// Constructs an updater for the stored game `short`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the game isn't in the database, or reading it fails.
+    pub(in super::super) fn new(tx: &'tx Transaction<'conn>, short: &str) -> Result<Self> {
+        let mut inserter = Inserter::new(tx)?;
+        let (game_id, name, frame_rate) = tx
+            .query_row(SQL_GAME, named_params![":short": short], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .optional()?
+            .ok_or_else(|| Error::MissingGame {
+                short: short.to_owned(),
+            })?;
+        inserter.game_id = game_id;
+
+        let mut stored = Stored {
+            name,
+            frame_rate,
+            categories: load_categories(tx, game_id)?,
+            segments: load_segments(tx, game_id)?,
+            splits: load_splits(tx, game_id)?,
+        };
+        for category in stored.categories.values_mut() {
+            category.segments = members(tx, SQL_CATEGORY_SEGMENTS, category.info.id)?;
+        }
+        for segment in stored.segments.values_mut() {
+            segment.splits = members(tx, SQL_SEGMENT_SPLITS, segment.info.id)?;
+        }
+
+        inserter
+            .split_ids
+            .extend(stored.splits.iter().map(|(k, v)| (*k, v.id)));
+        inserter
+            .segment_ids
+            .extend(stored.segments.iter().map(|(k, v)| (*k, v.info.id)));
+
+        Ok(Self {
+            inserter,
+            short: short.to_owned(),
+            stored,
+        })
+    }
+
+    /// Works out the changes needed to bring the stored game in line with `game`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `game` doesn't resolve, or counting affected runs fails.
+    pub(in super::super) fn plan(&self, game: &game::Config) -> Result<Plan> {
+        let mut changes = Vec::new();
+        let stored = &self.stored;
+
+        if stored.name != game.name {
+            changes.push(Change::Rename {
+                kind: Kind::Game,
+                short: short::Name::from(self.short.as_str()),
+                from: stored.name.clone(),
+                to: game.name.clone(),
+            });
+        }
+        if stored.frame_rate != game.frame_rate {
+            changes.push(Change::FrameRate {
+                from: stored.frame_rate,
+                to: game.frame_rate,
+            });
+        }
+
+        for (short, split) in &game.splits {
+            changes.extend(compare(
+                Kind::Split,
+                *short,
+                &split.name,
+                stored.splits.get(short),
+            ));
+        }
+        for (short, segment) in &game.segments {
+            let old = stored.segments.get(short);
+            changes.extend(compare(
+                Kind::Segment,
+                *short,
+                &segment.name,
+                old.map(|o| &o.info),
+            ));
+            if old.is_some_and(|old| old.splits != segment.splits) {
+                changes.push(Change::Restructure {
+                    kind: Kind::Segment,
+                    short: *short,
+                });
+            }
+        }
+        for (short, category) in &game.categories {
+            let old = stored.categories.get(short);
+            changes.extend(compare(
+                Kind::Category,
+                *short,
+                &category.name,
+                old.map(|o| &o.info),
+            ));
+            if let Some(old) = old {
+                changes.extend(self.plan_category(game, *short, category, old)?);
+            }
+        }
+        for (short, old) in &stored.categories {
+            if !game.categories.contains_key(short) {
+                changes.push(Change::Drop {
+                    category: *short,
+                    split: None,
+                    runs: self.count(SQL_CATEGORY_RUNS, old.game_category_id, None)?,
+                });
+            }
+        }
+
+        Ok(Plan { changes })
+    }
+
+    fn plan_category(
+        &self,
+        game: &game::Config,
+        short: short::Name,
+        category: &game::config::Category,
+        old: &StoredCategory,
+    ) -> Result<Vec<Change>> {
+        let mut changes = Vec::new();
+
+        let segments = category.segment_names(game)?;
+        if segments != old.segments {
+            changes.push(Change::Restructure {
+                kind: Kind::Category,
+                short,
+            });
+        }
+
+        let splits: HashSet<short::Name> = category
+            .full_segments(game)?
+            .into_iter()
+            .flat_map(|(_, s)| s.splits.iter().copied())
+            .collect();
+        for split in self.stored.category_splits(old) {
+            if splits.contains(&split) {
+                continue;
+            }
+            let split_id = self.stored.splits.get(&split).map(|s| s.id);
+            changes.push(Change::Drop {
+                category: short,
+                split: Some(split),
+                runs: self.count(SQL_SPLIT_RUNS, old.game_category_id, split_id)?,
+            });
+        }
+
+        Ok(changes)
+    }
+
+    fn count(&self, sql: &str, game_category_id: i64, split_id: Option<i64>) -> Result<usize> {
+        let mut query = self.inserter.tx.prepare_cached(sql)?;
+        let count: i64 = match split_id {
+            Some(split_id) => query.query_row(
+                named_params![":game_category_id": game_category_id, ":split_id": split_id],
+                |row| row.get(0),
+            )?,
+            None => query.query_row(
+                named_params![":game_category_id": game_category_id],
+                |row| row.get(0),
+            )?,
+        };
+        // Counts from SQLite are never negative.
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
+    /// Updates the stored game to match `game`, returning the changes made.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any database operation fails, or if the update is destructive and
+    /// `allow_destructive` is false; in the latter case, nothing is changed.
+    pub(in super::super) fn update(
+        &mut self,
+        game: &game::Config,
+        allow_destructive: bool,
+    ) -> Result<Plan> {
+        let plan = self.plan(game)?;
+        if !allow_destructive && plan.is_destructive() {
+            return Err(Error::DestructiveUpdate {
+                short: self.short.clone(),
+                changes: plan.destructive().map(ToString::to_string).collect(),
+            });
+        }
+
+        self.update_main(game)?;
+        self.update_splits(game)?;
+        self.update_segments(game)?;
+        self.update_categories(game)?;
+
+        Ok(plan)
+    }
+
+    fn update_main(&mut self, game: &game::Config) -> Result<()> {
+        log::info!("updating game {}", self.short);
+        self.inserter.tx.execute(
+            SQL_UPDATE_GAME,
+            named_params![
+                ":game_id": self.inserter.game_id,
+                ":name": game.name,
+                ":frame_rate": game.frame_rate
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn update_splits(&mut self, game: &game::Config) -> Result<()> {
+        for (short, split) in &game.splits {
+            match self.stored.splits.get(short) {
+                Some(old) => rename(self.inserter.tx, SQL_RENAME_SPLIT, old, &split.name)?,
+                None => self.inserter.add_split(*short, split)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn update_segments(&mut self, game: &game::Config) -> Result<()> {
+        for (short, segment) in &game.segments {
+            match self.stored.segments.get(short) {
+                Some(old) => {
+                    rename(
+                        self.inserter.tx,
+                        SQL_RENAME_SEGMENT,
+                        &old.info,
+                        &segment.name,
+                    )?;
+                    if old.splits != segment.splits {
+                        let id = old.info.id;
+                        self.inserter
+                            .tx
+                            .execute(SQL_CLEAR_SEGMENT, named_params![":segment_id": id])?;
+                        self.inserter.add_splits_to_segment(id, segment)?;
+                    }
+                }
+                None => self.inserter.add_segment(*short, segment)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn update_categories(&mut self, game: &game::Config) -> Result<()> {
+        for (short, category) in &game.categories {
+            match self.stored.categories.get(short) {
+                Some(old) => {
+                    let id = old.info.id;
+                    rename(
+                        self.inserter.tx,
+                        SQL_RENAME_CATEGORY,
+                        &old.info,
+                        &category.name,
+                    )?;
+                    if category.segment_names(game)? != old.segments {
+                        self.inserter
+                            .tx
+                            .execute(SQL_CLEAR_CATEGORY, named_params![":category_id": id])?;
+                        self.inserter.add_segments_to_category(id, category, game)?;
+                    }
+                }
+                None => self.inserter.add_category(*short, category, game)?,
+            }
+        }
+
+        for (short, old) in &self.stored.categories {
+            if !game.categories.contains_key(short) {
+                log::info!("removing category {short} from game {}", self.short);
+                self.inserter.tx.execute(
+                    SQL_CLEAR_CATEGORY,
+                    named_params![":category_id": old.info.id],
+                )?;
+                self.inserter.tx.execute(
+                    SQL_REMOVE_GAME_CATEGORY,
+                    named_params![":game_category_id": old.game_category_id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares a configured component with its stored version, if any.
+fn compare(kind: Kind, short: short::Name, name: &str, old: Option<&Named>) -> Option<Change> {
+    match old {
+        None => Some(Change::Add { kind, short }),
+        Some(old) if old.name != name => Some(Change::Rename {
+            kind,
+            short,
+            from: old.name.clone(),
+            to: name.to_owned(),
+        }),
+        Some(_) => None,
+    }
+}
+
+fn rename(tx: &Transaction, sql: &str, old: &Named, name: &str) -> Result<()> {
+    if old.name != name {
+        tx.execute(sql, named_params![":id": old.id, ":name": name])?;
+    }
+    Ok(())
+}
+
+fn load_categories(tx: &Transaction, game_id: i64) -> Result<short::Map<StoredCategory>> {
+    let mut query = tx.prepare(SQL_CATEGORIES)?;
+    let rows = query.query_and_then(named_params![":game_id": game_id], |row| {
+        Ok((
+            row.get("short")?,
+            StoredCategory {
+                info: Named {
+                    id: row.get("category_id")?,
+                    name: row.get("name")?,
+                },
+                game_category_id: row.get("game_category_id")?,
+                segments: Vec::new(),
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+fn load_segments(tx: &Transaction, game_id: i64) -> Result<short::Map<StoredSegment>> {
+    let mut query = tx.prepare(SQL_SEGMENTS)?;
+    let rows = query.query_and_then(named_params![":game_id": game_id], |row| {
+        Ok((
+            row.get("short")?,
+            StoredSegment {
+                info: Named {
+                    id: row.get("segment_id")?,
+                    name: row.get("name")?,
+                },
+                splits: Vec::new(),
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+fn load_splits(tx: &Transaction, game_id: i64) -> Result<short::Map<Named>> {
+    let mut query = tx.prepare(SQL_SPLITS)?;
+    let rows = query.query_and_then(named_params![":game_id": game_id], |row| {
+        Ok((
+            row.get("short")?,
+            Named {
+                id: row.get("split_id")?,
+                name: row.get("name")?,
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+/// Gets the short names of the members of the category or segment with ID `id`, in order.
+fn members(tx: &Transaction, sql: &str, id: i64) -> Result<Vec<short::Name>> {
+    let mut query = tx.prepare_cached(sql)?;
+    let rows = query.query_and_then(named_params![":id": id], |row| Ok(row.get(0)?))?;
+    rows.collect()
+}
+
+const SQL_GAME: &str = "SELECT game_id, name, frame_rate FROM game WHERE short = :short;";
+
+const SQL_CATEGORIES: &str = "
+    SELECT category_id, game_category_id, short, name
+      FROM game_category
+           INNER JOIN category USING (category_id)
+     WHERE game_category.game_id = :game_id
+       AND category.game_id = :game_id;";
+
+const SQL_SEGMENTS: &str = "SELECT segment_id, short, name FROM segment WHERE game_id = :game_id;";
+
+const SQL_SPLITS: &str = "SELECT split_id, short, name FROM split WHERE game_id = :game_id;";
+
+const SQL_CATEGORY_SEGMENTS: &str = "
+    SELECT segment.short
+      FROM category_segment
+           INNER JOIN segment USING (segment_id)
+     WHERE category_id = :id
+     ORDER BY position ASC;";
+
+const SQL_SEGMENT_SPLITS: &str = "
+    SELECT split.short
+      FROM segment_split
+           INNER JOIN split USING (split_id)
+     WHERE segment_id = :id
+     ORDER BY position ASC;";
+
+const SQL_CATEGORY_RUNS: &str =
+    "SELECT COUNT(*) FROM run WHERE game_category_id = :game_category_id;";
+
+const SQL_SPLIT_RUNS: &str = "
+    SELECT COUNT(DISTINCT run_id)
+      FROM run
+           INNER JOIN run_split USING (run_id)
+     WHERE game_category_id = :game_category_id
+       AND split_id = :split_id;";
+
+const SQL_UPDATE_GAME: &str =
+    "UPDATE game SET name = :name, frame_rate = :frame_rate WHERE game_id = :game_id;";
+const SQL_RENAME_CATEGORY: &str = "UPDATE category SET name = :name WHERE category_id = :id;";
+const SQL_RENAME_SEGMENT: &str = "UPDATE segment SET name = :name WHERE segment_id = :id;";
+const SQL_RENAME_SPLIT: &str = "UPDATE split SET name = :name WHERE split_id = :id;";
+const SQL_CLEAR_CATEGORY: &str = "DELETE FROM category_segment WHERE category_id = :category_id;";
+const SQL_CLEAR_SEGMENT: &str = "DELETE FROM segment_split WHERE segment_id = :segment_id;";
+const SQL_REMOVE_GAME_CATEGORY: &str =
+    "DELETE FROM game_category WHERE game_category_id = :game_category_id;";
@@ -0,0 +1,100 @@
+//! A bundle of all of the low-level database getters.
+
+use super::{
+    category::{self, id::InfoWithID, GcID},
+    comparison,
+    error::Result,
+    run,
+    util::WithID,
+};
+use crate::model::{
+    game::category::{AttemptInfo, ShortDescriptor, Target},
+    history, session, short,
+    timing::time,
+};
+
+/// All of the low-level getters on one database connection.
+///
+/// This implements every getter trait by delegating to the appropriate getter, and so can be used
+/// wherever something needs to query categories, runs, and comparisons at once.
+pub struct Getters<'conn> {
+    /// The category getter.
+    pub cat: category::Getter<'conn>,
+    /// The run getter.
+    pub run: run::Getter<'conn>,
+    /// The comparison getter.
+    pub comparison: comparison::Getter<'conn>,
+}
+
+impl<'conn> Getters<'conn> {
+    /// Constructs all of the getters on `conn`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database can't prepare a query.
+    pub fn new(conn: &'conn rusqlite::Connection) -> Result<Self> {
+        Ok(Self {
+            cat: category::Getter::new(conn)?,
+            run: run::Getter::new(conn)?,
+            comparison: comparison::Getter::new(conn)?,
+        })
+    }
+}
+
+impl category::Source for Getters<'_> {
+    fn all_game_category_info(&mut self) -> Result<Vec<Target>> {
+        self.cat.all_game_category_info()
+    }
+
+    fn info_from_short(&mut self, short: &ShortDescriptor) -> Result<InfoWithID> {
+        self.cat.info_from_short(short)
+    }
+
+    fn info_from_id(&mut self, gcid: GcID) -> Result<InfoWithID> {
+        self.cat.info_from_id(gcid)
+    }
+
+    fn attempt_info(&mut self, gcid: GcID) -> Result<AttemptInfo> {
+        self.cat.attempt_info(gcid)
+    }
+
+    fn splits(&mut self, gcid: GcID) -> Result<session::split::Set> {
+        self.cat.splits(gcid)
+    }
+}
+
+impl run::Source for Getters<'_> {
+    fn run_at(
+        &mut self,
+        id: GcID,
+        index: usize,
+    ) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
+        self.run.run_at(id, index)
+    }
+
+    fn runs_for(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>> {
+        self.run.runs_for(id)
+    }
+
+    fn split_totals_for(&mut self, id: i64) -> Result<history::timing::Totals> {
+        self.run.split_totals_for(id)
+    }
+}
+
+impl comparison::Source for Getters<'_> {
+    fn run_pb(&mut self, id: GcID) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
+        self.comparison.run_pb(id)
+    }
+
+    fn split_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
+        self.comparison.split_pbs(id)
+    }
+
+    fn segment_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
+        self.comparison.segment_pbs(id)
+    }
+
+    fn sum_of_best(&mut self, id: GcID) -> Result<Option<time::Time>> {
+        self.comparison.sum_of_best(id)
+    }
+}
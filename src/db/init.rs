@@ -13,21 +13,112 @@ pub fn on_db<C: Deref<Target = rusqlite::Connection>>(conn: C) -> Result<()> {
     Ok(())
 }
 
+/// Sets up a newly opened connection.
+///
+/// Write-ahead logging lets readers carry on while another connection writes, and the busy
+/// timeout makes concurrent writers wait for each other rather than failing outright.
+///
+/// # Errors
+///
+/// Propagates any errors from the underlying SQL database.
+pub(super) fn on_connect(conn: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+}
+
+/// How long a connection waits for another to finish writing before giving up.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Brings the database at `conn` up to date, if it was initialised by an older zombiesplit.
+///
+/// Databases that haven't been initialised yet are left alone.
+///
+/// # Errors
+///
+/// Propagates any errors from the underlying SQL database.  Each migration runs in its own
+/// transaction, so a failed migration is rolled back, leaving the database at the last version it
+/// successfully reached.
+pub fn migrate<C: Deref<Target = rusqlite::Connection>>(conn: C) -> Result<()> {
+    let is_initialised: bool = conn.query_row(SQL_IS_INITIALISED, [], |row| row.get(0))?;
+    if !is_initialised {
+        return Ok(());
+    }
+
+    let version: usize = conn.query_row("PRAGMA user_version;", [], |row| row.get(0))?;
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        log::info!("migrating database from schema version {from}");
+        // The pool hands out shared connections, so we can't take the connection mutably here.
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+const SQL_IS_INITIALISED: &str =
+    "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'game');";
+
+/// Migrations from each schema version to the next, indexed by the version they migrate from.
+///
+/// Each migration must end by setting `user_version` to the version it migrates to, and [SCHEMA]
+/// must set `user_version` to the number of migrations.  Migrations shouldn't open their own
+/// transactions, as [migrate] runs each in one.
+const MIGRATIONS: &[&str] = &[MIGRATE_GAME_SCOPE];
+
+/// Scopes categories, segments, and splits to the games that own them, and adds frame rates to games.
+///
+/// Games previously got their own category, segment, and split rows, so we can recover the owning
+/// game by following the links from each game's categories.  Rows that no category reaches are
+/// left without a game.  Existing games don't time in frames, so their frame rates are `NULL`.
+const MIGRATE_GAME_SCOPE: &str = "
+ALTER TABLE game     ADD COLUMN frame_rate TEXT;
+
+ALTER TABLE category ADD COLUMN game_id INTEGER REFERENCES game;
+ALTER TABLE segment  ADD COLUMN game_id INTEGER REFERENCES game;
+ALTER TABLE split    ADD COLUMN game_id INTEGER REFERENCES game;
+
+UPDATE category
+   SET game_id = (SELECT game_id
+                    FROM game_category
+                   WHERE game_category.category_id = category.category_id);
+UPDATE segment
+   SET game_id = (SELECT category.game_id
+                    FROM category_segment
+                         INNER JOIN category USING (category_id)
+                   WHERE category_segment.segment_id = segment.segment_id
+                   LIMIT 1);
+UPDATE split
+   SET game_id = (SELECT segment.game_id
+                    FROM segment_split
+                         INNER JOIN segment USING (segment_id)
+                   WHERE segment_split.split_id = split.split_id
+                   LIMIT 1);
+
+CREATE UNIQUE INDEX category_game_short ON category (game_id, short);
+CREATE UNIQUE INDEX segment_game_short  ON segment  (game_id, short);
+CREATE UNIQUE INDEX split_game_short    ON split    (game_id, short);
+
+PRAGMA user_version = 1;
+";
+
 /// The zombiesplit database schema.
 const SCHEMA: &str = "
 BEGIN;
 
 CREATE TABLE
     game
-        ( game_id  INTEGER PRIMARY KEY
-        , short    TEXT UNIQUE
-        , name     TEXT 
+        ( game_id     INTEGER PRIMARY KEY
+        , short       TEXT UNIQUE
+        , name        TEXT
+        , frame_rate  TEXT  -- NULL if the game doesn't time in frames
         );
 CREATE TABLE
     category
         ( category_id  INTEGER PRIMARY KEY
+        , game_id      INTEGER NOT NULL REFERENCES game
         , short        TEXT
         , name         TEXT
+        , UNIQUE(game_id, short)  -- short names only need to be unique within a game
         );
 CREATE TABLE
     game_category
@@ -39,8 +130,10 @@ CREATE TABLE
 CREATE TABLE
     segment
         ( segment_id  INTEGER PRIMARY KEY
+        , game_id     INTEGER NOT NULL REFERENCES game
         , short       TEXT
         , name        TEXT
+        , UNIQUE(game_id, short)
         );
 CREATE TABLE
     category_segment
@@ -54,8 +147,10 @@ CREATE TABLE
 CREATE TABLE
     split
         ( split_id  INTEGER PRIMARY KEY
+        , game_id   INTEGER NOT NULL REFERENCES game
         , short     TEXT
         , name      TEXT
+        , UNIQUE(game_id, short)
         );
 CREATE TABLE
     segment_split
@@ -124,4 +219,6 @@ CREATE VIEW split_pb (game_category_id, split_id, total) AS
            INNER JOIN run USING (run_id, game_category_id)
      GROUP BY game_category_id, split_id;
 
+PRAGMA user_version = 1;
+
 COMMIT;";
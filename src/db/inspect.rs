@@ -2,62 +2,57 @@
 
 use super::{
     super::model::{history, session, timing},
-    category::{self, id::InfoWithID},
+    category::{self, id::InfoWithID, Locator},
     comparison, run,
     util::WithID,
     Result,
 };
 
-/// Inspects various aspects of the database for a given game-category ID.
-pub struct Inspector<'db> {
-    /// The game-category being inspected.
-    pub info: InfoWithID,
-
-    // TODO(@MattWindsor91): abstract the getters into traits, so we can mock
-    // the inspector logic.
-    /// The run getter.
-    pub run: run::Getter<'db>,
-    /// The comparison getter.
-    pub comparison: comparison::Getter<'db>,
-    /// The category getter.
-    pub cat: category::Getter<'db>,
-}
+/// Trait of sources that can answer every query an [Inspector] makes.
+///
+/// This is implemented automatically for anything implementing all of the getter traits, such as
+/// [`Getters`](super::getters::Getters) for the database and
+/// [`memory::Store`](super::memory::Store) for an in-memory stand-in.
+pub trait Source: category::Source + run::Source + comparison::Source {}
 
-impl<'db> AsMut<run::Getter<'db>> for Inspector<'db> {
-    fn as_mut(&mut self) -> &mut run::Getter<'db> {
-        &mut self.run
-    }
-}
+impl<T: category::Source + run::Source + comparison::Source + ?Sized> Source for T {}
 
-impl<'db> AsMut<category::Getter<'db>> for Inspector<'db> {
-    fn as_mut(&mut self) -> &mut category::Getter<'db> {
-        &mut self.cat
-    }
+/// Inspects various aspects of a source of game data for a given game-category ID.
+pub struct Inspector<S> {
+    /// The game-category being inspected.
+    pub info: InfoWithID,
+    /// The source being inspected.
+    pub source: S,
 }
 
-impl<'db> timing::comparison::provider::Provider for Inspector<'db> {
+impl<S: Source> timing::comparison::provider::Provider for Inspector<S> {
     fn comparison(&mut self) -> timing::comparison::provider::Result {
         Ok(Some(
-            self.comparison
-                .get(&mut self.cat, &mut self.run, self.info.id)
-                .map_err(anyhow::Error::from)?,
+            comparison::build(&mut self.source, self.info.id).map_err(anyhow::Error::from)?,
         ))
     }
 }
 
-impl<'db> Inspector<'db> {
-    /// Initialises an attempt session for the game/category referred to by
-    /// `desc`, and with the given observer and sink.
+impl<S: Source> Inspector<S> {
+    /// Constructs an inspector over `source` for the game-category located by `loc`.
     ///
     /// # Errors
     ///
-    /// Propagates any errors from the database.
-    pub fn init_session<'obs, O: session::Observer>(
-        &mut self,
-        obs: &'obs O,
-    ) -> Result<session::Session<'db, 'obs, O>> {
-        // TODO(@MattWindsor91): remove this?
-        Ok(session::Session::new(self.cat.run(&self.info)?, obs))
+    /// Propagates any errors from locating the game-category.
+    pub fn new(mut source: S, loc: &impl Locator) -> Result<Self> {
+        Ok(Self {
+            info: loc.locate(&mut source)?,
+            source,
+        })
+    }
+
+    /// Gets a fresh attempt at the game/category being inspected, from which to build a session.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the source.
+    pub fn attempt(&mut self) -> Result<session::Attempt> {
+        self.source.attempt(&self.info)
     }
 
     /// Gets the run at the given index (ordered by timestamp) for this game-category pair.
@@ -66,13 +61,13 @@ impl<'db> Inspector<'db> {
     ///
     /// # Errors
     ///
-    /// Returns any database errors occurring during the listing.
+    /// Returns any errors from the source occurring during the listing.
     pub fn run_at_index<L: TimingLevel>(
         &mut self,
         index: usize,
         level: &L,
     ) -> Result<Option<history::run::Run<category::GcID, L::Output>>> {
-        let run = self.run.run_at(self.info.id, index)?;
+        let run = self.source.run_at(self.info.id, index)?;
         self.lift_run(run, level)
     }
 
@@ -80,12 +75,12 @@ impl<'db> Inspector<'db> {
     ///
     /// # Errors
     ///
-    /// Returns any database errors occurring during the listing.
+    /// Returns any errors from the source occurring during the listing.
     pub fn run_pb<L: TimingLevel>(
         &mut self,
         level: &L,
     ) -> Result<Option<history::run::Run<category::GcID, L::Output>>> {
-        let run = self.comparison.run_pb(self.info.id)?;
+        let run = self.source.run_pb(self.info.id)?;
         self.lift_run(run, level)
     }
 
@@ -99,19 +94,6 @@ impl<'db> Inspector<'db> {
             .transpose()
             .map(|x| x.map(|x| x.item))
     }
-
-    /// Adds split totals to an existing run.
-    ///
-    /// # Errors
-    ///
-    /// Returns any errors from querying the split totals.
-    fn add_split_totals(
-        &mut self,
-        run: WithID<history::run::Summary<category::GcID>>,
-    ) -> Result<WithID<history::run::WithTotals<category::GcID>>> {
-        let totals = self.run.split_totals_for(run.id)?;
-        Ok(run.map_item(|i| i.with_timing(totals)))
-    }
 }
 
 /// Trait implemented by types providing timing levels for run fetching.
@@ -122,15 +104,15 @@ pub trait TimingLevel {
     /// Type of resulting timing information.
     type Output;
 
-    /// Adds timing to a summary run using the services provided by `inspector`.
+    /// Adds timing to a summary run using the source behind `inspector`.
     ///
     /// # Errors
     ///
-    /// May fail if there is a problem getting additional information from the database to populate
+    /// May fail if there is a problem getting additional information from the source to populate
     /// the new timing information.
-    fn add_timing(
+    fn add_timing<S: Source>(
         &self,
-        inspector: &mut Inspector,
+        inspector: &mut Inspector<S>,
         run: WithID<history::run::Summary<category::GcID>>,
     ) -> Result<WithID<history::run::Run<category::GcID, Self::Output>>>;
 }
@@ -143,9 +125,9 @@ pub struct Summary;
 impl TimingLevel for Summary {
     type Output = history::timing::Summary;
 
-    fn add_timing(
+    fn add_timing<S: Source>(
         &self,
-        _inspector: &mut Inspector,
+        _inspector: &mut Inspector<S>,
         run: WithID<history::run::Summary<category::GcID>>,
     ) -> Result<WithID<history::run::Summary<category::GcID>>> {
         Ok(run)
@@ -155,9 +137,9 @@ impl TimingLevel for Summary {
 impl TimingLevel for history::timing::Level {
     type Output = history::timing::ForLevel;
 
-    fn add_timing(
+    fn add_timing<S: Source>(
         &self,
-        inspector: &mut Inspector,
+        inspector: &mut Inspector<S>,
         run: WithID<history::run::Summary<category::GcID>>,
     ) -> Result<WithID<history::run::ForLevel<category::GcID>>> {
         match self {
@@ -165,6 +147,7 @@ impl TimingLevel for history::timing::Level {
                 Ok(run.map_item(|i| i.map_timing(history::timing::ForLevel::from)))
             }
             history::timing::Level::Totals => Ok(inspector
+                .source
                 .add_split_totals(run)?
                 .map_item(|i| i.map_timing(history::timing::ForLevel::from))),
             history::timing::Level::Full => todo!("full timing not yet implemented"),
@@ -0,0 +1,374 @@
+/*! An in-memory stand-in for the database.
+
+A [Store] holds game configurations and historic runs in memory, and answers the same queries as
+the database getters do, with the same semantics.  This lets the inspector, comparison
+computation, and the server run without a database file, which is useful for testing them.
+
+Game-category IDs are assigned when each category is first added, in the order in which games
+were added and then by category short name within each game, starting from 1.  Replacing a game
+keeps the IDs of its existing categories, and gives any new ones the next free IDs, so that runs
+already in the store stay on their categories.  Run IDs are assigned in the order in which runs
+were added, again starting from 1.
+*/
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use log::info;
+
+use super::{
+    category::{self, id::InfoWithID, GcID, Locator},
+    comparison,
+    error::{Error, Result},
+    run,
+    util::WithID,
+};
+use crate::model::{
+    game::{
+        self,
+        category::{AttemptInfo, ShortDescriptor, Target},
+    },
+    history, session, short,
+    timing::{comparison::provider, time},
+};
+
+/// An in-memory store of games and historic runs.
+#[derive(Debug, Default)]
+pub struct Store {
+    /// The games in the store, along with their short names.
+    games: Vec<(short::Name, game::Config)>,
+    /// The game-categories in the store, in the order in which they were added.
+    ///
+    /// The ID of each game-category is its position in this list, plus one.
+    category_ids: Vec<ShortDescriptor>,
+    /// The runs in the store, in the order in which they were added.
+    runs: Vec<history::run::FullyTimed<GcID>>,
+}
+
+impl Store {
+    /// Constructs an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the game `game` to the store, assigning it shortname `short`.
+    ///
+    /// If there is already a game with this shortname, it is replaced.
+    pub fn add_game(&mut self, short: impl Into<short::Name>, game: game::Config) {
+        let short = short.into();
+        for cshort in sorted(&game.categories) {
+            let desc = ShortDescriptor::new(short, cshort);
+            if !self.category_ids.contains(&desc) {
+                self.category_ids.push(desc);
+            }
+        }
+
+        if let Some(existing) = self.games.iter_mut().find(|(s, _)| *s == short) {
+            existing.1 = game;
+        } else {
+            self.games.push((short, game));
+        }
+    }
+
+    /// Adds the historic run `run` to the store.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the run's category isn't in the store, or the run has times for splits that
+    /// aren't in its category.
+    pub fn add_run<L: Locator>(&mut self, run: &history::run::FullyTimed<L>) -> Result<()> {
+        let gcid = run.category_locator.locate_gcid(self)?;
+        info!(
+            "adding run from {} to game-category ID {}",
+            run.date, gcid.0
+        );
+
+        let splits = category::Source::splits(self, gcid)?;
+        if let Some(short) = run
+            .timing
+            .times
+            .keys()
+            .find(|s| splits.position(**s).is_none())
+        {
+            return Err(Error::MissingRunSplit { short: *short });
+        }
+
+        self.runs.push(run.with_locator(gcid));
+        Ok(())
+    }
+
+    /// Iterates over every game-category in the store, along with its ID.
+    fn categories(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            GcID,
+            ShortDescriptor,
+            &game::Config,
+            &game::config::Category,
+        ),
+    > {
+        // Categories dropped when replacing a game keep their IDs, but no longer appear.
+        (1..).zip(&self.category_ids).filter_map(|(id, short)| {
+            let (_, game) = self.games.iter().find(|(g, _)| *g == short.game)?;
+            let cat = game.categories.get(&short.category)?;
+            Some((GcID(id), *short, game, cat))
+        })
+    }
+
+    /// Finds the game-category matching `pred`, failing with `missing` if there isn't one.
+    fn find_category(
+        &self,
+        pred: impl Fn(GcID, ShortDescriptor) -> bool,
+        missing: impl FnOnce() -> String,
+    ) -> Result<(
+        GcID,
+        ShortDescriptor,
+        &game::Config,
+        &game::config::Category,
+    )> {
+        self.categories()
+            .find(|(id, short, _, _)| pred(*id, *short))
+            .ok_or_else(|| Error::MissingCategory(missing()))
+    }
+
+    /// Iterates over every run on the game-category `gcid`, along with its ID.
+    fn runs_in(&self, gcid: GcID) -> impl Iterator<Item = (i64, &history::run::FullyTimed<GcID>)> {
+        (1..)
+            .zip(&self.runs)
+            .filter(move |(_, run)| run.category_locator == gcid)
+    }
+
+    /// Summarises every run on the game-category `gcid` that has at least one time.
+    ///
+    /// Like the database, this only ranks completed runs.
+    fn summaries(&self, gcid: GcID) -> Vec<WithID<history::run::Summary<GcID>>> {
+        let totals: Vec<_> = self
+            .runs_in(gcid)
+            .filter_map(|(id, run)| total(run).map(|total| (id, run, total)))
+            .collect();
+        totals
+            .iter()
+            .map(|(id, run, total)| {
+                let rank = run.was_completed.then(|| {
+                    1 + totals
+                        .iter()
+                        .filter(|(_, r, t)| r.was_completed && t < total)
+                        .count()
+                });
+                WithID {
+                    id: *id,
+                    item: history::run::Summary {
+                        category_locator: gcid,
+                        timing: history::timing::Summary {
+                            total: *total,
+                            rank,
+                        },
+                        was_completed: run.was_completed,
+                        date: run.date,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Gets the total of every split that has times in `run`.
+fn split_totals(run: &history::run::FullyTimed<GcID>) -> short::Map<time::Time> {
+    run.timing
+        .times
+        .iter()
+        .filter(|(_, times)| !times.is_empty())
+        .map(|(short, times)| (*short, times.iter().copied().sum()))
+        .collect()
+}
+
+/// Gets the total of `run`, if it has any times at all.
+fn total(run: &history::run::FullyTimed<GcID>) -> Option<time::Time> {
+    let mut times = run.timing.times.values().flatten().copied().peekable();
+    times.peek().is_some().then(|| times.sum())
+}
+
+/// Keeps the smaller of `time` and the time already in `map` at `short`.
+fn keep_min(map: &mut short::Map<time::Time>, short: short::Name, time: time::Time) {
+    let entry = map.entry(short).or_insert(time);
+    *entry = (*entry).min(time);
+}
+
+impl category::Source for Store {
+    fn all_game_category_info(&mut self) -> Result<Vec<Target>> {
+        let mut targets: Vec<_> = self
+            .categories()
+            .map(|(_, short, game, cat)| target(short, game, cat))
+            .collect();
+        // Short names compare by interned address, so we sort by their text instead.
+        targets.sort_by_cached_key(|t| (t.short.game.to_string(), t.short.category.to_string()));
+        Ok(targets)
+    }
+
+    fn info_from_short(&mut self, short: &ShortDescriptor) -> Result<InfoWithID> {
+        let (id, short, game, cat) =
+            self.find_category(|_, s| s == *short, || short.to_string())?;
+        Ok(InfoWithID {
+            id,
+            info: target(short, game, cat),
+        })
+    }
+
+    fn info_from_id(&mut self, gcid: GcID) -> Result<InfoWithID> {
+        let (id, short, game, cat) =
+            self.find_category(|id, _| id == gcid, || gcid.0.to_string())?;
+        Ok(InfoWithID {
+            id,
+            info: target(short, game, cat),
+        })
+    }
+
+    fn attempt_info(&mut self, gcid: GcID) -> Result<AttemptInfo> {
+        Ok(self
+            .runs_in(gcid)
+            .fold(AttemptInfo::default(), |mut info, (_, run)| {
+                info.total += 1;
+                info.completed += usize::from(run.was_completed);
+                info
+            }))
+    }
+
+    fn splits(&mut self, gcid: GcID) -> Result<session::split::Set> {
+        let (_, _, game, cat) = self.find_category(|id, _| id == gcid, || gcid.0.to_string())?;
+        Ok(session::split::Set::from_config(game, cat)?)
+    }
+}
+
+impl run::Source for Store {
+    fn run_at(
+        &mut self,
+        id: GcID,
+        index: usize,
+    ) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
+        let mut runs = self.summaries(id);
+        runs.sort_by_key(|r| r.item.date);
+        Ok(runs.into_iter().nth(index))
+    }
+
+    fn runs_for(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>> {
+        let mut runs = self.summaries(id);
+        runs.sort_by_key(|r| {
+            let rank = r.item.timing.rank;
+            (rank.is_none(), rank, r.item.date)
+        });
+        Ok(runs)
+    }
+
+    fn split_totals_for(&mut self, id: i64) -> Result<history::timing::Totals> {
+        let run = usize::try_from(id - 1).ok().and_then(|i| self.runs.get(i));
+        Ok(history::timing::Totals {
+            totals: run.map(split_totals).unwrap_or_default(),
+        })
+    }
+}
+
+impl comparison::Source for Store {
+    fn run_pb(&mut self, id: GcID) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
+        Ok(self
+            .summaries(id)
+            .into_iter()
+            .filter(|r| r.item.was_completed)
+            .min_by_key(|r| r.item.timing.total))
+    }
+
+    fn split_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
+        let mut pbs = short::Map::default();
+        for (_, run) in self.runs_in(id) {
+            for (short, total) in split_totals(run) {
+                keep_min(&mut pbs, short, total);
+            }
+        }
+        Ok(pbs)
+    }
+
+    fn segment_pbs(&mut self, id: GcID) -> Result<short::Map<time::Time>> {
+        let splits = category::Source::splits(self, id)?;
+        let shorts: Vec<_> = splits.iter().map(|s| s.info.short).collect();
+
+        let mut pbs = short::Map::default();
+        for (_, run) in self.runs_in(id) {
+            let totals = split_totals(run);
+            for segment in splits.segments() {
+                let total: Option<time::Time> = shorts[segment.splits.clone()]
+                    .iter()
+                    .map(|s| totals.get(s).copied())
+                    .sum();
+                if let Some(total) = total {
+                    keep_min(&mut pbs, segment.info.short, total);
+                }
+            }
+        }
+        Ok(pbs)
+    }
+
+    fn sum_of_best(&mut self, id: GcID) -> Result<Option<time::Time>> {
+        let pbs = self.split_pbs(id)?;
+        Ok((!pbs.is_empty()).then(|| pbs.values().copied().sum()))
+    }
+}
+
+/// Gets the keys of `map` sorted by their text, as short names compare by interned address.
+fn sorted<T>(map: &short::Map<T>) -> Vec<short::Name> {
+    let mut keys: Vec<short::Name> = map.keys().copied().collect();
+    keys.sort_by_cached_key(ToString::to_string);
+    keys
+}
+
+fn target(short: ShortDescriptor, game: &game::Config, cat: &game::config::Category) -> Target {
+    Target {
+        game: game.name.clone(),
+        category: cat.name.clone(),
+        short,
+        frame_rate: game.frame_rate,
+    }
+}
+
+/// A [Store] that can be shared between the sink and comparison provider of a session.
+pub type Shared = Arc<Mutex<Store>>;
+
+/// Locks `store`, ignoring poisoning; the store is never left half-updated.
+fn lock(store: &Shared) -> MutexGuard<'_, Store> {
+    store.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A run sink that adds runs to a shared in-memory store.
+#[derive(Clone)]
+pub struct Sink(pub Shared);
+
+impl session::sink::Sink for Sink {
+    fn accept(&mut self, run: session::sink::Run) -> session::sink::Result {
+        lock(&self.0).add_run(&run).map_err(anyhow::Error::new)?;
+        Ok(session::sink::Outcome::Saved)
+    }
+}
+
+/// A comparison provider that computes comparisons for one game-category from a shared store.
+#[derive(Clone)]
+pub struct Provider {
+    /// The store from which comparisons are computed.
+    store: Shared,
+    /// The game-category whose comparisons are being provided.
+    id: GcID,
+}
+
+impl Provider {
+    /// Constructs a provider for comparisons on game-category `id` in `store`.
+    #[must_use]
+    pub fn new(store: Shared, id: GcID) -> Self {
+        Self { store, id }
+    }
+}
+
+impl provider::Provider for Provider {
+    fn comparison(&mut self) -> provider::Result {
+        let comparison =
+            comparison::build(&mut *lock(&self.store), self.id).map_err(anyhow::Error::from)?;
+        Ok(Some(comparison))
+    }
+}
@@ -3,6 +3,6 @@
 pub mod get;
 pub mod inserter;
 pub mod sink;
-pub use get::Getter;
+pub use get::{Getter, Source};
 pub use inserter::Inserter;
 pub use sink::Sink;
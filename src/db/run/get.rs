@@ -12,6 +12,7 @@ use super::super::{
 };
 
 /// Object for finding historic runs of interest in the database.
+#[allow(clippy::struct_field_names)] // each field is a prepared query
 pub struct Getter<'conn> {
     /// Query used for getting a run by its number.
     query_run_at_index: Statement<'conn>,
@@ -34,13 +35,56 @@ impl<'conn> Getter<'conn> {
             query_splits_for_run: conn.prepare(SQL_SPLITS_FOR_RUN)?,
         })
     }
+}
 
-    /// Gets the run at index `index` within the game-category at database ID `id`.
+/// Trait of things from which we can get historic runs.
+///
+/// [Getter] gets runs from the database; other sources, such as
+/// [`memory::Store`](super::super::memory::Store), can stand in for the database in tests.
+pub trait Source {
+    /// Gets the run at index `index` (ordered by timestamp) within the game-category `id`.
     ///
     /// # Errors
     ///
-    /// Errors if the run can't be converted from a database row.
-    pub fn run_at(
+    /// Errors if the run can't be retrieved from the source.
+    fn run_at(
+        &mut self,
+        id: GcID,
+        index: usize,
+    ) -> Result<Option<WithID<history::run::Summary<GcID>>>>;
+
+    /// Gets summaries for each run on a given game-category ID.
+    ///
+    /// Runs come out in rank order, with incomplete runs last; ties are broken by timestamp.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the runs can't be retrieved from the source.
+    fn runs_for(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>>;
+
+    /// Gets split totals for the run with the given ID.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the totals can't be retrieved from the source.
+    fn split_totals_for(&mut self, id: i64) -> Result<history::timing::Totals>;
+
+    /// Adds split totals to an existing run.
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors from querying the split totals.
+    fn add_split_totals(
+        &mut self,
+        run: WithID<history::run::Summary<GcID>>,
+    ) -> Result<WithID<history::run::WithTotals<GcID>>> {
+        let totals = self.split_totals_for(run.id)?;
+        Ok(run.map_item(|i| i.with_timing(totals)))
+    }
+}
+
+impl Source for Getter<'_> {
+    fn run_at(
         &mut self,
         id: GcID,
         index: usize,
@@ -53,12 +97,7 @@ impl<'conn> Getter<'conn> {
             .transpose()
     }
 
-    /// Gets summaries for each run on a given game-category ID.
-    ///
-    /// # Errors
-    ///
-    /// Errors if the database query fails.
-    pub fn runs_for(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>> {
+    fn runs_for(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>> {
         self.query_all_runs
             .query_and_then(named_params![":game_category": id], |r| {
                 WithID::from_row(id, r)
@@ -66,12 +105,7 @@ impl<'conn> Getter<'conn> {
             .collect()
     }
 
-    /// Gets split totals for the run with the given ID.
-    ///
-    /// # Errors
-    ///
-    /// Errors if the database query fails.
-    pub fn split_totals_for(&mut self, id: i64) -> Result<history::timing::Totals> {
+    fn split_totals_for(&mut self, id: i64) -> Result<history::timing::Totals> {
         let totals = self
             .query_splits_for_run
             .query_and_then(named_params![":run": id], |r| {
@@ -82,19 +116,6 @@ impl<'conn> Getter<'conn> {
             .collect::<Result<short::Map<time::Time>>>()?;
         Ok(history::timing::Totals { totals })
     }
-
-    /// Adds split totals to an existing run.
-    ///
-    /// # Errors
-    ///
-    /// Returns any errors from querying the split totals.
-    pub fn add_split_totals(
-        &mut self,
-        run: WithID<history::run::Summary<GcID>>,
-    ) -> Result<WithID<history::run::WithTotals<GcID>>> {
-        let totals = self.split_totals_for(run.id)?;
-        Ok(run.map_item(|i| i.with_timing(totals)))
-    }
 }
 
 fn date_from_timestamp(timestamp: i64) -> Result<chrono::DateTime<chrono::Utc>> {
@@ -127,4 +127,5 @@ const SQL_SPLIT_SHORTMAP: &str = "
             INNER JOIN segment_split    USING(split_id)
             INNER JOIN category_segment USING(segment_id)
             INNER JOIN game_category    USING(category_id)
-    WHERE game_category_id = :game_category;";
+    WHERE game_category_id = :game_category
+      AND split.game_id = game_category.game_id;";
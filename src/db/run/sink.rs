@@ -1,7 +1,5 @@
 //! Implements run sinking by autosaving runs into the database on reset.
 
-use std::rc::Rc;
-
 use log::info;
 
 use crate::{model::session::sink, Db};
@@ -11,8 +9,8 @@ use crate::{model::session::sink, Db};
 /// A run sink that logs runs into a database.
 #[derive(Clone)]
 pub struct Sink {
-    /// A reference to the database.
-    db: Rc<Db>,
+    /// A handle to the database.
+    db: Db,
 }
 
 impl sink::Sink for Sink {
@@ -24,8 +22,9 @@ impl sink::Sink for Sink {
 }
 
 impl Sink {
+    /// Constructs a sink saving runs into `db`.
     #[must_use]
-    pub fn new(db: Rc<Db>) -> Self {
+    pub fn new(db: Db) -> Self {
         Sink { db }
     }
 }
@@ -2,7 +2,9 @@
 
 pub mod category;
 pub mod config;
+pub mod segment;
 pub mod split;
 
 pub use self::config::Config;
+pub use segment::Segment;
 pub use split::Split;
@@ -4,7 +4,7 @@ use std::{
     str::FromStr,
 };
 
-use super::super::short;
+use super::super::{short, timing::time::frame};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -83,6 +83,9 @@ pub struct Target {
     pub category: String,
     /// The short descriptor of the category and game.
     pub short: ShortDescriptor,
+    /// The frame rate of the game, if it times in frames.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_rate: Option<frame::Rate>,
 }
 
 /// Information about the number of attempts a game-category has had.
@@ -5,6 +5,8 @@ These models represent the way in which zombiesplit is fed new games to track
 (not to be confused with the models that represent in-database game data).
 */
 
+pub mod lint;
+
 use std::{
     fmt::{self, Display},
     str::FromStr,
@@ -19,7 +21,11 @@ use crate::model::timing::time;
 use super::super::short;
 
 /// Configuration for a game.
+///
+/// Configuration is validated as it is deserialised, so that every category it contains can be
+/// resolved into its full segment and split lists.
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(try_from = "Unchecked")]
 pub struct Config {
     /// The name of the game.
     pub name: String,
@@ -29,6 +35,12 @@ pub struct Config {
     pub splits: short::Map<Split>,
     /// Map of categories for the game.
     pub categories: short::Map<Category>,
+    /// The frame rate at which the game reports in-game time, if it does so in frames.
+    ///
+    /// Games with a frame rate have their times entered in frame notation and summed by frame
+    /// count, to avoid rounding drift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_rate: Option<time::frame::Rate>,
 }
 
 impl Config {
@@ -43,39 +55,201 @@ impl Config {
             .get(&short)
             .ok_or(Error::MissingCategory { short })
     }
+
+    /// Checks that every category in this configuration resolves fully.
+    ///
+    /// This resolves each category's inheritance and edits, and checks that every segment and
+    /// split referenced along the way exists.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first resolution error found.
+    pub fn validate(&self) -> Result<()> {
+        for category in self.categories.values() {
+            for (_, segment) in category.full_segments(self)? {
+                segment.full_splits(self).try_for_each(|s| s.map(|_| ()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Game configuration as deserialised, before validation.
+#[derive(Deserialize)]
+struct Unchecked {
+    name: String,
+    segments: short::Map<Segment>,
+    splits: short::Map<Split>,
+    categories: short::Map<Category>,
+    #[serde(default)]
+    frame_rate: Option<time::frame::Rate>,
+}
+
+impl TryFrom<Unchecked> for Config {
+    type Error = Error;
+
+    fn try_from(raw: Unchecked) -> Result<Self> {
+        let config = raw.assume_valid();
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl Unchecked {
+    /// Converts this configuration without validating it.
+    fn assume_valid(self) -> Config {
+        Config {
+            name: self.name,
+            segments: self.segments,
+            splits: self.splits,
+            categories: self.categories,
+            frame_rate: self.frame_rate,
+        }
+    }
 }
 
 /// A run category.
+///
+/// A category can extend another category, inheriting its segments; it can then replace, remove,
+/// or insert individual segments rather than restating the whole list.  Edits apply in that order.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Category {
     /// The display name of the category.
     pub name: String,
+    /// The category, if any, whose segments this category inherits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<short::Name>,
     /// The list of segments that make up the category.
-    #[serde(default)]
+    ///
+    /// If the category extends another, a non-empty list overrides the inherited segments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub segments: Vec<short::Name>,
+    /// Map from inherited segments to the segments that replace them in this category.
+    #[serde(default, skip_serializing_if = "short::Map::is_empty")]
+    pub replace: short::Map<short::Name>,
+    /// Inherited segments that are not part of this category.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<short::Name>,
+    /// Segments inserted into the inherited segments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub insert: Vec<Insert>,
 }
 
 impl Category {
-    /// Iterates over the full split group information for this category.
+    /// Resolves the short names of the segments in this category, in order.
     ///
-    /// This iterator requires information from the underlying `game`, and returns results that may
-    /// fail if the `game` is missing the referenced data.
+    /// This applies inheritance and any segment edits, but does not check that the segments
+    /// exist; see [`Category::full_segments`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the category extends a missing category, its inheritance is cyclic, or an edit
+    /// refers to a segment that isn't in the inherited list.
+    pub fn segment_names(&self, game: &Config) -> Result<Vec<short::Name>> {
+        // Any chain longer than the number of categories must revisit one of them.
+        self.resolve(game, game.categories.len())
+    }
+
+    fn resolve(&self, game: &Config, depth: usize) -> Result<Vec<short::Name>> {
+        let mut segments = match self.extends {
+            Some(parent) => {
+                let depth = depth.checked_sub(1).ok_or_else(|| Error::CyclicCategory {
+                    in_category: self.name.clone(),
+                })?;
+                let parent = game
+                    .categories
+                    .get(&parent)
+                    .ok_or_else(|| Error::MissingParent {
+                        short: parent,
+                        in_category: self.name.clone(),
+                    })?;
+                parent.resolve(game, depth)?
+            }
+            None => vec![],
+        };
+        if !self.segments.is_empty() {
+            segments.clone_from(&self.segments);
+        }
+
+        for (from, to) in &self.replace {
+            let index = self.find(&segments, *from)?;
+            segments[index] = *to;
+        }
+        for short in &self.remove {
+            let index = self.find(&segments, *short)?;
+            segments.remove(index);
+        }
+        for insert in &self.insert {
+            let index = match (insert.before, insert.after) {
+                (None, None) => segments.len(),
+                (Some(before), None) => self.find(&segments, before)?,
+                (None, Some(after)) => self.find(&segments, after)? + 1,
+                (Some(_), Some(_)) => {
+                    return Err(Error::AmbiguousInsert {
+                        short: insert.segment,
+                        in_category: self.name.clone(),
+                    })
+                }
+            };
+            segments.insert(index, insert.segment);
+        }
+
+        Ok(segments)
+    }
+
+    fn find(&self, segments: &[short::Name], short: short::Name) -> Result<usize> {
+        segments
+            .iter()
+            .position(|s| *s == short)
+            .ok_or_else(|| Error::NotInherited {
+                short,
+                in_category: self.name.clone(),
+            })
+    }
+
+    /// Resolves the full segment information for this category, in order.
+    ///
+    /// This requires information from the underlying `game`, including any categories this
+    /// category extends.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the segment list can't be resolved (see [`Category::segment_names`]), or the
+    /// `game` is missing any of the resulting segments.
     pub fn full_segments<'cfg>(
-        &'cfg self,
+        &self,
         game: &'cfg Config,
-    ) -> impl Iterator<Item = Result<(short::Name, &'cfg Segment)>> + 'cfg {
-        self.segments.iter().map(|sn| {
-            game.segments
-                .get_key_value(sn)
-                .map(|(k, v)| (*k, v))
-                .ok_or_else(|| Error::MissingSegment {
-                    short: *sn,
-                    in_category: self.name.clone(),
-                })
-        })
+    ) -> Result<Vec<(short::Name, &'cfg Segment)>> {
+        self.segment_names(game)?
+            .into_iter()
+            .map(|sn| {
+                game.segments
+                    .get_key_value(&sn)
+                    .map(|(k, v)| (*k, v))
+                    .ok_or_else(|| Error::MissingSegment {
+                        short: sn,
+                        in_category: self.name.clone(),
+                    })
+            })
+            .collect()
     }
 }
 
+/// An insertion of a segment into the segments a category inherits.
+///
+/// At most one of `before` and `after` may be given; if neither is, the segment goes at the end.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Insert {
+    /// The segment to insert.
+    pub segment: short::Name,
+    /// The segment before which the new segment goes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<short::Name>,
+    /// The segment after which the new segment goes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<short::Name>,
+}
+
 /// A configured split segment.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Segment {
@@ -149,6 +323,31 @@ pub enum Error {
     #[error("couldn't find category {short}")]
     MissingCategory { short: short::Name },
 
+    /// A category extended a category that is missing in the configuration.
+    #[error("couldn't find category {short} extended by category {in_category}")]
+    MissingParent {
+        short: short::Name,
+        in_category: String,
+    },
+
+    /// A category's chain of extended categories loops back on itself.
+    #[error("category {in_category} extends itself, directly or indirectly")]
+    CyclicCategory { in_category: String },
+
+    /// A category edited a segment that isn't in the segment list it inherited.
+    #[error("category {in_category} edits segment {short}, which it doesn't inherit")]
+    NotInherited {
+        short: short::Name,
+        in_category: String,
+    },
+
+    /// A category inserted a segment both before and after other segments.
+    #[error("category {in_category} inserts segment {short} both before and after segments")]
+    AmbiguousInsert {
+        short: short::Name,
+        in_category: String,
+    },
+
     /// A category referenced a segment that is missing in the configuration or database.
     #[error("couldn't find segment {short} requested by category {in_category}")]
     MissingSegment {
@@ -166,3 +365,88 @@ pub enum Error {
 
 /// Shorthand for results over expanding game config.
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = r#"
+name = "Test Game"
+
+[segments.a]
+    name = "A"
+    splits = ["a1"]
+[segments.b]
+    name = "B"
+    splits = ["b1"]
+[segments.c]
+    name = "C"
+    splits = ["c1"]
+[segments.d]
+    name = "D"
+    splits = ["d1"]
+
+[splits.a1]
+    name = "A1"
+[splits.b1]
+    name = "B1"
+[splits.c1]
+    name = "C1"
+[splits.d1]
+    name = "D1"
+
+[categories.base]
+name = "Base"
+segments = ["a", "b", "c"]
+"#;
+
+    fn load(categories: &str) -> std::result::Result<Config, toml::de::Error> {
+        toml::from_str(&format!("{GAME}{categories}"))
+    }
+
+    fn names(config: &Config, short: &str) -> Vec<String> {
+        config
+            .category(short)
+            .and_then(|c| c.segment_names(config))
+            .expect("category should resolve")
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    /// Tests that extending categories inherit and then edit their parent's segments.
+    #[test]
+    fn extends_with_edits() {
+        let config = load(
+            r#"
+[categories.same]
+name = "Same"
+extends = "base"
+
+[categories.edited]
+name = "Edited"
+extends = "same"
+replace = { b = "d" }
+remove = ["a"]
+insert = [{ segment = "a", after = "c" }, { segment = "b", before = "d" }]
+"#,
+        )
+        .expect("config should load");
+
+        assert_eq!(vec!["a", "b", "c"], names(&config, "same"));
+        assert_eq!(vec!["b", "d", "c", "a"], names(&config, "edited"));
+    }
+
+    /// Tests that unresolvable categories are rejected on load.
+    #[test]
+    fn invalid_categories_rejected() {
+        for categories in [
+            "[categories.x]\nname = \"X\"\nextends = \"nope\"",
+            "[categories.x]\nname = \"X\"\nextends = \"y\"\n[categories.y]\nname = \"Y\"\nextends = \"x\"",
+            "[categories.x]\nname = \"X\"\nextends = \"base\"\nremove = [\"d\"]",
+            "[categories.x]\nname = \"X\"\nsegments = [\"nope\"]",
+        ] {
+            assert!(load(categories).is_err(), "{categories} should be rejected");
+        }
+    }
+}
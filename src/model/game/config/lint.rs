@@ -0,0 +1,313 @@
+//! Up-front checking of game configuration.
+//!
+//! Resolving categories stops at the first problem it finds, which makes fixing a large game
+//! configuration a slow process.  The checks here instead report every problem at once, along
+//! with some things that are probably mistakes but don't stop the configuration from working.
+
+use std::collections::HashSet;
+
+use super::{super::super::short, Config, Error, Record, Unchecked};
+use crate::model::timing::time;
+
+/// How seriously to take a lint issue.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The configuration works, but probably isn't what was intended.
+    Warning,
+    /// The configuration can't be loaded, or will fail when used.
+    Error,
+}
+
+/// A problem found in game configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum Issue {
+    /// The configuration couldn't be parsed at all.
+    #[error("couldn't parse game config: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// A category or segment couldn't be resolved.
+    #[error("{0}")]
+    Unresolved(#[from] Error),
+
+    /// A split record couldn't be parsed as a time.
+    #[error("record {record} of split {split} isn't a valid time: {err}")]
+    BadRecord {
+        split: short::Name,
+        record: short::Name,
+        err: time::Error,
+    },
+
+    /// A short name contains a slash, which would make category descriptors ambiguous.
+    #[error("short name {short} contains '/'")]
+    SlashInShortName { short: short::Name },
+
+    /// A category has no splits.
+    #[error("category {in_category} has no splits")]
+    EmptyCategory { in_category: short::Name },
+
+    /// A category contains the same segment more than once.
+    #[error("category {in_category} contains segment {short} more than once")]
+    DuplicateSegment {
+        short: short::Name,
+        in_category: short::Name,
+    },
+
+    /// A category contains the same split more than once.
+    #[error("category {in_category} contains split {short} more than once")]
+    DuplicateSplit {
+        short: short::Name,
+        in_category: short::Name,
+    },
+
+    /// A segment isn't in any category.
+    #[error("segment {short} isn't used by any category")]
+    UnusedSegment { short: short::Name },
+
+    /// A split isn't in any category.
+    #[error("split {short} isn't used by any category")]
+    UnusedSplit { short: short::Name },
+}
+
+impl Issue {
+    /// Gets the severity of this issue.
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::EmptyCategory { .. } | Self::UnusedSegment { .. } | Self::UnusedSplit { .. } => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// The issues found when checking a game configuration.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// The issues, in the order in which they were found.
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    /// Gets whether any of the issues in this report are errors.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.severity() == Severity::Error)
+    }
+
+    fn push(&mut self, issue: impl Into<Issue>) {
+        self.issues.push(issue.into());
+    }
+}
+
+/// Checks game configuration given as TOML source.
+///
+/// Unlike loading the configuration, this reports unparsable records alongside every other
+/// issue, rather than failing outright.
+#[must_use]
+pub fn source(toml: &str) -> Report {
+    let mut report = Report::default();
+    let mut value: toml::Value = match toml::from_str(toml) {
+        Ok(value) => value,
+        Err(err) => {
+            report.push(err);
+            return report;
+        }
+    };
+
+    check_records(&mut report, &mut value);
+    // Unchecked skips the validation that would stop us reporting everything at once.
+    match value.try_into::<Unchecked>() {
+        Ok(raw) => report.issues.extend(config(&raw.assume_valid()).issues),
+        Err(err) => report.push(err),
+    }
+    report
+}
+
+/// Checks every record in `value`, removing any that don't parse.
+fn check_records(report: &mut Report, value: &mut toml::Value) {
+    let splits = value.get_mut("splits").and_then(toml::Value::as_table_mut);
+    for (split, body) in splits.into_iter().flatten() {
+        let Some(records) = body.get_mut("records").and_then(toml::Value::as_table_mut) else {
+            continue;
+        };
+
+        let mut bad = Vec::new();
+        for (record, time) in records.iter() {
+            // Records that aren't strings fail deserialisation later, with a better message.
+            if let Some(Err(err)) = time.as_str().map(str::parse::<Record>) {
+                bad.push(record.clone());
+                report.push(Issue::BadRecord {
+                    split: short::Name::from(split.as_str()),
+                    record: short::Name::from(record.as_str()),
+                    err,
+                });
+            }
+        }
+        for record in bad {
+            records.remove(&record);
+        }
+    }
+}
+
+/// Checks a game configuration.
+#[must_use]
+pub fn config(game: &Config) -> Report {
+    let mut report = Report::default();
+
+    for short in sorted(&game.categories)
+        .into_iter()
+        .chain(sorted(&game.segments))
+        .chain(sorted(&game.splits))
+    {
+        if short.to_string().contains('/') {
+            report.push(Issue::SlashInShortName { short });
+        }
+    }
+
+    for short in sorted(&game.segments) {
+        let segment = &game.segments[&short];
+        for split in &segment.splits {
+            if !game.splits.contains_key(split) {
+                report.push(Error::MissingSplit {
+                    short: *split,
+                    in_segment: segment.name.clone(),
+                });
+            }
+        }
+    }
+
+    let mut used_segments = HashSet::new();
+    let mut used_splits = HashSet::new();
+    for short in sorted(&game.categories) {
+        let category = &game.categories[&short];
+        let segments = match category.segment_names(game) {
+            Ok(segments) => segments,
+            Err(err) => {
+                report.push(err);
+                continue;
+            }
+        };
+
+        let mut seen_segments = HashSet::new();
+        let mut seen_splits = HashSet::new();
+        for segment_short in segments {
+            if !seen_segments.insert(segment_short) {
+                report.push(Issue::DuplicateSegment {
+                    short: segment_short,
+                    in_category: short,
+                });
+            }
+            let Some(segment) = game.segments.get(&segment_short) else {
+                report.push(Error::MissingSegment {
+                    short: segment_short,
+                    in_category: category.name.clone(),
+                });
+                continue;
+            };
+            for split in &segment.splits {
+                if !seen_splits.insert(*split) {
+                    report.push(Issue::DuplicateSplit {
+                        short: *split,
+                        in_category: short,
+                    });
+                }
+            }
+        }
+
+        if seen_splits.is_empty() {
+            report.push(Issue::EmptyCategory { in_category: short });
+        }
+        used_segments.extend(seen_segments);
+        used_splits.extend(seen_splits);
+    }
+
+    for short in sorted(&game.segments) {
+        if !used_segments.contains(&short) {
+            report.push(Issue::UnusedSegment { short });
+        }
+    }
+    for short in sorted(&game.splits) {
+        if !used_splits.contains(&short) {
+            report.push(Issue::UnusedSplit { short });
+        }
+    }
+
+    report
+}
+
+/// Gets the keys of `map` in a stable order, so that reports don't vary from run to run.
+fn sorted<T>(map: &short::Map<T>) -> Vec<short::Name> {
+    let mut keys: Vec<short::Name> = map.keys().copied().collect();
+    keys.sort_by_cached_key(ToString::to_string);
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = r#"
+name = "Test Game"
+
+[segments.a]
+    name = "A"
+    splits = ["a1", "a2"]
+[segments.b]
+    name = "B"
+    splits = ["a2", "nope"]
+[segments.c]
+    name = "C"
+    splits = []
+
+[splits.a1]
+    name = "A1"
+    records = { wr = "1:02.3" }
+[splits.a2]
+    name = "A2"
+    records = { wr = "1:02x" }
+[splits.d1]
+    name = "D1"
+
+[categories."x/y"]
+name = "Slashed"
+segments = ["a", "b"]
+
+[categories.empty]
+name = "Empty"
+segments = ["c"]
+
+[categories.broken]
+name = "Broken"
+extends = "missing"
+"#;
+
+    /// Tests that checking reports every problem in a configuration.
+    #[test]
+    fn source_reports_everything() {
+        let report = source(GAME);
+        let has = |f: &dyn Fn(&Issue) -> bool| report.issues.iter().any(f);
+
+        assert!(report.has_errors());
+        assert!(has(&|i| matches!(i, Issue::BadRecord { .. })));
+        assert!(has(&|i| matches!(i, Issue::SlashInShortName { .. })));
+        assert!(has(&|i| matches!(i, Issue::EmptyCategory { .. })));
+        assert!(has(&|i| matches!(i, Issue::DuplicateSplit { .. })));
+        assert!(has(&|i| matches!(i, Issue::UnusedSplit { .. })));
+        assert!(has(&|i| matches!(
+            i,
+            Issue::Unresolved(Error::MissingSplit { .. })
+        )));
+        assert!(has(&|i| matches!(
+            i,
+            Issue::Unresolved(Error::MissingParent { .. })
+        )));
+    }
+
+    /// Tests that the sample game configuration has no errors.
+    #[test]
+    fn sample_has_no_errors() {
+        let report = source(include_str!("../../../../scd11.toml"));
+        assert!(!report.has_errors(), "{:?}", report.issues);
+    }
+}
@@ -0,0 +1,33 @@
+//! Models about segments in the context of a game configuration.
+
+use crate::model::short;
+
+/// Information about a segment.
+///
+/// A segment is a named group of consecutive splits, such as the acts of a zone in a platformer.
+/// Records here correspond to entries in the `segment` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The short name of this segment.
+    pub short: short::Name,
+    /// The display name of this segment.
+    pub name: String,
+}
+
+impl Segment {
+    /// Constructs a new segment.
+    ///
+    /// ```
+    /// use zombiesplit::model::game::segment;
+    ///
+    /// let segment = segment::Segment::new("pp", "Palmtree Panic");
+    /// assert_eq!("pp", segment.short.to_string());
+    /// assert_eq!("Palmtree Panic", segment.name);
+    /// ```
+    pub fn new(short: impl Into<short::Name>, name: &(impl ToString + ?Sized)) -> Self {
+        Segment {
+            short: short.into(),
+            name: name.to_string(),
+        }
+    }
+}
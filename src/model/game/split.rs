@@ -2,23 +2,22 @@
 
 use crate::model::short;
 
-/// Information about a segment.
+/// Information about a split.
 ///
-/// Records here correspond to entries in the `split` table.  (There is currently a terminology
-/// mismatch; we are phasing out the term 'split' in favour of 'segment'.)
+/// Records here correspond to entries in the `split` table.  Splits are grouped into
+/// [`super::Segment`]s.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Split {
-    /// The short name of this segment.
+    /// The short name of this split.
     pub short: short::Name,
-    /// The display name of this segment.
+    /// The display name of this split.
     pub name: String,
-    /// The nickname of this segment.
+    /// The nickname of this split.
     pub nickname: Option<String>,
-    // TODO(@MattWindsor91): segments
 }
 
 impl Split {
-    /// Constructs a new segment.
+    /// Constructs a new split.
     ///
     /// The split will initially have an empty nickname.
     ///
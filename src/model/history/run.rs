@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 /// Runs are parametric over category locators and time calculations.
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Run<L, T> {
-    /// The category_locator used to locate the game and category.
+    /// The `category_locator` used to locate the game and category.
     #[serde(flatten)]
     pub category_locator: L,
     /// Timing information for the run.
@@ -5,9 +5,15 @@ information about the timing of a historic run, and each implies an
 increasing amount of query complexity, there are several
 different historic time models used.
 */
-use std::fmt::Formatter;
+use std::fmt::{self, Formatter, Write};
 
-use super::super::{short, timing::time};
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+use super::super::{
+    short,
+    timing::time::{self, position, Position},
+};
 
 pub trait Timing {
     /// Gets the total across all splits.
@@ -16,8 +22,10 @@ pub trait Timing {
 
 /// Full timing information for a run.
 ///
-/// This includes every logged time for every split in the run.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// This includes every logged time for every split in the run.  In run files, the times are
+/// written in unit notation (as in `1h02m03s456`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "FullRepr", try_from = "FullRepr")]
 pub struct Full {
     pub times: short::Map<Vec<time::Time>>,
 }
@@ -28,6 +36,89 @@ impl Timing for Full {
     }
 }
 
+/// The serialised form of [Full].
+#[derive(Serialize, Deserialize)]
+struct FullRepr {
+    times: short::Map<Vec<UnitTime>>,
+}
+
+impl From<Full> for FullRepr {
+    fn from(full: Full) -> Self {
+        let times = full
+            .times
+            .into_iter()
+            .map(|(split, ts)| (split, ts.into_iter().map(UnitTime::from).collect()))
+            .collect();
+        Self { times }
+    }
+}
+
+/// A human time that serialises in unit notation, which, unlike the plain human notation, can't
+/// be mistaken for a number of milliseconds when parsed back.
+#[derive(SerializeDisplay, DeserializeFromStr)]
+struct UnitTime(time::human::Time);
+
+impl From<time::Time> for UnitTime {
+    fn from(time: time::Time) -> Self {
+        Self(time.into())
+    }
+}
+
+impl TryFrom<UnitTime> for time::Time {
+    type Error = time::Error;
+
+    fn try_from(time: UnitTime) -> Result<Self, Self::Error> {
+        time.0.try_into()
+    }
+}
+
+/// Leaves out any zero fields other than the milliseconds, as in `-2m03s456`.
+impl fmt::Display for UnitTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0.is_negative {
+            f.write_char('-')?;
+        }
+        [
+            Position::Hours,
+            Position::Minutes,
+            Position::Seconds,
+            Position::Milliseconds,
+        ]
+        .into_iter()
+        .try_for_each(|field| {
+            let value = self.0[field];
+            write!(f, "{}", position::Value { field, value })
+        })
+    }
+}
+
+impl std::str::FromStr for UnitTime {
+    type Err = time::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl TryFrom<FullRepr> for Full {
+    type Error = time::Error;
+
+    fn try_from(repr: FullRepr) -> Result<Self, Self::Error> {
+        let times = repr
+            .times
+            .into_iter()
+            .map(|(split, ts)| {
+                let ts = ts
+                    .into_iter()
+                    .map(time::Time::try_from)
+                    .collect::<Result<_, _>>()?;
+                Ok((split, ts))
+            })
+            .collect::<Result<_, Self::Error>>()?;
+        Ok(Self { times })
+    }
+}
+
 /// Split-total timing information for a run.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Totals {
@@ -58,9 +149,10 @@ impl Timing for Summary {
 /// Enumeration of the various timing levels.
 ///
 /// This is useful for presenting a choice of which timing level to get.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Level {
     /// Represents [Summary].
+    #[default]
     Summary,
     /// Represents [Totals].
     Totals,
@@ -81,12 +173,6 @@ impl std::str::FromStr for Level {
     }
 }
 
-impl Default for Level {
-    fn default() -> Self {
-        Self::Summary
-    }
-}
-
 impl std::fmt::Display for Level {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
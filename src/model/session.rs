@@ -10,17 +10,26 @@ This module contains the bulk of the model surface of the zombiesplit server, co
 */
 pub mod action;
 pub mod attempt;
+pub mod builder;
+mod error;
 pub mod event;
+pub mod journal;
+pub mod mirror;
 pub mod sink;
 pub mod split;
 pub mod state;
 
 use event::{split::Observer as SO, time::Observer as TO};
 
-use super::timing::{aggregate, comparison, comparison::provider, time, Comparison};
+use super::{
+    short,
+    timing::{aggregate, comparison, comparison::provider, time},
+};
 
 pub use action::Action;
 pub use attempt::Attempt;
+pub use builder::Builder;
+pub use error::Error;
 pub use event::{observer::Observer, Event};
 pub use sink::Sink;
 pub use split::Split;
@@ -48,55 +57,62 @@ pub struct Session<'cmp, 'obs, O> {
     // TODO(@MattWindsor91): refactor those into a separate struct?
     /// The sink attached to the session, for emitting saved runs.
     sink: Box<dyn Sink>,
+    /// Runs that failed to save, oldest first, kept so that saving them can be retried.
+    unsaved: Vec<sink::Run>,
     /// The comparison provider.
     comparator: Box<dyn comparison::Provider + 'cmp>,
+    /// The options with which the session was built.
+    options: builder::Options,
 }
 
-impl<'cmp, 'obs, O: Observer> action::Handler for Session<'cmp, 'obs, O> {
-    // This error may change later.
-    type Error = std::convert::Infallible;
+impl<O: Observer> action::Handler for Session<'_, '_, O> {
+    type Error = Error;
 
     fn dump(&mut self) -> Result<State, Self::Error> {
-        Ok(self.state.clone())
+        Ok(self.state().clone())
     }
 
-    fn handle(&mut self, action: Action) -> Result<(), Self::Error> {
+    fn handle(&mut self, action: Action) -> Result<Option<sink::Outcome>, Self::Error> {
         match action {
-            Action::NewRun(dest) => self.reset(dest),
+            Action::NewRun(dest) => return self.reset(dest),
+            Action::RetrySave => return self.retry_saves(),
             Action::Pop(s, action::Pop::One) => self.pop_from(s),
             Action::Pop(s, action::Pop::All) => self.clear_at(s),
             Action::Push(s, t) => self.push_to(s, t),
-        };
-        Ok(())
+        }
+        Ok(None)
     }
 }
 
 impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
-    /// Starts a new session with a given set of metadata and starting run.
+    /// Starts building a session on `run`, observed by `observer`.
+    ///
+    /// See [Builder] for the other parts of a session that can be configured.
     #[must_use]
-    pub fn new(run: Attempt, observer: &'obs O) -> Self {
-        Self {
-            state: State::new(run, Comparison::default()),
-            observer,
-            sink: Box::new(sink::Null),
-            timestamper: chrono::Utc::now,
-            comparator: Box::new(provider::Null),
-        }
+    pub fn builder(run: Attempt, observer: &'obs O) -> Builder<'cmp, 'obs, O> {
+        Builder::new(run, observer)
     }
 
-    // TODO(@MattWindsor91): replace these 'set_' functions with a builder.
-
-    /// Replaces the session's timestamper with a different function.
+    /// Gets the current state of the session.
     ///
-    /// Useful for stubbing out time when testing.
-    pub fn set_timestamper(&mut self, ts: fn() -> chrono::DateTime<chrono::Utc>) {
-        self.timestamper = ts;
+    /// Unlike [`action::Handler::dump`], this borrows the state rather than cloning it, and can't
+    /// fail.
+    #[must_use]
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Gets the options with which the session was built.
+    #[must_use]
+    pub fn options(&self) -> &builder::Options {
+        &self.options
     }
 
-    /// Replaces the session's comparison provider with a different one.
+    /// Replaces the session's comparison provider with a different one, such as when the
+    /// comparison configuration changes while the session is running.
     ///
-    /// By default, the session doesn't have comparisons set up, so this will
-    /// need to be done to get comparisons working.
+    /// To set up the comparison provider when creating the session, use
+    /// [`Builder::comparison_provider`] instead.
     ///
     /// Triggers an immediate comparison reset.
     pub fn set_comparison_provider(&mut self, p: Box<dyn provider::Provider + 'cmp>) {
@@ -104,24 +120,18 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         self.refresh_comparison();
     }
 
-    /// Replaces the session's run sink with a different one.
-    ///
-    /// By default, the session doesn't have comparisons set up, so this will
-    /// need to be done to get comparisons working.
-    pub fn set_sink(&mut self, s: Box<dyn Sink>) {
-        self.sink = s;
-    }
-
     /// Asks the comparison provider for an updated comparison.
     ///
-    /// This should occur when the run is reset, in case the outgoing run has
-    /// changed the comparisons.
-    fn refresh_comparison(&mut self) {
+    /// This happens when the run is reset, in case the outgoing run has changed the comparisons,
+    /// but should also happen if a sink saves the outgoing run only after the reset.
+    pub fn refresh_comparison(&mut self) {
         // TODO(@MattWindsor91): abort on error?
         match self.comparator.comparison() {
             Ok(Some(c)) => {
-                self.state.comparison = c;
+                let notes = self.state.set_comparison(c);
                 self.observe_comparison();
+                // Deltas against the old comparison are now stale.
+                self.observe_notes(notes);
             }
             Ok(None) => {}
             Err(e) => {
@@ -142,21 +152,14 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         self.observer.observe(Event::Reset(self.state.attempt.info));
     }
 
-    /// Observes notes for each split, notifying all observers.
+    /// Observes the notes that changed after a recalculation, followed by the new attempt total.
     ///
-    /// The notes will have been recalculated by the state before this is called.
-    fn observe_notes(&self) {
-        // TODO(@MattWindsor91): start from a particular split, to avoid redundancy?
-        for (short, note) in &self.state.notes {
-            if let Some(d) = note.delta {
-                self.observer
-                    .observe_split(*short, event::split::Split::Delta(d));
-            }
-            self.observer.observe_aggregate_set(
-                *short,
-                note.aggregates,
-                aggregate::Source::Attempt,
-            );
+    /// The notes will have been recalculated by the state before this is called.  They go out as
+    /// one batch, so that a change near the start of a long run doesn't flood observers with an
+    /// event per split.
+    fn observe_notes(&self, notes: event::Notes) {
+        if !notes.is_empty() {
+            self.observer.observe(Event::Notes(notes));
         }
 
         let delta = self.state.total.map(|x| x.delta).unwrap_or_default();
@@ -165,6 +168,22 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
             .observe(Event::Total(event::Total::Attempt(delta), time));
     }
 
+    /// Observes a set of aggregates `set` for segment `segment`, from source `source`.
+    fn observe_segment_aggregate_set(
+        &self,
+        segment: short::Name,
+        set: aggregate::Set,
+        source: aggregate::Source,
+    ) {
+        for scope in [aggregate::Scope::Split, aggregate::Scope::Cumulative] {
+            let event = event::Time::Aggregate(source.with(scope));
+            self.observer.observe(Event::Segment(
+                segment,
+                event::split::Split::Time(set[scope], event),
+            ));
+        }
+    }
+
     /// Observes the contents of a comparison.
     ///
     /// This lets the user interface know, for each splits, which times we are
@@ -172,6 +191,7 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
     fn observe_comparison(&self) {
         self.observe_comparison_run();
         self.observe_comparison_splits();
+        self.observe_comparison_segments();
     }
 
     /// Observes comparison data for the run as a whole.
@@ -193,50 +213,200 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         }
     }
 
-    fn reset(&mut self, dest: action::OldDestination) {
-        self.handle_last_attempt(dest);
+    /// Observes comparison data for each segment in the run.
+    fn observe_comparison_segments(&self) {
+        for segment in self.state.attempt.splits.segments() {
+            let short = segment.info.short;
+            if let Some(s) = self.state.comparison.segment_aggregate_for(short) {
+                self.observe_segment_aggregate_set(short, *s, aggregate::Source::Comparison);
+            }
+        }
+    }
+
+    /// Records the result of saving `run`, returning the outcome.
+    ///
+    /// Observers hear about every result, and runs that failed to save are kept for retrying.
+    /// Sinks that save runs later, having reported [`sink::Outcome::Queued`] at first, should have
+    /// their eventual results passed in here too.
+    ///
+    /// # Errors
+    ///
+    /// Fails, with the sink's error, if `result` is a failure.
+    pub fn finish_save(
+        &mut self,
+        run: sink::Run,
+        result: sink::Result,
+    ) -> Result<sink::Outcome, Error> {
+        self.observer
+            .observe(Event::Save(event::Save::from(&result)));
+        result.map_err(|e| {
+            log::warn!("couldn't save run: {e}");
+            self.unsaved.push(run);
+            Error::from(e)
+        })
+    }
+
+    fn reset(&mut self, dest: action::OldDestination) -> Result<Option<sink::Outcome>, Error> {
+        let result = self.handle_last_attempt(dest);
         self.state.reset(dest);
         // Important that this happens AFTER the session is reset, so the new attempt info is sent.
         self.observe_reset();
         self.refresh_comparison();
+        result
     }
 
-    fn handle_last_attempt(&mut self, dest: action::OldDestination) {
+    fn handle_last_attempt(
+        &mut self,
+        dest: action::OldDestination,
+    ) -> Result<Option<sink::Outcome>, Error> {
         match dest {
             action::OldDestination::Save => self.send_run_to_sink(),
-            action::OldDestination::Discard => (),
+            action::OldDestination::Discard => Ok(None),
         }
     }
 
-    fn send_run_to_sink(&mut self) {
-        if let Some(r) = self.state.attempt.as_historic((self.timestamper)()) {
-            if let Err(e) = self.sink.accept(r) {
-                log::warn!("couldn't save run: {e}");
+    fn send_run_to_sink(&mut self) -> Result<Option<sink::Outcome>, Error> {
+        self.state
+            .attempt
+            .as_historic((self.timestamper)())
+            .map(|r| self.save(r))
+            .transpose()
+    }
+
+    fn save(&mut self, run: sink::Run) -> Result<sink::Outcome, Error> {
+        let result = self.sink.accept(run.clone());
+        self.finish_save(run, result)
+    }
+
+    /// Sends each run that failed to save back to the sink, returning the last outcome.
+    ///
+    /// Retrying stops at the first run that fails again, leaving it and any later runs retained.
+    fn retry_saves(&mut self) -> Result<Option<sink::Outcome>, Error> {
+        let mut outcome = None;
+        let mut pending = std::mem::take(&mut self.unsaved).into_iter();
+        for run in pending.by_ref() {
+            match self.save(run) {
+                Ok(o) => outcome = Some(o),
+                Err(e) => {
+                    self.unsaved.extend(pending);
+                    return Err(e);
+                }
             }
         }
+
+        if outcome == Some(sink::Outcome::Saved) {
+            self.refresh_comparison();
+        }
+        Ok(outcome)
     }
 
     fn clear_at(&mut self, split: impl split::Locator) {
-        if let Some(short) = self.state.clear_at(split) {
+        if let Some((short, notes)) = self.state.clear_at(split) {
             self.observer
                 .observe_split(short, event::split::Split::Popped(action::Pop::All));
-            self.observe_notes();
+            self.observe_notes(notes);
         }
     }
 
     /// Pushes a time to a split located by `split`.
     pub fn push_to(&mut self, split: impl split::Locator, time: time::Time) {
-        if let Some(short) = self.state.push_to(split, time) {
+        if let Some((short, notes)) = self.state.push_to(split, time) {
             self.observer.observe_time(short, time, event::Time::Pushed);
-            self.observe_notes();
+            self.observe_notes(notes);
         }
     }
 
     fn pop_from(&mut self, split: impl split::Locator) {
-        if let Some(short) = self.state.pop_from(split) {
+        if let Some((short, notes)) = self.state.pop_from(split) {
             self.observer
                 .observe_split(short, event::split::Split::Popped(action::Pop::One));
-            self.observe_notes();
+            self.observe_notes(notes);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{action::Handler, *};
+    use crate::model::game;
+    use std::sync::Mutex;
+
+    /// A sink that fails to save the first `failures` runs it is given.
+    struct FlakySink {
+        failures: usize,
+    }
+
+    impl Sink for FlakySink {
+        fn accept(&mut self, _run: sink::Run) -> sink::Result {
+            if self.failures == 0 {
+                Ok(sink::Outcome::Saved)
+            } else {
+                self.failures -= 1;
+                Err(anyhow::anyhow!("database is locked").into())
+            }
+        }
+    }
+
+    /// An observer that logs save events.
+    #[derive(Default)]
+    struct SaveLogger {
+        log: Mutex<Vec<event::Save>>,
+    }
+
+    impl SaveLogger {
+        fn last(&self) -> Option<event::Save> {
+            let log = self.log.lock().expect("couldn't lock log");
+            log.last().cloned()
+        }
+    }
+
+    impl Observer for SaveLogger {
+        fn observe(&self, evt: Event) {
+            if let Event::Save(save) = evt {
+                let mut log = self.log.lock().expect("couldn't lock log");
+                log.push(save);
+            }
+        }
+    }
+
+    fn attempt() -> Attempt {
+        Attempt {
+            category: game::category::Target::default(),
+            info: game::category::AttemptInfo::default(),
+            splits: [game::Split::new("s1", "Split 1")].into_iter().collect(),
+        }
+    }
+
+    /// Tests that a run that fails to save is reported, kept, and can be saved on retrying.
+    #[test]
+    fn retry_save() {
+        let obs = SaveLogger::default();
+        let mut s = Session::builder(attempt(), &obs)
+            .sink(Box::new(FlakySink { failures: 2 }))
+            .build()
+            .expect("couldn't build session");
+
+        s.handle(Action::Push(0, time::Time::from_millis(25_060)))
+            .expect("push shouldn't fail");
+        assert!(s
+            .handle(Action::NewRun(action::OldDestination::Save))
+            .is_err());
+        assert!(matches!(obs.last(), Some(event::Save::Failed(_))));
+        assert_eq!(1, s.unsaved.len());
+
+        // Failing again keeps the run for another retry.
+        assert!(s.handle(Action::RetrySave).is_err());
+        assert_eq!(1, s.unsaved.len());
+
+        let outcome = s.handle(Action::RetrySave).expect("retry should succeed");
+        assert_eq!(Some(sink::Outcome::Saved), outcome);
+        assert_eq!(
+            Some(event::Save::Accepted(sink::Outcome::Saved)),
+            obs.last()
+        );
+
+        // Nothing is left to retry.
+        let outcome = s.handle(Action::RetrySave).expect("retry should succeed");
+        assert_eq!(None, outcome);
+    }
+}
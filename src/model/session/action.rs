@@ -2,10 +2,11 @@
 An event interface for manipulating a current attempt.
 */
 
-use super::super::timing::time;
+use super::{super::timing::time, sink};
+use serde::{Deserialize, Serialize};
 
 /// An event that manipulates the current session.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum Action {
     /// Start a new run.
@@ -14,10 +15,12 @@ pub enum Action {
     Push(usize, time::Time),
     /// Pops one or more times from the split at the given position.
     Pop(usize, Pop),
+    /// Tries again to save any runs that previously failed to save.
+    RetrySave,
 }
 
 /// What should we do with an old attempt when we start a new one?
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum OldDestination {
     /// Save the old attempt.
@@ -27,7 +30,7 @@ pub enum OldDestination {
 }
 
 /// Type of pop used in pop actions.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum Pop {
     /// Pop one time.
@@ -53,12 +56,14 @@ pub trait Handler {
     /// Fails if we can't, for whatever reason, get a dump from the handler.
     fn dump(&mut self) -> Result<super::State, Self::Error>;
 
-    // TODO(@MattWindsor91): errors?
-
     /// Performs the action `a`.
     ///
+    /// If the action sent a run to be saved (as starting a new run with [`OldDestination::Save`]
+    /// does), returns what the sink did with the run.
+    ///
     /// # Errors
     ///
-    /// Fails if we can't, for whatever reason, perform the action.
-    fn handle(&mut self, a: Action) -> Result<(), Self::Error>;
+    /// Fails if we can't, for whatever reason, perform the action, or if a run sent to be saved
+    /// failed to save.  In the latter case, the rest of the action still takes effect.
+    fn handle(&mut self, a: Action) -> Result<Option<sink::Outcome>, Self::Error>;
 }
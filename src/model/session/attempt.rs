@@ -41,13 +41,14 @@ impl Attempt {
         short: category::ShortDescriptor,
     ) -> config::Result<Self> {
         let category = game.category(short.category)?;
-        let splits: split::Set = split::Set::from_config(game, category)?;
+        let splits = split::Set::from_config(game, category)?.with_frame_rate(game.frame_rate);
 
         Ok(Self {
             category: category::Target {
                 game: game.name.clone(),
                 category: category.name.clone(),
                 short,
+                frame_rate: game.frame_rate,
             },
             // TODO: indeterminate attempt information
             info: category::AttemptInfo::default(),
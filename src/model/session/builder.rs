@@ -0,0 +1,244 @@
+//! Builders for sessions.
+
+use thiserror::Error;
+
+use super::{
+    super::{
+        short,
+        timing::{comparison::provider, Comparison},
+    },
+    sink, Attempt, Observer, Session, State,
+};
+
+/// A builder for [Session]s.
+///
+/// Every session needs an attempt and an observer; everything else has a default, so that (for
+/// instance) a session with no sink discards runs rather than saving them.  Nothing reaches the
+/// observer until [`Builder::build`], which checks that the configuration makes sense.
+///
+/// # Examples
+///
+/// ```
+/// use zombiesplit::model::{
+///     game::{self, category},
+///     session::{builder, event::observer::Null, sink, Attempt, Session},
+/// };
+///
+/// let mut attempt = Attempt {
+///     category: category::Target::default(),
+///     info: category::AttemptInfo::default(),
+///     splits: [game::Split::new("pp1", "Palmtree Panic 1")].into_iter().collect(),
+/// };
+/// let session = Session::builder(attempt.clone(), &Null)
+///     .sink(Box::new(sink::Null))
+///     .build()
+///     .expect("this session should be valid");
+/// assert_eq!(1, session.state().attempt.splits.len());
+///
+/// let too_deep = Session::builder(attempt.clone(), &Null).undo_depth(builder::MAX_UNDO_DEPTH + 1);
+/// assert!(matches!(too_deep.build(), Err(builder::Error::UndoTooDeep(_))));
+///
+/// attempt.splits = std::iter::empty::<game::Split>().collect();
+/// assert!(matches!(
+///     Session::builder(attempt, &Null).build(),
+///     Err(builder::Error::NoSplits)
+/// ));
+/// ```
+pub struct Builder<'cmp, 'obs, O> {
+    /// The attempt with which the session starts.
+    attempt: Attempt,
+    /// The observer to attach to the session.
+    observer: &'obs O,
+    /// The sink for runs, if one has been given.
+    sink: Option<Box<dyn sink::Sink>>,
+    /// The comparison providers, in the order in which they should be tried.
+    providers: Vec<Box<dyn provider::Provider + 'cmp>>,
+    /// The function for timestamping outgoing runs.
+    timestamper: fn() -> chrono::DateTime<chrono::Utc>,
+    /// Everything else about the session.
+    options: Options,
+}
+
+/// Options for how a session behaves, beyond what it is wired up to.
+///
+/// Some of these are placeholders for features the session doesn't have yet; they are accepted
+/// and validated now so that configuration can mention them without every caller changing later.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Options {
+    /// The number of actions the session should be able to undo.
+    ///
+    /// Sessions can't undo actions yet, so this is only recorded.
+    pub undo_depth: usize,
+    /// Where the session's split times come from.
+    pub timer: TimerSource,
+}
+
+/// Where a session's split times come from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimerSource {
+    /// Clients time splits themselves and push the times into the session.
+    #[default]
+    Manual,
+}
+
+/// The largest undo depth a session accepts.
+///
+/// Each level of undo will need to keep enough to reverse an action, so this stops a typo in
+/// configuration from reserving memory without bound.
+pub const MAX_UNDO_DEPTH: usize = 1024;
+
+impl<'cmp, 'obs, O: Observer> Builder<'cmp, 'obs, O> {
+    /// Starts building a session on `attempt`, observed by `observer`.
+    #[must_use]
+    pub fn new(attempt: Attempt, observer: &'obs O) -> Self {
+        Self {
+            attempt,
+            observer,
+            sink: None,
+            providers: vec![],
+            timestamper: chrono::Utc::now,
+            options: Options::default(),
+        }
+    }
+
+    /// Sets the sink into which the session sends runs.
+    ///
+    /// By default, runs go into a [`sink::Null`], and so are never saved.
+    #[must_use]
+    pub fn sink(mut self, sink: Box<dyn sink::Sink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Adds a comparison provider.
+    ///
+    /// If this is called more than once, the session asks each provider in turn, and uses the
+    /// first comparison it finds.  By default, the session has no comparisons.
+    #[must_use]
+    pub fn comparison_provider(mut self, provider: Box<dyn provider::Provider + 'cmp>) -> Self {
+        self.providers.push(provider);
+        self
+    }
+
+    /// Sets the function used to timestamp outgoing runs.
+    ///
+    /// By default, runs are timestamped with the current time; replacing this is useful for
+    /// stubbing out time when testing.
+    #[must_use]
+    pub fn timestamper(mut self, timestamper: fn() -> chrono::DateTime<chrono::Utc>) -> Self {
+        self.timestamper = timestamper;
+        self
+    }
+
+    /// Sets the number of actions the session should be able to undo.
+    ///
+    /// By default, this is zero.  Sessions can't undo actions yet; see [`Options::undo_depth`].
+    #[must_use]
+    pub fn undo_depth(mut self, depth: usize) -> Self {
+        self.options.undo_depth = depth;
+        self
+    }
+
+    /// Sets where the session's split times come from.
+    ///
+    /// By default, and at present only, clients push times in manually.
+    #[must_use]
+    pub fn timer(mut self, timer: TimerSource) -> Self {
+        self.options.timer = timer;
+        self
+    }
+
+    /// Builds the session, fetching its first comparison.
+    ///
+    /// The observer hears about the comparison, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the attempt has no splits, or more than one split with the same short name; or if
+    /// the undo depth is above [`MAX_UNDO_DEPTH`].
+    pub fn build(mut self) -> Result<Session<'cmp, 'obs, O>> {
+        self.validate()?;
+
+        let comparator: Box<dyn provider::Provider + 'cmp> = match self.providers.len() {
+            0 => Box::new(provider::Null),
+            1 => self.providers.remove(0),
+            _ => Box::new(provider::Chain(self.providers)),
+        };
+        let mut session = Session {
+            state: State::new(self.attempt, Comparison::default()),
+            observer: self.observer,
+            timestamper: self.timestamper,
+            sink: self.sink.unwrap_or_else(|| Box::new(sink::Null)),
+            unsaved: Vec::new(),
+            comparator,
+            options: self.options,
+        };
+        session.refresh_comparison();
+        Ok(session)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.attempt.splits.is_empty() {
+            return Err(Error::NoSplits);
+        }
+        // Notes are keyed by short name, so duplicates would share notes.
+        let mut seen = std::collections::HashSet::new();
+        if let Some(split) = self
+            .attempt
+            .splits
+            .iter()
+            .find(|s| !seen.insert(s.info.short))
+        {
+            return Err(Error::DuplicateSplit(split.info.short));
+        }
+        if MAX_UNDO_DEPTH < self.options.undo_depth {
+            return Err(Error::UndoTooDeep(self.options.undo_depth));
+        }
+        Ok(())
+    }
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors that can occur when building a session.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The attempt has no splits, so there would be nothing to time.
+    #[error("the attempt has no splits")]
+    NoSplits,
+    /// The attempt has more than one split with the given short name.
+    #[error("the attempt has more than one split named {0}")]
+    DuplicateSplit(short::Name),
+    /// The requested undo depth is above [`MAX_UNDO_DEPTH`].
+    #[error("undo depth {0} is above the maximum of {MAX_UNDO_DEPTH}")]
+    UndoTooDeep(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{game, session::event::observer::Null};
+
+    /// Tests that attempts with two splits of the same name are rejected.
+    #[test]
+    fn duplicate_split() {
+        let attempt = Attempt {
+            category: game::category::Target::default(),
+            info: game::category::AttemptInfo::default(),
+            splits: [
+                game::Split::new("pp1", "Palmtree Panic 1"),
+                game::Split::new("pp1", "Palmtree Panic 1 (again)"),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        assert!(matches!(
+            Builder::new(attempt, &Null).build(),
+            Err(Error::DuplicateSplit(short)) if short == short::Name::from("pp1")
+        ));
+    }
+}
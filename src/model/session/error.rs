@@ -0,0 +1,14 @@
+//! Error types for sessions.
+
+use thiserror::Error;
+
+use super::sink;
+
+/// Errors that can occur while a session handles an action.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// A run couldn't be saved; the session has kept it so that saving can be retried.
+    #[error("couldn't save run: {0}")]
+    Save(#[from] sink::Error),
+}
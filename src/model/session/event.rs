@@ -1,21 +1,28 @@
 //! Observer pattern wiring for attempt sessions.
 
 pub mod debug;
+pub mod filter;
 pub mod mux;
 pub mod observer;
 pub mod split;
 pub mod time;
 
-use super::super::{game::category, short, timing};
+use super::{
+    super::{game::category, short, timing},
+    sink, state,
+};
+
+use serde::{Deserialize, Serialize};
 
 pub use debug::Debug;
+pub use filter::Filter;
 pub use mux::Mux;
 pub use observer::{Observable, Observer};
 pub use split::Split;
 pub use time::Time;
 
 /// Enumeration of events that can be sent through an observer.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum Event {
     /// Observes a change in one of the run total times.
@@ -24,10 +31,60 @@ pub enum Event {
     Reset(category::AttemptInfo),
     /// Observes an event on a split.
     Split(short::Name, Split),
+    /// Observes an event on the segment with the given short name.
+    ///
+    /// Segment events use the same payloads as split events, but segments never have times
+    /// pushed to or popped from them directly: only aggregates and deltas are observed.
+    Segment(short::Name, Split),
+    /// Observes what happened to a run sent to be saved.
+    Save(Save),
+    /// Observes recalculated notes for the splits and segments whose notes changed.
+    ///
+    /// Notes carry the attempt aggregates and deltas for each split and segment, and are sent in
+    /// one batch after each change to the attempt, rather than split by split.
+    Notes(Notes),
+}
+
+/// A batch of split and segment notes that have changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notes {
+    /// The new notes for each split whose notes changed.
+    pub splits: short::Map<state::SplitNote>,
+    /// The new notes for each segment whose notes changed.
+    pub segments: short::Map<state::SplitNote>,
+}
+
+impl Notes {
+    /// Gets whether no notes changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.splits.is_empty() && self.segments.is_empty()
+    }
+}
+
+/// What happened to a run sent to be saved.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Save {
+    /// The sink accepted the run, with the given outcome.
+    Accepted(sink::Outcome),
+    /// The sink couldn't save the run, for the given reason.
+    ///
+    /// The session keeps the run in memory, so that saving it can be retried.
+    Failed(String),
+}
+
+impl From<&sink::Result> for Save {
+    fn from(result: &sink::Result) -> Self {
+        match result {
+            Ok(outcome) => Self::Accepted(*outcome),
+            Err(e) => Self::Failed(e.to_string()),
+        }
+    }
 }
 
 /// Information about a type of total.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum Total {
     /// The total is the attempt total, and has the given delta from the comparison total.
@@ -0,0 +1,221 @@
+//! Filters for picking out the events an observer cares about.
+
+use std::collections::HashSet;
+
+use super::{
+    super::super::{short, timing::aggregate},
+    Event, Notes, Split, Time, Total,
+};
+
+/// A filter over events.
+///
+/// Each part of the filter is a set of things to let through; an empty set lets everything
+/// through, so the default filter allows every event.  An event passes the filter only if it
+/// passes every part.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// The kinds of event to allow.
+    pub kinds: HashSet<Kind>,
+    /// The short names of the splits and segments whose events we allow.
+    ///
+    /// Events not tied to a split, such as totals and resets, aren't affected by this part.
+    pub splits: HashSet<short::Name>,
+    /// The sources of aggregate times and totals to allow.
+    ///
+    /// Events that don't carry an aggregate time or total aren't affected by this part.
+    pub sources: HashSet<aggregate::Source>,
+}
+
+impl Filter {
+    /// Checks whether `event` passes this filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::{session::event::{filter, Event, Filter, Split, Time}, timing};
+    ///
+    /// let filter = Filter {
+    ///     kinds: [filter::Kind::Time].into_iter().collect(),
+    ///     splits: ["pp1".into()].into_iter().collect(),
+    ///     ..Filter::default()
+    /// };
+    /// let time = timing::time::Time::from_millis(1000);
+    ///
+    /// assert!(filter.allows(&Event::Split("pp1".into(), Split::Time(time, Time::Pushed))));
+    /// assert!(!filter.allows(&Event::Split("pp2".into(), Split::Time(time, Time::Pushed))));
+    /// assert!(!filter.allows(&Event::Split("pp1".into(), Split::Popped(
+    ///     zombiesplit::model::session::action::Pop::One
+    /// ))));
+    /// ```
+    #[must_use]
+    pub fn allows(&self, event: &Event) -> bool {
+        allows(&self.kinds, Some(Kind::of(event)))
+            && allows(&self.splits, split_of(event))
+            && allows(&self.sources, source_of(event))
+    }
+
+    /// Applies this filter to `event`, returning what, if anything, of it should be observed.
+    ///
+    /// This is like [`Filter::allows`], except that batches of notes are narrowed down to the
+    /// splits and segments this filter allows, and dropped if none are left.
+    #[must_use]
+    pub fn apply(&self, event: Event) -> Option<Event> {
+        let event = match event {
+            Event::Notes(notes) if !self.splits.is_empty() => {
+                let notes = Notes {
+                    splits: self.narrow(notes.splits),
+                    segments: self.narrow(notes.segments),
+                };
+                if notes.is_empty() {
+                    return None;
+                }
+                Event::Notes(notes)
+            }
+            event => event,
+        };
+        self.allows(&event).then_some(event)
+    }
+
+    fn narrow<T>(&self, notes: short::Map<T>) -> short::Map<T> {
+        notes
+            .into_iter()
+            .filter(|(name, _)| self.splits.contains(name))
+            .collect()
+    }
+
+    /// Checks whether this filter allows every event.
+    #[must_use]
+    pub fn allows_all(&self) -> bool {
+        self.kinds.is_empty() && self.splits.is_empty() && self.sources.is_empty()
+    }
+}
+
+fn allows<T: Eq + std::hash::Hash>(set: &HashSet<T>, item: Option<T>) -> bool {
+    set.is_empty() || item.into_iter().all(|x| set.contains(&x))
+}
+
+/// Gets the short name of the split or segment to which `event` belongs, if any.
+fn split_of(event: &Event) -> Option<short::Name> {
+    match event {
+        Event::Split(name, _) | Event::Segment(name, _) => Some(*name),
+        _ => None,
+    }
+}
+
+/// Gets the source of any aggregate time or total carried by `event`.
+fn source_of(event: &Event) -> Option<aggregate::Source> {
+    match event {
+        Event::Total(Total::Attempt(_), _) | Event::Notes(_) => Some(aggregate::Source::Attempt),
+        Event::Total(Total::Comparison(_), _) => Some(aggregate::Source::Comparison),
+        Event::Split(_, Split::Time(_, Time::Aggregate(kind)))
+        | Event::Segment(_, Split::Time(_, Time::Aggregate(kind))) => Some(kind.source),
+        _ => None,
+    }
+}
+
+/// Broad kinds of event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Kind {
+    /// Changes to run totals.
+    Total,
+    /// Resets.
+    Reset,
+    /// Times pushed to splits, and comparison aggregates for splits and segments.
+    Time,
+    /// Recalculated notes (attempt aggregates and deltas) for splits and segments.
+    Notes,
+    /// Times popped from splits.
+    Pop,
+    /// Outcomes of saving runs.
+    Save,
+}
+
+impl Kind {
+    /// Gets the kind of `event`.
+    #[must_use]
+    pub fn of(event: &Event) -> Self {
+        match event {
+            Event::Total(..) => Self::Total,
+            Event::Reset(_) => Self::Reset,
+            Event::Split(_, s) | Event::Segment(_, s) => match s {
+                Split::Time(..) => Self::Time,
+                Split::Popped(_) => Self::Pop,
+            },
+            Event::Save(_) => Self::Save,
+            Event::Notes(_) => Self::Notes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{session::action, timing::time};
+
+    fn time(source: aggregate::Source) -> Event {
+        Event::Segment(
+            "pp".into(),
+            Split::Time(
+                time::Time::from_millis(1000),
+                Time::Aggregate(source.with(aggregate::Scope::Cumulative)),
+            ),
+        )
+    }
+
+    /// Tests that the default filter allows everything.
+    #[test]
+    fn default_allows_all() {
+        let filter = Filter::default();
+        assert!(filter.allows_all());
+        assert!(filter.allows(&time(aggregate::Source::Attempt)));
+        assert!(filter.allows(&Event::Split("pp1".into(), Split::Popped(action::Pop::All))));
+    }
+
+    /// Tests that source filters only affect events with sources.
+    #[test]
+    fn sources() {
+        let filter = Filter {
+            sources: [aggregate::Source::Comparison].into_iter().collect(),
+            ..Filter::default()
+        };
+        assert!(!filter.allows_all());
+        assert!(filter.allows(&time(aggregate::Source::Comparison)));
+        assert!(!filter.allows(&time(aggregate::Source::Attempt)));
+        assert!(filter.allows(&Event::Split("pp1".into(), Split::Popped(action::Pop::All))));
+    }
+
+    /// Tests that split filters narrow batches of notes.
+    #[test]
+    fn narrows_notes() {
+        let filter = Filter {
+            splits: ["pp1".into()].into_iter().collect(),
+            ..Filter::default()
+        };
+        let note = crate::model::session::state::SplitNote::default();
+        let notes = |names: &[&str]| {
+            Event::Notes(Notes {
+                splits: names.iter().map(|n| ((*n).into(), note)).collect(),
+                segments: short::Map::default(),
+            })
+        };
+
+        assert_eq!(Some(notes(&["pp1"])), filter.apply(notes(&["pp1", "pp2"])));
+        assert_eq!(None, filter.apply(notes(&["pp2"])));
+    }
+
+    /// Tests that split filters let through events not tied to a split.
+    #[test]
+    fn splits() {
+        let filter = Filter {
+            splits: ["pp1".into()].into_iter().collect(),
+            ..Filter::default()
+        };
+        assert!(!filter.allows(&time(aggregate::Source::Attempt)));
+        assert!(filter.allows(&Event::Split("pp1".into(), Split::Popped(action::Pop::All))));
+        assert!(filter.allows(&Event::Total(
+            Total::Comparison(crate::model::timing::comparison::run::TotalType::SumOfBest),
+            None
+        )));
+    }
+}
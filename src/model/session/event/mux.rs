@@ -21,12 +21,15 @@ impl Observable for Mux {
 
 impl Observer for Mux {
     fn observe(&self, evt: super::Event) {
-        // TODO(@MattWindsor91): eliminate redundant clone
-        for o in &self.observers {
-            // TODO(@MattWindsor91): remove dead references
-            if let Some(o) = o.upgrade() {
+        // TODO(@MattWindsor91): remove dead references
+        let live: Vec<_> = self.observers.iter().filter_map(Weak::upgrade).collect();
+        // The last observer can take the event itself, saving a clone in the common case of
+        // only one observer.
+        if let Some((last, rest)) = live.split_last() {
+            for o in rest {
                 o.observe(evt.clone());
             }
+            last.observe(evt);
         }
     }
 }
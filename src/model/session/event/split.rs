@@ -1,17 +1,15 @@
 //! Split events in attempt observations.
-use crate::model::{
-    short,
-    timing::{comparison::delta, time},
-};
+use crate::model::{short, timing::time};
+use serde::{Deserialize, Serialize};
 
 /// Enumeration of split-level events.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// Attempt aggregates and deltas for splits arrive in batches, as [`super::Event::Notes`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum Split {
-    /// Got a new time pushed to, or aggregated for, the split.
+    /// Got a new time pushed to, or comparison aggregate for, the split.
     Time(time::Time, super::Time),
-    /// Got a new delta for the split.
-    Delta(delta::Split),
     /// One or more times have been popped from the split.
     Popped(super::super::action::Pop),
 }
@@ -0,0 +1,266 @@
+/*! Journals of the actions sent to a session, and the events they caused.
+
+A journal is a file of JSON lines, each an [Entry] recording an action, when the session handled
+it, and the events it produced in response.  A [Recorder] writes a journal as a session runs, and
+[replay] feeds a journal's actions into a fresh session, checking that they produce the same
+events.
+
+Journals help track down bugs where clients and the server disagree about the state of a session:
+a journal that replays cleanly gives a reproducible test case for the disagreement, and one that
+doesn't points at the session itself behaving nondeterministically. */
+
+use std::{
+    io::{BufRead, Write},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    super::game, action::Handler, builder, event, Action, Attempt, Builder, Event, Observer,
+};
+
+/// An entry in a journal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When the session handled the action.
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// The action, or `None` if the events happened outside of any action (for instance, when a
+    /// run finishes saving in the background, or the comparison changes).
+    pub action: Option<Action>,
+    /// The events the session produced, in order.
+    pub events: Vec<Event>,
+}
+
+/// An observer that keeps every event it sees, until they are taken.
+#[derive(Debug, Default)]
+pub struct Tape(Mutex<Vec<Event>>);
+
+impl Tape {
+    /// Takes every event seen since the last time events were taken.
+    pub fn take(&self) -> Vec<Event> {
+        std::mem::take(&mut *lock(&self.0))
+    }
+}
+
+impl Observer for Tape {
+    fn observe(&self, evt: Event) {
+        lock(&self.0).push(evt);
+    }
+}
+
+/// An observer that writes a journal to `W`.
+///
+/// The recorder collects events as it observes them; its owner must call [`Recorder::record`]
+/// after each action to write those events out, along with the action that caused them.
+#[derive(Debug)]
+pub struct Recorder<W> {
+    /// The events observed since the last entry was written.
+    tape: Tape,
+    /// The writer to which entries go.
+    out: Mutex<W>,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Constructs a recorder writing entries to `out`.
+    #[must_use]
+    pub fn new(out: W) -> Self {
+        Self {
+            tape: Tape::default(),
+            out: Mutex::new(out),
+        }
+    }
+
+    /// Writes an entry for `action`, with every event observed since the last entry.
+    ///
+    /// Entries with no action are only written if there were events.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the entry can't be serialised or written.
+    pub fn record(&self, action: Option<Action>) -> Result<()> {
+        let events = self.tape.take();
+        if action.is_none() && events.is_empty() {
+            return Ok(());
+        }
+        let entry = Entry {
+            at: chrono::Utc::now(),
+            action,
+            events,
+        };
+
+        let mut out = lock(&self.out);
+        serde_json::to_writer(&mut *out, &entry)?;
+        writeln!(out)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Consumes this recorder, returning its writer.
+    pub fn into_inner(self) -> W {
+        self.out
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Recorder<std::fs::File> {
+    /// Opens a recorder appending entries to the journal file at `path`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be opened.
+    pub fn create(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self::new(file))
+    }
+}
+
+impl<W> Observer for Recorder<W> {
+    fn observe(&self, evt: Event) {
+        self.tape.observe(evt);
+    }
+}
+
+/// Reads every entry in the journal `reader`.
+///
+/// # Errors
+///
+/// Fails if the journal can't be read, or any line isn't a valid entry.
+pub fn read(reader: impl BufRead) -> Result<Vec<Entry>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(|(index, line)| {
+            serde_json::from_str(&line?).map_err(|source| Error::Parse {
+                line: index + 1,
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Replays the actions in `entries` into a fresh session on `attempt`, checking that each action
+/// produces the events recorded for it.
+///
+/// Before replaying, `setup` can add comparison providers, sinks, and so on to the session's
+/// builder, to match the session that was recorded; events arising from building the session
+/// aren't checked.  Entries without actions aren't replayed, as nothing in the journal says how
+/// to reproduce them.  Events are compared as seen through `filter`, so that events that can't
+/// be reproduced (such as save outcomes from a sink that is no longer around) can be left out.
+///
+/// Returns the number of actions replayed.
+///
+/// # Errors
+///
+/// Fails if the session can't be built, or with [`Error::Mismatch`] at the first action that
+/// doesn't produce the recorded events.
+pub fn replay<'cmp>(
+    attempt: Attempt,
+    entries: impl IntoIterator<Item = Entry>,
+    filter: &event::Filter,
+    setup: impl for<'tape> FnOnce(Builder<'cmp, 'tape, Tape>) -> Builder<'cmp, 'tape, Tape>,
+) -> Result<usize> {
+    let tape = Tape::default();
+    let mut session = setup(Builder::new(attempt, &tape)).build()?;
+    let _ = tape.take();
+
+    let mut count = 0;
+    for (index, entry) in entries.into_iter().enumerate() {
+        let Some(action) = entry.action else {
+            continue;
+        };
+        // Failed saves show up as events, which are checked like any other.
+        let _ = session.handle(action);
+
+        let expected = apply(filter, entry.events);
+        let actual = apply(filter, tape.take());
+        if expected != actual {
+            return Err(Error::Mismatch {
+                entry: index,
+                action,
+                expected,
+                actual,
+            });
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Like [replay], but replays into a fresh session on the category `category` of `game`.
+///
+/// This saves building the attempt by hand when the journal was recorded against a game config
+/// rather than a database; see [`Attempt::from_config`].
+///
+/// # Errors
+///
+/// Fails if `game` doesn't describe `category`, or for any of the reasons [replay] can fail.
+pub fn replay_config<'cmp>(
+    game: &game::Config,
+    category: game::category::ShortDescriptor,
+    entries: impl IntoIterator<Item = Entry>,
+    filter: &event::Filter,
+    setup: impl for<'tape> FnOnce(Builder<'cmp, 'tape, Tape>) -> Builder<'cmp, 'tape, Tape>,
+) -> Result<usize> {
+    let attempt = Attempt::from_config(game, category)?;
+    replay(attempt, entries, filter, setup)
+}
+
+fn apply(filter: &event::Filter, events: Vec<Event>) -> Vec<Event> {
+    events.into_iter().filter_map(|e| filter.apply(e)).collect()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors that can occur when recording or replaying journals.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Couldn't read or write the journal.
+    #[error("couldn't access journal")]
+    Io(#[from] std::io::Error),
+    /// Couldn't build the attempt into which to replay the journal from game config.
+    #[error("couldn't build attempt for replay")]
+    Config(#[from] game::config::Error),
+    /// Couldn't build the session into which to replay the journal.
+    #[error("couldn't build session for replay")]
+    Build(#[from] builder::Error),
+    /// Couldn't serialise an entry.
+    #[error("couldn't serialise journal entry")]
+    Serialise(#[from] serde_json::Error),
+    /// A line of the journal wasn't a valid entry.
+    #[error("invalid journal entry on line {line}")]
+    Parse {
+        /// The line number, from 1.
+        line: usize,
+        /// The underlying parse error.
+        source: serde_json::Error,
+    },
+    /// Replaying an action produced different events from those recorded.
+    #[error(
+        "entry {entry} ({action:?}) replayed differently: expected {expected:?}, got {actual:?}"
+    )]
+    Mismatch {
+        /// The position of the entry in the journal, from 0.
+        entry: usize,
+        /// The action that was replayed.
+        action: Action,
+        /// The events recorded for the action.
+        expected: Vec<Event>,
+        /// The events the action produced on replay.
+        actual: Vec<Event>,
+    },
+}
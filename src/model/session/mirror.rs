@@ -0,0 +1,251 @@
+/*! Client-side mirrors of session state.
+
+A client that wants to know the full state of a session, rather than just react to its events,
+can start from a dump of the state and apply each event it observes afterwards.  A [Mirror] does
+this, so that clients don't each need their own copy of the logic for wiping the attempt on a
+reset, pushing and popping times, and storing aggregates and notes.
+
+Events don't carry everything in the state.  Personal bests in the comparison only ever arrive
+through dumps, so the mirror keeps whatever the last dump had for them; nor does anything tell the
+mirror when a new comparison drops a split.  To catch the mirror drifting from the server,
+[`Mirror::check`] compares it against a fresh dump, looking only at the parts of the state that
+events keep up to date.
+*/
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use thiserror::Error;
+
+use super::{
+    super::{
+        short,
+        timing::{aggregate, comparison},
+    },
+    action, event, Event, Observer, State,
+};
+
+/// An observer that keeps a copy of a session's state up to date from its events.
+#[derive(Debug)]
+pub struct Mirror(Mutex<State>);
+
+impl Mirror {
+    /// Constructs a mirror starting from `dump`, the state of the session as last dumped.
+    ///
+    /// The mirror should be observing the session from the moment `dump` was taken; any events
+    /// it misses will leave it out of date until the next [`Mirror::resync`].
+    #[must_use]
+    pub fn new(dump: State) -> Self {
+        Self(Mutex::new(dump))
+    }
+
+    /// Borrows the mirrored state.
+    ///
+    /// The mirror can't apply events while the state is borrowed, so the borrow should be short.
+    pub fn state(&self) -> MutexGuard<'_, State> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replaces the mirrored state with `dump`, for instance after missing events.
+    pub fn resync(&self, dump: State) {
+        *self.state() = dump;
+    }
+
+    /// Compares the mirrored state against `dump`, a fresh dump of the session's state.
+    ///
+    /// Returns every part of the state in which the two differ, so an empty result means the
+    /// mirror is consistent with the session.
+    #[must_use]
+    pub fn check(&self, dump: &State) -> Vec<Mismatch> {
+        check(&self.state(), dump)
+    }
+}
+
+impl Observer for Mirror {
+    fn observe(&self, evt: Event) {
+        apply(&mut self.state(), evt);
+    }
+}
+
+/// Applies `event` to `state`, as the session that sent it would have.
+///
+/// Events for splits and segments that aren't in `state` are ignored, except for aggregates and
+/// notes, which are stored anyway.
+pub fn apply(state: &mut State, event: Event) {
+    match event {
+        Event::Total(event::Total::Attempt(delta), time) => {
+            state.total = time.map(|time| comparison::delta::Time { delta, time });
+        }
+        Event::Total(event::Total::Comparison(ty), time) => state.comparison.run[ty] = time,
+        Event::Reset(info) => reset(state, info),
+        Event::Split(short, split) => apply_split(state, short, &split),
+        Event::Segment(short, event::Split::Time(time, event::Time::Aggregate(kind))) => {
+            let set = match kind.source {
+                aggregate::Source::Attempt => {
+                    &mut state.segment_notes.entry(short).or_default().aggregates
+                }
+                aggregate::Source::Comparison => {
+                    &mut state
+                        .comparison
+                        .segments
+                        .entry(short)
+                        .or_default()
+                        .in_pb_run
+                }
+            };
+            set[kind.scope] = time;
+        }
+        // Segments never have times pushed or popped.
+        Event::Segment(..) | Event::Save(_) => {}
+        Event::Notes(notes) => {
+            state.notes.extend(notes.splits);
+            state.segment_notes.extend(notes.segments);
+        }
+    }
+}
+
+/// Block-wipes `state` for a new attempt with information `info`.
+///
+/// This has to agree with [`State::reset`], which we can't use directly as the new attempt
+/// information comes from the server rather than from incrementing the old information.
+fn reset(state: &mut State, info: super::super::game::category::AttemptInfo) {
+    state.attempt.info = info;
+    state.attempt.splits.reset();
+    for note in state
+        .notes
+        .values_mut()
+        .chain(state.segment_notes.values_mut())
+    {
+        *note = super::state::SplitNote::default();
+    }
+    state.total = None;
+}
+
+fn apply_split(state: &mut State, short: short::Name, split: &event::Split) {
+    match *split {
+        event::Split::Time(time, event::Time::Pushed) => {
+            if let Some(s) = state.attempt.splits.get_mut(short) {
+                s.push(time);
+            }
+        }
+        event::Split::Time(time, event::Time::Aggregate(kind)) => {
+            let set = match kind.source {
+                aggregate::Source::Attempt => &mut state.notes.entry(short).or_default().aggregates,
+                aggregate::Source::Comparison => {
+                    &mut state.comparison.splits.entry(short).or_default().in_pb_run
+                }
+            };
+            set[kind.scope] = time;
+        }
+        event::Split::Popped(pop) => {
+            if let Some(s) = state.attempt.splits.get_mut(short) {
+                match pop {
+                    action::Pop::One => {
+                        let _ = s.pop();
+                    }
+                    action::Pop::All => s.clear(),
+                }
+            }
+        }
+    }
+}
+
+/// Finds every part of `dump` that `mirror` doesn't agree with.
+fn check(mirror: &State, dump: &State) -> Vec<Mismatch> {
+    let mut result = vec![];
+
+    if mirror.attempt.info != dump.attempt.info {
+        result.push(Mismatch::Info);
+    }
+    for split in dump.attempt.splits.iter() {
+        let short = split.info.short;
+        let times = mirror.attempt.splits.get(short).map(|s| &s.times);
+        if times != Some(&split.times) {
+            result.push(Mismatch::Times(short));
+        }
+    }
+
+    check_map(&mirror.notes, &dump.notes, &mut result, Mismatch::Note);
+    check_map(
+        &mirror.segment_notes,
+        &dump.segment_notes,
+        &mut result,
+        Mismatch::SegmentNote,
+    );
+    if mirror.total != dump.total {
+        result.push(Mismatch::Total);
+    }
+
+    let aggregates = |c: &comparison::Comparison| -> short::Map<aggregate::Set> {
+        c.splits.iter().map(|(k, v)| (*k, v.in_pb_run)).collect()
+    };
+    let segment_aggregates = |c: &comparison::Comparison| -> short::Map<aggregate::Set> {
+        c.segments.iter().map(|(k, v)| (*k, v.in_pb_run)).collect()
+    };
+    check_map(
+        &aggregates(&mirror.comparison),
+        &aggregates(&dump.comparison),
+        &mut result,
+        Mismatch::Comparison,
+    );
+    check_map(
+        &segment_aggregates(&mirror.comparison),
+        &segment_aggregates(&dump.comparison),
+        &mut result,
+        Mismatch::SegmentComparison,
+    );
+    for (ty, time) in dump.comparison.run.totals() {
+        if mirror.comparison.run[ty] != time {
+            result.push(Mismatch::ComparisonTotal(ty));
+        }
+    }
+
+    result
+}
+
+/// Pushes a `mismatch` for every key on which `mirror` and `dump` disagree, including keys that
+/// only one of them has.
+fn check_map<T: PartialEq>(
+    mirror: &short::Map<T>,
+    dump: &short::Map<T>,
+    result: &mut Vec<Mismatch>,
+    mismatch: fn(short::Name) -> Mismatch,
+) {
+    for (short, value) in dump {
+        if !matches!(mirror.get(short), Some(v) if v == value) {
+            result.push(mismatch(*short));
+        }
+    }
+    for short in mirror.keys().filter(|k| !dump.contains_key(*k)) {
+        result.push(mismatch(*short));
+    }
+}
+
+/// A part of the state in which a mirror and a fresh dump disagree.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum Mismatch {
+    /// The attempt information differs.
+    #[error("attempt information differs")]
+    Info,
+    /// The times for the given split differ.
+    #[error("times for split {0} differ")]
+    Times(short::Name),
+    /// The notes for the given split differ.
+    #[error("notes for split {0} differ")]
+    Note(short::Name),
+    /// The notes for the given segment differ.
+    #[error("notes for segment {0} differ")]
+    SegmentNote(short::Name),
+    /// The attempt total differs.
+    #[error("attempt total differs")]
+    Total,
+    /// The comparison aggregates for the given split differ.
+    #[error("comparison for split {0} differs")]
+    Comparison(short::Name),
+    /// The comparison aggregates for the given segment differ.
+    #[error("comparison for segment {0} differs")]
+    SegmentComparison(short::Name),
+    /// The given comparison total differs.
+    #[error("comparison total {0:?} differs")]
+    ComparisonTotal(comparison::run::TotalType),
+}
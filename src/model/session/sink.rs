@@ -1,10 +1,23 @@
 /*! A sink for attempted runs.
 
 When a run is reset, the session may send it (completed or otherwise) to an
-implementation of the [Sink] trait. */
+implementation of the [Sink] trait.
+
+Besides the trivial [Null] sink, this module provides sinks that write runs to files
+([File]), pass only some runs on to another sink ([Filter]), and send runs to several
+other sinks at once ([Fanout]). */
+
+pub mod fanout;
+pub mod file;
+pub mod filter;
+
+pub use fanout::Fanout;
+pub use file::File;
+pub use filter::Filter;
 
 use super::super::{game::category, history};
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Type of runs accepted by a [Sink].
@@ -21,16 +34,27 @@ pub trait Sink {
     fn accept(&mut self, run: Run) -> Result;
 }
 
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn accept(&mut self, run: Run) -> Result {
+        (**self).accept(run)
+    }
+}
+
 /// Shorthand for results from sinks.
 pub type Result = std::result::Result<Outcome, Error>;
 
 /// Possible outcomes of storing a run into a sink.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+///
+/// Outcomes are ordered by how far the run got towards being saved, with [`Outcome::Saved`] as the
+/// greatest.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum Outcome {
-    /// The run was successfully saved.
-    Saved,
     /// The run was ignored by the sink because it is not saving runs.
     Ignored,
+    /// The run was handed over to be saved later, so whether it will be saved is not yet known.
+    Queued,
+    /// The run was successfully saved.
+    Saved,
 }
 
 /// Type of errors from sinks.
@@ -0,0 +1,178 @@
+//! The [Fanout] sink.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use super::{super::super::game::category::ShortDescriptor, Error, Outcome, Result, Run, Sink};
+
+/// A sink that sends each run to several other sinks.
+///
+/// The outcome is the furthest any sink got towards saving the run, so a run counts as saved if
+/// any sink saved it.  If any sink fails, so does the fan-out; the sinks that succeeded are
+/// remembered, so that retrying the run only sends it to the sinks that failed.  Only the
+/// [`MAX_PARTIAL`] most recent such runs are remembered; retrying an older one sends it to every
+/// sink again.
+#[derive(Default)]
+pub struct Fanout {
+    /// The sinks receiving runs.
+    sinks: Vec<Box<dyn Sink + Send>>,
+    /// For each run that some sinks failed to accept, the indices of the sinks that did accept it,
+    /// and the best outcome they gave.
+    partial: HashMap<Key, (HashSet<usize>, Outcome)>,
+}
+
+/// Identifies a run well enough to recognise it when it is retried.
+type Key = (ShortDescriptor, DateTime<Utc>);
+
+/// The number of partially saved runs a [Fanout] remembers.
+///
+/// Runs that are never retried would otherwise be remembered forever.
+pub const MAX_PARTIAL: usize = 64;
+
+impl Fanout {
+    /// Constructs a fan-out over `sinks`.
+    #[must_use]
+    pub fn new(sinks: Vec<Box<dyn Sink + Send>>) -> Self {
+        Self {
+            sinks,
+            partial: HashMap::new(),
+        }
+    }
+}
+
+impl Fanout {
+    /// Remembers that the sinks in `done` accepted the run at `key`, forgetting the oldest run if
+    /// we're remembering too many.
+    fn remember(&mut self, key: Key, done: HashSet<usize>, outcome: Outcome) {
+        if MAX_PARTIAL <= self.partial.len() {
+            if let Some(oldest) = self.partial.keys().min_by_key(|(_, date)| *date).copied() {
+                self.partial.remove(&oldest);
+            }
+        }
+        self.partial.insert(key, (done, outcome));
+    }
+}
+
+impl Sink for Fanout {
+    fn accept(&mut self, run: Run) -> Result {
+        let key = (run.category_locator, run.date);
+        let (mut done, mut outcome) = self
+            .partial
+            .remove(&key)
+            .unwrap_or((HashSet::new(), Outcome::Ignored));
+
+        let mut errors = vec![];
+        for (index, sink) in self.sinks.iter_mut().enumerate() {
+            if done.contains(&index) {
+                continue;
+            }
+            match sink.accept(run.clone()) {
+                Ok(o) => {
+                    done.insert(index);
+                    outcome = outcome.max(o);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(outcome)
+        } else {
+            self.remember(key, done, outcome);
+            Err(Error::Other(anyhow::anyhow!(errors.join("; "))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Null, *};
+    use crate::model::{history, short};
+
+    /// A sink that fails until it is told to work, counting the runs it saves.
+    struct Flaky {
+        works: std::sync::Arc<std::sync::atomic::AtomicBool>,
+        saved: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl Sink for Flaky {
+        fn accept(&mut self, _run: Run) -> Result {
+            use std::sync::atomic::Ordering;
+            if self.works.load(Ordering::SeqCst) {
+                self.saved.fetch_add(1, Ordering::SeqCst);
+                Ok(Outcome::Saved)
+            } else {
+                Err(Error::Other(anyhow::anyhow!("not working")))
+            }
+        }
+    }
+
+    fn run() -> Run {
+        Run {
+            category_locator: ShortDescriptor::new("game", "cat"),
+            timing: history::timing::Full {
+                times: short::Map::default(),
+            },
+            was_completed: true,
+            date: Utc::now(),
+        }
+    }
+
+    /// Tests that a fan-out only retries the sinks that failed.
+    #[test]
+    fn retries_only_failed_sinks() {
+        use std::sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        };
+        let good_saved = Arc::new(AtomicUsize::new(0));
+        let bad_works = Arc::new(AtomicBool::new(false));
+        let bad_saved = Arc::new(AtomicUsize::new(0));
+        let mut fanout = Fanout::new(vec![
+            Box::new(Null),
+            Box::new(Flaky {
+                works: Arc::new(AtomicBool::new(true)),
+                saved: good_saved.clone(),
+            }),
+            Box::new(Flaky {
+                works: bad_works.clone(),
+                saved: bad_saved.clone(),
+            }),
+        ]);
+
+        let run = run();
+        assert!(fanout.accept(run.clone()).is_err());
+        assert_eq!(1, good_saved.load(Ordering::SeqCst));
+
+        bad_works.store(true, Ordering::SeqCst);
+        assert_eq!(Outcome::Saved, fanout.accept(run).expect("retry failed"));
+        assert_eq!(1, good_saved.load(Ordering::SeqCst));
+        assert_eq!(1, bad_saved.load(Ordering::SeqCst));
+    }
+
+    /// Tests that a fan-out forgets the oldest partially saved runs once it has too many.
+    #[test]
+    fn partial_is_capped() {
+        let mut fanout = Fanout::new(vec![Box::new(Flaky {
+            works: std::sync::Arc::default(),
+            saved: std::sync::Arc::default(),
+        })]);
+        let first = run();
+        for i in 0..=MAX_PARTIAL {
+            let mut run = first.clone();
+            run.date = first.date + chrono::Duration::seconds(i64::try_from(i).unwrap());
+            assert!(fanout.accept(run).is_err());
+        }
+        assert_eq!(MAX_PARTIAL, fanout.partial.len());
+        assert!(!fanout
+            .partial
+            .contains_key(&(first.category_locator, first.date)));
+    }
+
+    /// Tests that a fan-out over nothing ignores runs.
+    #[test]
+    fn empty_ignores() {
+        assert_eq!(Outcome::Ignored, Fanout::default().accept(run()).unwrap());
+    }
+}
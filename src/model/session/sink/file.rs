@@ -0,0 +1,86 @@
+//! The [File] sink.
+
+use std::path::PathBuf;
+
+use super::{Outcome, Result, Run, Sink};
+
+/// A sink that writes each run to its own file in a directory.
+///
+/// Runs are written in the same TOML format that run files are loaded from, and are named after
+/// their game, category, and date.  Writing the same run twice overwrites the first copy.
+pub struct File {
+    /// The directory into which runs are written.
+    dir: PathBuf,
+}
+
+impl File {
+    /// Constructs a sink that writes runs into `dir`, creating it if needed.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Gets the path to which this sink writes `run`.
+    #[must_use]
+    pub fn path(&self, run: &Run) -> PathBuf {
+        let loc = &run.category_locator;
+        let date = run.date.format("%Y%m%dT%H%M%S%.3fZ");
+        self.dir
+            .join(format!("{}-{}-{date}.toml", loc.game, loc.category))
+    }
+
+    fn write(&self, run: &Run) -> anyhow::Result<()> {
+        // Going through a value puts plain fields before tables, as TOML requires.
+        let toml = toml::to_string(&toml::Value::try_from(run)?)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(run), toml)?;
+        Ok(())
+    }
+}
+
+impl Sink for File {
+    fn accept(&mut self, run: Run) -> Result {
+        self.write(&run)?;
+        log::info!("wrote run to {}", self.path(&run).display());
+        Ok(Outcome::Saved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{game::category::ShortDescriptor, history, timing::time, Loadable};
+
+    /// Tests that runs written by the sink can be loaded back in.
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().expect("couldn't make temporary directory");
+        let run = Run {
+            category_locator: ShortDescriptor::new("scd11", "btg-sonic"),
+            timing: history::timing::Full {
+                times: [
+                    ("pp1".into(), vec![time::Time::from_millis(26_710)]),
+                    ("pp2".into(), vec![time::Time::from_millis(3_723_456)]),
+                ]
+                .into_iter()
+                .collect(),
+            },
+            was_completed: false,
+            date: chrono::DateTime::parse_from_rfc3339("2021-06-11T19:25:48+01:00")
+                .expect("couldn't parse date")
+                .into(),
+        };
+
+        let mut sink = File::new(dir.path());
+        assert_eq!(
+            Outcome::Saved,
+            sink.accept(run.clone()).expect("couldn't save")
+        );
+        let written = std::fs::read_to_string(sink.path(&run)).expect("couldn't read run");
+        assert!(written.contains(r#"pp1 = ["26s710"]"#), "{written}");
+        assert!(written.contains(r#"pp2 = ["01h02m03s456"]"#), "{written}");
+
+        let loaded = Run::from_toml_file(sink.path(&run)).expect("couldn't load run");
+        assert_eq!(run, loaded);
+    }
+}
@@ -0,0 +1,95 @@
+//! The [Filter] sink.
+
+use super::{Outcome, Result, Run, Sink};
+
+/// A sink that passes only runs meeting some criteria on to another sink.
+///
+/// Runs that don't meet the criteria are ignored.
+pub struct Filter<S> {
+    /// The sink receiving runs that meet the criteria.
+    inner: S,
+    /// Whether only completed runs should be passed on.
+    only_completed: bool,
+    /// The number of splits that must have times for a run to be passed on.
+    min_splits: usize,
+}
+
+impl<S: Sink> Filter<S> {
+    /// Constructs a filter that passes every run on to `inner`.
+    #[must_use]
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            only_completed: false,
+            min_splits: 0,
+        }
+    }
+
+    /// Makes the filter pass on only completed runs.
+    #[must_use]
+    pub fn only_completed(mut self) -> Self {
+        self.only_completed = true;
+        self
+    }
+
+    /// Makes the filter pass on only runs with times in at least `count` splits.
+    #[must_use]
+    pub fn min_splits(mut self, count: usize) -> Self {
+        self.min_splits = count;
+        self
+    }
+
+    /// Gets whether `run` meets this filter's criteria.
+    #[must_use]
+    pub fn allows(&self, run: &Run) -> bool {
+        let timed_splits = run.timing.times.values().filter(|t| !t.is_empty()).count();
+        (run.was_completed || !self.only_completed) && self.min_splits <= timed_splits
+    }
+}
+
+impl<S: Sink> Sink for Filter<S> {
+    fn accept(&mut self, run: Run) -> Result {
+        if self.allows(&run) {
+            self.inner.accept(run)
+        } else {
+            Ok(Outcome::Ignored)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Null, *};
+    use crate::model::{game::category::ShortDescriptor, history, timing::time};
+
+    fn run(was_completed: bool, timed_splits: usize) -> Run {
+        let times = (0..4)
+            .map(|i| {
+                let times = if i < timed_splits {
+                    vec![time::Time::from_millis(1000)]
+                } else {
+                    vec![]
+                };
+                (format!("s{i}").as_str().into(), times)
+            })
+            .collect();
+        Run {
+            category_locator: ShortDescriptor::new("game", "cat"),
+            timing: history::timing::Full { times },
+            was_completed,
+            date: chrono::Utc::now(),
+        }
+    }
+
+    /// Tests that filters only allow runs meeting all of their criteria.
+    #[test]
+    fn allows_only_matching_runs() {
+        let filter = Filter::new(Null).only_completed().min_splits(2);
+        assert!(filter.allows(&run(true, 2)));
+        assert!(!filter.allows(&run(false, 4)));
+        assert!(!filter.allows(&run(true, 1)));
+
+        let everything = Filter::new(Null);
+        assert!(everything.allows(&run(false, 0)));
+    }
+}
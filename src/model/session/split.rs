@@ -1,9 +1,11 @@
 //! Splits and related items.
 
+pub use segment::Segment;
 pub use set::{Locator, Set};
 
 use super::super::{game, timing::time};
 
+pub mod segment;
 pub mod set;
 
 /// A split in a run attempt.
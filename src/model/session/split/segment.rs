@@ -0,0 +1,150 @@
+//! Segments of split sets.
+
+use std::ops::Range;
+
+use super::{
+    super::super::{
+        game, short,
+        timing::{aggregate, time},
+    },
+    Set, Split,
+};
+
+/// A segment in a run attempt.
+///
+/// Segments group consecutive splits of a [Set] (for instance, the acts of a zone), so that they
+/// can be displayed hierarchically, collapsed once completed, and timed as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The game/category segment information for this segment.
+    pub info: game::Segment,
+    /// The positions of this segment's splits in the split set.
+    pub splits: Range<usize>,
+}
+
+impl Segment {
+    /// Constructs a segment covering the splits at positions `splits`.
+    #[must_use]
+    pub fn new(info: game::Segment, splits: Range<usize>) -> Self {
+        Self { info, splits }
+    }
+
+    /// Gets whether the split at position `position` is in this segment.
+    #[must_use]
+    pub fn contains(&self, position: usize) -> bool {
+        self.splits.contains(&position)
+    }
+
+    /// Iterates over this segment's splits, as found in `set`.
+    pub fn splits_in<'a>(&self, set: &'a Set) -> impl Iterator<Item = &'a Split> {
+        set.iter().skip(self.splits.start).take(self.splits.len())
+    }
+
+    /// Gets whether every split in this segment has at least one time in `set`.
+    ///
+    /// Empty segments are never complete.
+    #[must_use]
+    pub fn is_complete(&self, set: &Set) -> bool {
+        !self.splits.is_empty() && self.splits_in(set).all(|s| 0 < s.num_times())
+    }
+
+    /// Derives aggregate times for this segment, given the aggregates for each split in `set`.
+    ///
+    /// The segment's split time is the time taken across all of its splits, and its cumulative
+    /// time is that of its last split.  Deriving both from cumulative times means that segments
+    /// inherit any frame-accurate summing done on the splits.
+    ///
+    /// Returns `None` if the segment is empty, or `split_aggregates` is missing any aggregates
+    /// needed to compute the result.
+    pub fn aggregate(
+        &self,
+        set: &Set,
+        split_aggregates: impl Fn(short::Name) -> Option<aggregate::Set>,
+    ) -> Option<aggregate::Set> {
+        let cumulative_at = |position: usize| {
+            set.get(position)
+                .and_then(|s| split_aggregates(s.info.short))
+                .map(|a| a.cumulative)
+        };
+
+        let last = self
+            .splits
+            .end
+            .checked_sub(1)
+            .filter(|l| self.contains(*l))?;
+        let cumulative = cumulative_at(last)?;
+        let before = match self.splits.start.checked_sub(1) {
+            None => time::Time::default(),
+            Some(position) => cumulative_at(position)?,
+        };
+        Some(aggregate::Set {
+            split: cumulative - before,
+            cumulative,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set() -> Set {
+        [
+            game::Split::new("s1", "Split 1"),
+            game::Split::new("s2", "Split 2"),
+            game::Split::new("s3", "Split 3"),
+        ]
+        .into_iter()
+        .collect::<Set>()
+        .with_segments([
+            (game::Segment::new("a", "Segment A"), 1),
+            (game::Segment::new("b", "Segment B"), 2),
+        ])
+    }
+
+    /// Tests that segment aggregates span their splits' cumulative times.
+    #[test]
+    fn aggregate() {
+        let set = set();
+        let aggregates: short::Map<aggregate::Set> =
+            [("s1", 10, 10), ("s2", 20, 30), ("s3", 40, 70)]
+                .into_iter()
+                .map(|(short, split, cumulative)| {
+                    (
+                        short.into(),
+                        aggregate::Set {
+                            split: time::Time::from_millis(split),
+                            cumulative: time::Time::from_millis(cumulative),
+                        },
+                    )
+                })
+                .collect();
+        let lookup = |s| aggregates.get(&s).copied();
+
+        let b = &set.segments()[1];
+        assert_eq!(
+            Some(aggregate::Set {
+                split: time::Time::from_millis(60),
+                cumulative: time::Time::from_millis(70),
+            }),
+            b.aggregate(&set, lookup)
+        );
+        assert_eq!(None, b.aggregate(&set, |_| None));
+    }
+
+    /// Tests that segments are only complete when all of their splits have times.
+    #[test]
+    fn is_complete() {
+        let mut set = set();
+        let b = set.segments()[1].clone();
+        assert!(!b.is_complete(&set));
+
+        for short in ["s2", "s3"] {
+            set.get_mut(short::Name::from(short))
+                .expect("split should exist")
+                .push(time::Time::from_millis(1));
+        }
+        assert!(b.is_complete(&set));
+        assert!(!set.segments()[0].is_complete(&set));
+    }
+}
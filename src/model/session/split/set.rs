@@ -1,9 +1,13 @@
 //! Split sets.
-use itertools::{Either, Itertools};
-
 use super::{
-    super::super::{game, short, timing::aggregate},
-    Split,
+    super::super::{
+        game, short,
+        timing::{
+            aggregate,
+            time::{self, frame},
+        },
+    },
+    Segment, Split,
 };
 
 /// A set of splits, addressable by [Locator]s.
@@ -18,6 +22,12 @@ pub struct Set {
     ///
     /// Used for implementing [Locator] for short names.
     cache: short::Map<usize>,
+    /// The frame rate at which times in this set are summed, if any.
+    frame_rate: Option<frame::Rate>,
+    /// The segments into which the splits are grouped, in position order.
+    ///
+    /// Sets without segment information have no segments, rather than one big segment.
+    segments: Vec<Segment>,
 }
 
 /// We can construct a [Set] from any iterator that yields attempt split information.
@@ -25,7 +35,12 @@ impl FromIterator<Split> for Set {
     fn from_iter<T: IntoIterator<Item = Split>>(iter: T) -> Self {
         let contents: Vec<Split> = iter.into_iter().collect();
         let cache = make_cache(&contents);
-        Self { contents, cache }
+        Self {
+            contents,
+            cache,
+            frame_rate: None,
+            segments: Vec::new(),
+        }
     }
 }
 
@@ -50,6 +65,57 @@ impl Set {
         self.contents.is_empty()
     }
 
+    /// Sets the frame rate at which times in this set are summed.
+    ///
+    /// With a frame rate, aggregate times are summed by frame count rather than by millisecond,
+    /// so that they don't drift away from the frame-accurate total.
+    #[must_use]
+    pub fn with_frame_rate(mut self, frame_rate: Option<frame::Rate>) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// Gets the frame rate at which times in this set are summed, if any.
+    #[must_use]
+    pub fn frame_rate(&self) -> Option<frame::Rate> {
+        self.frame_rate
+    }
+
+    /// Groups the splits in this set into segments.
+    ///
+    /// `segments` gives, in position order, each segment along with its number of splits.
+    /// Segments running past the end of the set are truncated, and any splits after the last
+    /// segment are left ungrouped.
+    #[must_use]
+    pub fn with_segments(
+        mut self,
+        segments: impl IntoIterator<Item = (game::Segment, usize)>,
+    ) -> Self {
+        let mut start: usize = 0;
+        self.segments = segments
+            .into_iter()
+            .map(|(info, len)| {
+                let end = start.saturating_add(len).min(self.contents.len());
+                let segment = Segment::new(info, start..end);
+                start = end;
+                segment
+            })
+            .collect();
+        self
+    }
+
+    /// Gets the segments into which this set's splits are grouped, in position order.
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Gets the segment containing the split at `position`, if there is one.
+    #[must_use]
+    pub fn segment_of(&self, position: usize) -> Option<&Segment> {
+        self.segments.iter().find(|s| s.contains(position))
+    }
+
     /// Wipes all data for all splits.
     pub fn reset(&mut self) {
         for s in &mut self.contents {
@@ -66,11 +132,20 @@ impl Set {
         game: &game::Config,
         category: &game::config::Category,
     ) -> game::config::Result<Self> {
-        category
-            .full_segments(game)
-            .flat_map(|r| process_segment_result(r, game))
-            .map_ok(|(n, s)| game::Split::new(n, &s.name))
-            .collect()
+        let mut splits = Vec::new();
+        let mut segments = Vec::new();
+        for (short, segment) in category.full_segments(game)? {
+            let start = splits.len();
+            for split in segment.full_splits(game) {
+                let (short, split) = split?;
+                splits.push(game::Split::new(short, &split.name));
+            }
+            segments.push((
+                game::Segment::new(short, &segment.name),
+                splits.len() - start,
+            ));
+        }
+        Ok(splits.into_iter().collect::<Self>().with_segments(segments))
     }
 
     /// Gets an iterator of all of the splits in this set, in position order.
@@ -81,8 +156,10 @@ impl Set {
     }
 
     /// Iterates over all of the aggregate times for this split set.
-    pub fn aggregates(&self) -> impl Iterator<Item = (&Split, aggregate::Set)> + '_ {
-        aggregate::Set::accumulate_splits(self.contents.iter())
+    ///
+    /// If any aggregate overflows, the iterator yields an error and then stops.
+    pub fn aggregates(&self) -> impl Iterator<Item = time::Result<(&Split, aggregate::Set)>> + '_ {
+        aggregate::Set::accumulate_splits_at(self.contents.iter(), self.frame_rate)
     }
 
     /// Uses `loc` to find a split in this set.
@@ -91,6 +168,12 @@ impl Set {
         loc.locate(self)
     }
 
+    /// Gets the position of the split with short name `short` in this set.
+    #[must_use]
+    pub fn position(&self, short: short::Name) -> Option<usize> {
+        self.cache.get(&short).copied()
+    }
+
     /// Uses `loc` to find a split in this set, returning a mutable reference.
     #[must_use]
     pub fn get_mut(&mut self, loc: impl Locator) -> Option<&mut Split> {
@@ -106,16 +189,6 @@ impl Set {
     }
 }
 
-fn process_segment_result<'g>(
-    r: game::config::Result<(short::Name, &'g game::config::Segment)>,
-    game: &'g game::Config,
-) -> impl Iterator<Item = game::config::Result<(short::Name, &'g game::config::Split)>> {
-    match r {
-        Ok((_, seg)) => Either::Left(seg.full_splits(game)),
-        Err(e) => Either::Right(std::iter::once(Err(e))),
-    }
-}
-
 fn make_cache(from: &[Split]) -> short::Map<usize> {
     from.iter()
         .enumerate()
@@ -158,7 +231,11 @@ impl Locator for short::Name {
 #[cfg(test)]
 mod test {
     use super::Set;
-    use crate::model::{game::Split, short, timing::time::human};
+    use crate::model::{
+        game::{Segment, Split},
+        short,
+        timing::time,
+    };
 
     #[test]
     fn aggregates_sample_run() {
@@ -168,11 +245,11 @@ mod test {
         let s3: short::Name = "s3".into();
 
         let s1s = set.get_mut(s1).expect("split 1 should exist");
-        s1s.push(human::Time::seconds(10).unwrap());
-        s1s.push(human::Time::seconds(25).unwrap());
+        s1s.push(time::Time::from_millis(10_000));
+        s1s.push(time::Time::from_millis(25_000));
 
         let s3s = set.get_mut(s3).expect("split 3 should exist");
-        s3s.push(human::Time::seconds(110).unwrap());
+        s3s.push(time::Time::from_millis(110_000));
 
         assert_eq!(
             3,
@@ -183,6 +260,25 @@ mod test {
         // TODO(@MattWindsor91): investigate times
     }
 
+    #[test]
+    fn with_segments_truncates() {
+        let set: Set = splits().into_iter().collect();
+        let set = set.with_segments([
+            (Segment::new("a", "Segment A"), 2),
+            (Segment::new("b", "Segment B"), 2),
+        ]);
+
+        assert_eq!(0..2, set.segments()[0].splits);
+        assert_eq!(2..3, set.segments()[1].splits);
+        assert_eq!(
+            Some("b"),
+            set.segment_of(2)
+                .map(|s| s.info.short.to_string())
+                .as_deref()
+        );
+        assert_eq!(None, set.segment_of(3));
+    }
+
     // TODO(@MattWindsor91): possibly unify this with the integration test version?
     fn splits() -> [Split; 3] {
         [
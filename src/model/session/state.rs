@@ -6,8 +6,9 @@ as they connect to the server.
 
 use super::{
     super::super::model::{short, timing},
-    split, Attempt,
+    event, split, Attempt,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// The state of a session.
@@ -25,6 +26,10 @@ pub struct State {
     /// This is kept separate from the attempt itself as it is effectively a denormalised
     /// derivative of the attempt data.
     pub notes: HashMap<short::Name, SplitNote>,
+    /// Pre-cached extra data for the segments, keyed by segment short name.
+    ///
+    /// Segment notes only have deltas once every split in the segment has a time.
+    pub segment_notes: HashMap<short::Name, SplitNote>,
     /// Total for the run, including a delta against its comparison.
     pub total: Option<timing::comparison::delta::Time>,
 }
@@ -39,6 +44,7 @@ impl State {
             attempt: run,
             comparison,
             notes: HashMap::default(),
+            segment_notes: HashMap::default(),
             total: None,
         };
         result.reset_notes();
@@ -54,6 +60,14 @@ impl State {
         self.total = None;
     }
 
+    /// Replaces the comparison, recalculating every note against it.
+    ///
+    /// Returns the notes that changed.
+    pub fn set_comparison(&mut self, comparison: timing::Comparison) -> event::Notes {
+        self.comparison = comparison;
+        self.recalculate_indirect_fields(0)
+    }
+
     /// Gets a mutable reference to the split at the given location.
     #[must_use]
     fn get_split_mut(&mut self, split: impl split::Locator) -> Option<&mut split::Split> {
@@ -62,19 +76,36 @@ impl State {
 
     /// Tries to locate the given split and, if found, pushes the given time to it.
     ///
-    /// Returns the short-name of the split if successful.
+    /// Returns the short-name of the split, and the notes that changed, if successful.  Pushes
+    /// fail, leaving the state unchanged, if the new time would overflow any of the attempt's
+    /// aggregate times.
     pub fn push_to(
         &mut self,
         split: impl split::Locator,
         time: timing::time::Time,
-    ) -> Option<short::Name> {
-        self.act_on_split(split, |s| s.push(time))
+    ) -> Option<(short::Name, event::Notes)> {
+        let split = self.get_split_mut(split)?;
+        split.push(time);
+        let short = split.info.short;
+
+        let checked: timing::time::Result<()> = self
+            .attempt
+            .splits
+            .aggregates()
+            .try_for_each(|r| r.map(drop));
+        if let Err(e) = checked {
+            log::warn!("rejected time for split {short}: {e}");
+            let _ = self.get_split_mut(short).and_then(split::Split::pop);
+            return None;
+        }
+
+        Some((short, self.recalculate_from(short)))
     }
 
     /// Tries to locate the given split and, if found, pops the most recent time from it.
     ///
-    /// Returns the short-name of the split if fully successful.
-    pub fn pop_from(&mut self, split: impl split::Locator) -> Option<short::Name> {
+    /// Returns the short-name of the split, and the notes that changed, if fully successful.
+    pub fn pop_from(&mut self, split: impl split::Locator) -> Option<(short::Name, event::Notes)> {
         self.act_on_split(split, |s| {
             let _ = s.pop();
         })
@@ -82,8 +113,8 @@ impl State {
 
     /// Tries to locate the given split and, if found, clears all times from it.
     ///
-    /// Returns the short-name of the split if successful.
-    pub fn clear_at(&mut self, split: impl split::Locator) -> Option<short::Name> {
+    /// Returns the short-name of the split, and the notes that changed, if successful.
+    pub fn clear_at(&mut self, split: impl split::Locator) -> Option<(short::Name, event::Notes)> {
         self.act_on_split(split, split::Split::clear)
     }
 
@@ -92,36 +123,89 @@ impl State {
         &mut self,
         split: impl split::Locator,
         f: impl FnOnce(&mut split::Split),
-    ) -> Option<short::Name> {
-        self.get_split_mut(split)
-            .map(|s| {
-                f(s);
-                s.info.short
-            })
-            .map(|result| {
-                self.recalculate_indirect_fields();
-                result
-            })
+    ) -> Option<(short::Name, event::Notes)> {
+        let split = self.get_split_mut(split)?;
+        f(split);
+        let short = split.info.short;
+        Some((short, self.recalculate_from(short)))
     }
 
-    fn recalculate_indirect_fields(&mut self) {
-        self.recalculate_notes();
+    /// Recalculates the fields that depend on the split `short`, which has just changed.
+    fn recalculate_from(&mut self, short: short::Name) -> event::Notes {
+        let position = self.attempt.splits.position(short).unwrap_or_default();
+        self.recalculate_indirect_fields(position)
+    }
+
+    fn recalculate_indirect_fields(&mut self, from: usize) -> event::Notes {
+        let notes = self.recalculate_notes(from);
         self.recalculate_total();
+        notes
     }
 
-    /// Populates the notes table with an empty note for each split.
+    /// Populates the notes tables with an empty note for each split and segment.
     fn reset_notes(&mut self) {
         for s in self.attempt.splits.iter() {
             self.notes.insert(s.info.short, SplitNote::default());
         }
+        self.segment_notes.clear();
+        for s in self.attempt.splits.segments() {
+            self.segment_notes
+                .insert(s.info.short, SplitNote::default());
+        }
     }
 
-    /// Populates the notes table with recalculated notes.
-    fn recalculate_notes(&mut self) {
-        // TODO(@MattWindsor91): only recalculate from the changed split.
-        for (s, a) in self.attempt.splits.aggregates() {
-            self.notes.insert(s.info.short, self.note(s, a));
-        }
+    /// Recalculates the notes for the split at position `from` and every split after it, as well
+    /// as any segments containing those splits, returning the notes that changed.
+    ///
+    /// Notes for earlier splits can't depend on a change at `from`, so they are left alone.
+    fn recalculate_notes(&mut self, from: usize) -> event::Notes {
+        let mut changed = event::Notes::default();
+
+        // Cumulative times still need the earlier splits' times, but we don't build notes for
+        // them.  Pushes shouldn't overflow the aggregates, but if they do, the splits from the
+        // overflow onwards keep their old notes.
+        let split_notes: Vec<_> = self
+            .attempt
+            .splits
+            .aggregates()
+            .skip(from)
+            .map_while(|agg| {
+                agg.map_err(|e| log::error!("couldn't recalculate split notes: {e}"))
+                    .ok()
+            })
+            .map(|(s, a)| (s.info.short, self.note(s, a)))
+            .collect();
+        update_notes(&mut self.notes, &mut changed.splits, split_notes);
+
+        // Segment notes derive from the split notes, so must come after them.
+        let segment_notes: Vec<_> = self
+            .attempt
+            .splits
+            .segments()
+            .iter()
+            .filter(|s| from < s.splits.end)
+            .map(|s| (s.info.short, self.segment_note(s)))
+            .collect();
+        update_notes(
+            &mut self.segment_notes,
+            &mut changed.segments,
+            segment_notes,
+        );
+
+        changed
+    }
+
+    fn segment_note(&self, segment: &split::Segment) -> SplitNote {
+        let splits = &self.attempt.splits;
+        let aggregates = segment
+            .aggregate(splits, |s| self.notes.get(&s).map(|n| n.aggregates))
+            .unwrap_or_default();
+        // A delta against a partially completed segment would be meaningless.
+        let delta = segment.is_complete(splits).then(|| {
+            self.comparison
+                .segment_delta(segment.info.short, aggregates)
+        });
+        SplitNote { aggregates, delta }
     }
 
     fn note(&self, split: &split::Split, aggregates: timing::aggregate::Set) -> SplitNote {
@@ -158,11 +242,24 @@ impl State {
     }
 }
 
-/// A precomputed series of facts about a split.
+/// Stores each of `fresh` into `notes`, also storing into `changed` those that differ from before.
+fn update_notes(
+    notes: &mut short::Map<SplitNote>,
+    changed: &mut short::Map<SplitNote>,
+    fresh: Vec<(short::Name, SplitNote)>,
+) {
+    for (short, note) in fresh {
+        if notes.insert(short, note) != Some(note) {
+            changed.insert(short, note);
+        }
+    }
+}
+
+/// A precomputed series of facts about a split or segment.
 ///
 /// These are things that the client could compute itself, but which we keep in the state to
 /// centralise computation and allow for simpler client logic.
-#[derive(Debug, Copy, Default, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SplitNote {
     /// Attempt-level aggregates for this split.
     ///
@@ -187,3 +284,72 @@ impl SplitNote {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{
+        game,
+        timing::{aggregate, comparison, time::Time},
+    };
+
+    /// Constructs a state over one two-split segment, with a comparison run of 25 seconds per
+    /// split and a segment PB better than the PB run's.
+    fn state() -> State {
+        let splits = [
+            game::Split::new("pp1", "Palmtree Panic 1"),
+            game::Split::new("pp2", "Palmtree Panic 2"),
+        ]
+        .into_iter()
+        .collect::<split::Set>()
+        .with_segments([(game::Segment::new("pp", "Palmtree Panic"), 2)]);
+        let attempt = Attempt {
+            category: game::category::Target::default(),
+            info: game::category::AttemptInfo::default(),
+            splits,
+        };
+
+        let segment = |split, cumulative, split_pb| comparison::Segment {
+            split_pb: Time::from_millis(split_pb),
+            in_pb_run: aggregate::Set {
+                split: Time::from_millis(split),
+                cumulative: Time::from_millis(cumulative),
+            },
+        };
+        let comparison = comparison::Comparison {
+            splits: short::Map::from([
+                ("pp1".into(), segment(25_000, 25_000, 25_000)),
+                ("pp2".into(), segment(25_000, 50_000, 25_000)),
+            ]),
+            segments: short::Map::from([("pp".into(), segment(50_000, 50_000, 49_500))]),
+            run: comparison::Run::default(),
+        };
+
+        State::new(attempt, comparison)
+    }
+
+    fn push(state: &mut State, split: &str, millis: i64) -> event::Notes {
+        let (_, notes) = state
+            .push_to(short::Name::from(split), Time::from_millis(millis))
+            .expect("push should succeed");
+        notes
+    }
+
+    /// Tests that segments only get deltas once complete, and can be gold on their own.
+    #[test]
+    fn segment_deltas() {
+        let mut state = state();
+        let pp = short::Name::from("pp");
+
+        push(&mut state, "pp1", 24_000);
+        assert_eq!(None, state.segment_notes[&pp].delta);
+
+        // 1 second ahead overall, which beats the segment PB.
+        let notes = push(&mut state, "pp2", 25_000);
+        let ahead =
+            comparison::Delta::of_comparison(Time::from_millis(49_000), Time::from_millis(50_000));
+        let expected = comparison::delta::Split::new(ahead, ahead).with_personal_best(true);
+        assert_eq!(Some(expected), state.segment_notes[&pp].delta);
+        assert_eq!(Some(&state.segment_notes[&pp]), notes.segments.get(&pp));
+    }
+}
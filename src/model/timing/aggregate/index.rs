@@ -51,7 +51,6 @@ impl Source {
     /// assert_eq!(Source::Attempt, x.source);
     /// assert_eq!(Scope::Cumulative, x.scope);
     /// assert_eq!(Kind::ATTEMPT_CUMULATIVE, x);
-
     /// ```
     #[must_use]
     pub const fn with(self, scope: Scope) -> Kind {
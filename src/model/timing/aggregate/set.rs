@@ -2,8 +2,13 @@
 
 use std::ops::{Index, IndexMut};
 
+use serde::{Deserialize, Serialize};
+
 use super::{
-    super::{super::session::split, time},
+    super::{
+        super::session::split,
+        time::{self, frame},
+    },
     index::{Kind, Scope, Source},
 };
 
@@ -56,7 +61,7 @@ impl IndexMut<Kind> for Full {
 }
 
 /// A set of aggregate times at various scopes.
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Set {
     /// Single time for this split only.
     pub split: time::Time,
@@ -70,10 +75,25 @@ impl Set {
     ///
     /// Although split aggregates are yielded in order, we provide the short
     /// name of each split for convenience.
+    ///
+    /// If any aggregate overflows, the iterator yields an error and then stops.
     pub fn accumulate_splits<'a>(
         splits: impl IntoIterator<Item = &'a split::Split> + 'a,
-    ) -> impl Iterator<Item = (&'a split::Split, Set)> + 'a {
-        Self::accumulate_pairs(splits.into_iter().map(|x| (x, x.total_time())))
+    ) -> impl Iterator<Item = time::Result<(&'a split::Split, Set)>> + 'a {
+        Self::accumulate_splits_at(splits, None)
+    }
+
+    /// Like [`Set::accumulate_splits`], but sums times by frame count at `frame_rate`, if given.
+    pub fn accumulate_splits_at<'a>(
+        splits: impl IntoIterator<Item = &'a split::Split> + 'a,
+        frame_rate: Option<frame::Rate>,
+    ) -> impl Iterator<Item = time::Result<(&'a split::Split, Set)>> + 'a {
+        Self::try_accumulate(
+            splits
+                .into_iter()
+                .map(move |x| Ok((x, frame::sum(frame_rate, x.times.iter().copied())?))),
+            frame_rate,
+        )
     }
 
     /// Constructs an iterator that yields, for each pair of split and
@@ -81,20 +101,53 @@ impl Set {
     ///
     /// Although aggregates are yielded in order, we provide the short
     /// name of each split for convenience.
+    ///
+    /// If any aggregate overflows, the iterator yields an error and then stops.
     pub fn accumulate_pairs<T>(
         pairs: impl IntoIterator<Item = (T, time::Time)>,
-    ) -> impl Iterator<Item = (T, Set)> {
+    ) -> impl Iterator<Item = time::Result<(T, Set)>> {
+        Self::accumulate_pairs_at(pairs, None)
+    }
+
+    /// Like [`Set::accumulate_pairs`], but sums times by frame count at `frame_rate`, if given.
+    ///
+    /// Summing by frame count keeps cumulative times from drifting away from the frame-accurate
+    /// total through repeated millisecond rounding.
+    pub fn accumulate_pairs_at<T>(
+        pairs: impl IntoIterator<Item = (T, time::Time)>,
+        frame_rate: Option<frame::Rate>,
+    ) -> impl Iterator<Item = time::Result<(T, Set)>> {
+        Self::try_accumulate(pairs.into_iter().map(Ok), frame_rate)
+    }
+
+    fn try_accumulate<T>(
+        pairs: impl Iterator<Item = time::Result<(T, time::Time)>>,
+        frame_rate: Option<frame::Rate>,
+    ) -> impl Iterator<Item = time::Result<(T, Set)>> {
+        let mut millis = time::Time::default();
+        let mut frames = 0_i64;
         pairs
-            .into_iter()
-            .scan(time::Time::default(), |cumulative, (short, split)| {
-                *cumulative += split;
-                Some((
-                    short,
-                    Set {
-                        split,
-                        cumulative: *cumulative,
-                    },
-                ))
+            .map(move |pair| {
+                let (short, split) = pair?;
+                millis = millis.checked_add(split)?;
+                let cumulative = match frame_rate {
+                    None => millis,
+                    Some(rate) => {
+                        frames = frames
+                            .checked_add(rate.frames(split))
+                            .ok_or(time::Error::Overflow)?;
+                        rate.time(frames)
+                    }
+                };
+                Ok((short, Set { split, cumulative }))
+            })
+            .scan(false, |has_failed, result| {
+                // Stop after the first error, as later cumulative times would be meaningless.
+                if *has_failed {
+                    return None;
+                }
+                *has_failed = result.is_err();
+                Some(result)
             })
     }
 }
@@ -108,8 +161,8 @@ impl Set {
 ///   split: time::Time::from_millis(20),
 ///   cumulative: time::Time::from_millis(40)
 /// };
-/// assert_eq!(20, x[Scope::Split].to_millis());
-/// assert_eq!(40, x[Scope::Cumulative].to_millis());
+/// assert_eq!(20, x[Scope::Split].into_millis());
+/// assert_eq!(40, x[Scope::Cumulative].into_millis());
 /// ```
 impl Index<Scope> for Set {
     type Output = time::Time;
@@ -131,8 +184,8 @@ impl Index<Scope> for Set {
 /// x[Scope::Split] = time::Time::from_millis(20);
 /// x[Scope::Cumulative] = time::Time::from_millis(40);
 ///
-/// assert_eq!(20, x[Scope::Split]);
-/// assert_eq!(40, x[Scope::Cumulative]);
+/// assert_eq!(time::Time::from_millis(20), x[Scope::Split]);
+/// assert_eq!(time::Time::from_millis(40), x[Scope::Cumulative]);
 /// ```
 impl IndexMut<Scope> for Set {
     fn index_mut(&mut self, index: Scope) -> &mut Self::Output {
@@ -146,7 +199,10 @@ impl IndexMut<Scope> for Set {
 #[cfg(test)]
 mod test {
     use super::{Scope, Set};
-    use crate::model::{short, timing::time};
+    use crate::model::{
+        short,
+        timing::time::{self, frame},
+    };
 
     #[test]
     fn accumulate_pairs_nonempty() {
@@ -155,7 +211,9 @@ mod test {
             (short::Name::from("split2"), time::Time::from_millis(42)),
             (short::Name::from("split3"), time::Time::from_millis(101)),
         ];
-        let results: Vec<_> = Set::accumulate_pairs(pairs).collect();
+        let results: Vec<_> = Set::accumulate_pairs(pairs)
+            .collect::<time::Result<_>>()
+            .expect("shouldn't overflow");
         assert_eq!(3, results.len(), "expected as many aggregates as splits");
 
         let mut cumulative = time::Time::default();
@@ -178,4 +236,34 @@ mod test {
             );
         }
     }
+
+    /// Tests that overflowing aggregates yield one error, then stop.
+    #[test]
+    fn accumulate_pairs_overflow() {
+        let pairs = [
+            (short::Name::from("split1"), time::Time::MAX),
+            (short::Name::from("split2"), time::Time::from_millis(1)),
+            (short::Name::from("split3"), time::Time::from_millis(1)),
+        ];
+        let results: Vec<_> = Set::accumulate_pairs(pairs).collect();
+        assert_eq!(2, results.len(), "expected to stop after the error");
+        assert!(results[0].is_ok());
+        assert_eq!(Some(&time::Error::Overflow), results[1].as_ref().err());
+    }
+
+    /// Tests that accumulating at a frame rate sums by frame, without drift.
+    #[test]
+    fn accumulate_pairs_at_frame_rate() {
+        let rate = frame::Rate::default();
+        // One frame at 60fps rounds to 17ms, so three rounded frames would sum to 51ms.
+        let pairs = [
+            (short::Name::from("split1"), rate.time(1)),
+            (short::Name::from("split2"), rate.time(1)),
+            (short::Name::from("split3"), rate.time(1)),
+        ];
+        let results: Vec<_> = Set::accumulate_pairs_at(pairs, Some(rate))
+            .collect::<time::Result<_>>()
+            .expect("shouldn't overflow");
+        assert_eq!(time::Time::from_millis(50), results[2].1[Scope::Cumulative]);
+    }
 }
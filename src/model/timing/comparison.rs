@@ -12,15 +12,20 @@ pub use run::Run;
 
 use super::{super::short, aggregate, time};
 
-/// Comparison data, containing information about split and run personal bests (PBs).
+/// Comparison data, containing information about split, segment, and run personal bests (PBs).
 ///
-/// A comparison struct contains both per-split and aggregated times.  There is no inherent checking
+/// A comparison struct contains per-split, per-segment, and aggregated times.  There is no inherent checking
 /// that the former and latter agree, as some comparison providers may have ways of calculating the
 /// latter that don't involve calculating the former.
 #[derive(Clone, Debug, Default)]
 pub struct Comparison {
     /// Split comparisons.
     pub splits: short::Map<Segment>,
+    /// Segment comparisons, keyed by segment short name.
+    ///
+    /// These compare whole groups of splits; their personal bests are the best times for each
+    /// segment across all runs, rather than the sums of the best times for its splits.
+    pub segments: short::Map<Segment>,
     /// Precomputed run data.
     pub run: Run,
 }
@@ -28,6 +33,7 @@ pub struct Comparison {
 impl Comparison {
     /// Gets a delta for the split with short name `split`, which has just posted an aggregate time
     /// pair of `against`.
+    #[must_use]
     pub fn delta(&self, split: short::Name, against: aggregate::Set) -> delta::Split {
         self.splits
             .get(&split)
@@ -40,6 +46,21 @@ impl Comparison {
     pub fn aggregate_for(&self, split: short::Name) -> Option<&aggregate::Set> {
         self.splits.get(&split).map(|x| &x.in_pb_run)
     }
+
+    /// Gets a delta for the segment with short name `segment`, which has just posted an aggregate
+    /// time pair of `against`.
+    #[must_use]
+    pub fn segment_delta(&self, segment: short::Name, against: aggregate::Set) -> delta::Split {
+        self.segments
+            .get(&segment)
+            .map_or(delta::Split::default(), |x| x.delta(against))
+    }
+
+    /// Gets the aggregate times for the segment with short name `segment`, if available.
+    #[must_use]
+    pub fn segment_aggregate_for(&self, segment: short::Name) -> Option<&aggregate::Set> {
+        self.segments.get(&segment).map(|x| &x.in_pb_run)
+    }
 }
 
 /// A [Comparison] can be turned back into an iterator over split name-comparison pairs.
@@ -73,12 +94,13 @@ impl FromIterator<(short::Name, Segment)> for Comparison {
 
 /// Segment comparisons.
 ///
-/// A segment comparison contains (for now) up to two pieces:
+/// Despite the name, these serve as comparisons for both individual splits and segments (groups of
+/// splits).  A segment comparison contains (for now) up to two pieces:
 ///
-/// - a 'personal best' for the split across all runs stored in the database
+/// - a 'personal best' for the split or segment across all runs stored in the database
 ///   (used for calculating so-called 'gold splits');
 /// - a set of aggregates that represent the important times logged for this
-///   split on the comparison run (right now, there is only one comparison run,
+///   split or segment on the comparison run (right now, there is only one comparison run,
 ///   the PB).
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Segment {
@@ -94,14 +116,11 @@ pub struct Segment {
 impl Segment {
     /// Gets delta information for this segment, which has just posted an aggregate time
     /// pair of `against`.
-    ///
-    /// # Errors
-    ///
-    /// Fails if the delta cannot be represented as a time.
+    #[must_use]
     pub fn delta(&self, against: aggregate::Set) -> delta::Split {
         let split = self.delta_against_aggregate(against, aggregate::Scope::Split);
         let run = self.delta_against_aggregate(against, aggregate::Scope::Cumulative);
-        delta::Split::new(split, run)
+        delta::Split::new(split, run).with_personal_best(self.is_personal_best(against.split))
     }
 
     /// Gets a delta for the aggregate time of scope `scope` between `against` and this comparison.
@@ -113,9 +132,9 @@ impl Segment {
         Delta::of_comparison(against[scope], self.in_pb_run[scope])
     }
 
-    // TODO(@Ashi): use is_personal_best again
-
     /// Checks whether `split time` is a new personal best.
+    ///
+    /// If there is no personal best yet, `split_pb` is zero, and so nothing counts as one.
     fn is_personal_best(&self, split_time: time::Time) -> bool {
         split_time < self.split_pb
     }
@@ -3,15 +3,28 @@
 //! A delta is simply a time with a specific meaning.
 
 use super::{super::time, pace};
+use serde::{Deserialize, Serialize};
 
 /// A single-time time difference (current time - comparison time).
 ///
 /// Usually, these will represent run-wide cumulative deltas, but they are also used to construct
 /// [Split]s.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Delta(time::Time);
 
 impl Delta {
+    /// Constructs a delta from its `amount`: the attempt time minus the comparison time.
+    #[must_use]
+    pub const fn new(amount: time::Time) -> Self {
+        Self(amount)
+    }
+
+    /// Gets the amount of this delta: the attempt time minus the comparison time.
+    #[must_use]
+    pub const fn amount(self) -> time::Time {
+        self.0
+    }
+
     /// Gets the pace of this delta.
     #[must_use]
     pub fn pace(&self) -> pace::Pace {
@@ -31,31 +44,63 @@ impl Delta {
 }
 
 /// A time difference at the split level.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Split {
     /// The split delta.
     pub split: Delta,
     /// The cumulative run delta.
     pub run: Delta,
+    /// Whether the split time beats the personal best for the split ('gold split').
+    pub is_personal_best: bool,
 }
 
 impl Split {
     /// Constructs a [Split] delta from two [Delta]s: one representing the split individually,
     /// the other representing the cumulative delta across the run so far.
+    ///
+    /// The resulting delta is not marked as a personal best; see [`Split::with_personal_best`].
     #[must_use]
     pub fn new(split: Delta, run: Delta) -> Self {
-        Self { split, run }
+        Self {
+            split,
+            run,
+            is_personal_best: false,
+        }
+    }
+
+    /// Marks whether this delta belongs to a gold split.
+    #[must_use]
+    pub fn with_personal_best(self, is_personal_best: bool) -> Self {
+        Self {
+            is_personal_best,
+            ..self
+        }
     }
 
     /// Gets the split-in-run pace from this delta.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::{comparison::{delta, pace}, time::Time};
+    ///
+    /// let split = delta::Delta::of_comparison(Time::from_millis(1000), Time::from_millis(2000));
+    /// let run = delta::Delta::of_comparison(Time::from_millis(3000), Time::from_millis(1000));
+    /// let d = delta::Split::new(split, run);
+    /// assert_eq!(pace::SplitInRun::BehindAndGaining, d.pace());
+    /// assert_eq!(pace::SplitInRun::SplitPersonalBest, d.with_personal_best(true).pace());
+    /// ```
     #[must_use]
     pub fn pace(&self) -> pace::SplitInRun {
-        pace::SplitInRun::new(self.split.pace(), self.run.pace())
+        let split = if self.is_personal_best {
+            pace::Pace::PersonalBest
+        } else {
+            self.split.pace()
+        };
+        pace::SplitInRun::new(split, self.run.pace())
     }
 }
 
 /// A pair of a time and its delta against comparison.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Time {
     /// The delta.
     pub delta: Delta,
@@ -93,6 +138,7 @@ impl Time {
     /// assert_eq!(t1, t1a);
     /// assert_eq!(t2, t2a);
     /// ```
+    #[must_use]
     pub fn comparison(&self) -> time::Time {
         // comparison = time - (time - comparison)
         self.time - self.delta.0
@@ -29,8 +29,8 @@ impl Pace {
     /// ```
     /// use zombiesplit::model::timing::{comparison::Pace, time};
     ///
-    /// let t1 = time::Time::from_millis(16).unwrap();
-    /// let t2 = time::Time::from_millis(80).unwrap();
+    /// let t1 = time::Time::from_millis(16);
+    /// let t2 = time::Time::from_millis(80);
     ///
     /// assert_eq!(Pace::Ahead, Pace::of_comparison(t1, t2));
     /// assert_eq!(Pace::Behind, Pace::of_comparison(t2, t1));
@@ -20,6 +20,22 @@ impl Provider for Null {
     }
 }
 
+/// A provider that asks each of a list of providers in turn, returning the first comparison found.
+///
+/// Errors from any provider stop the search, rather than falling through to the next provider.
+pub struct Chain<'a>(pub Vec<Box<dyn Provider + 'a>>);
+
+impl Provider for Chain<'_> {
+    fn comparison(&mut self) -> Result {
+        for p in &mut self.0 {
+            if let Some(c) = p.comparison()? {
+                return Ok(Some(c));
+            }
+        }
+        Ok(None)
+    }
+}
+
 /// Comparisons, themselves, are comparison providers.
 ///
 /// More specifically, anything that can be turned into an optional comparison is a comparison
@@ -1,8 +1,9 @@
 //! zombiesplit's notion of times.
 
-pub use error::Error;
+pub use error::{Error, Result};
 pub use format::Format;
 pub use position::Position;
+use serde::{Deserialize, Serialize};
 use std::{
     iter::Sum,
     ops::{Add, AddAssign, Sub, SubAssign},
@@ -11,19 +12,34 @@ use std::{
 pub mod error;
 pub mod field;
 pub mod format;
+pub mod frame;
 pub mod human;
+pub mod parse;
 pub mod position;
 
 /// A time.
 ///
-/// Times (more correctly, durations) are represented internally as a number of milliseconds.
-/// The exact representation is subject to change, but should be enough to accommodate all but the
-/// most pathological speedrun cumulative times (eg, it can stretch to several days before
-/// overflow).
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Default, Hash)]
-pub struct Time(i32);
+/// Times (more correctly, durations) are represented internally as a signed 64-bit number of
+/// milliseconds, which comfortably accommodates marathon runs and cumulative statistics over many
+/// runs.
+///
+/// The arithmetic operators on times saturate rather than wrapping or panicking; to detect
+/// overflow, use the `checked_` methods, which return [`Error::Overflow`].
+///
+/// Times serialise as their number of milliseconds; use [`human::Time`] for human-readable
+/// serialisation.
+#[derive(
+    Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Default, Hash, Serialize, Deserialize,
+)]
+pub struct Time(i64);
 
 impl Time {
+    /// The largest representable time.
+    pub const MAX: Self = Self(i64::MAX);
+
+    /// The smallest (most negative) representable time.
+    pub const MIN: Self = Self(i64::MIN);
+
     /// Creates a time from a signed millisecond representation.
     ///
     /// # Examples
@@ -35,61 +51,113 @@ impl Time {
     /// assert_eq!(42, Time::from_millis(42).into_millis())
     /// ```
     #[must_use]
-    pub const fn from_millis(amount: i32) -> Self {
+    pub const fn from_millis(amount: i64) -> Self {
         Self(amount)
     }
 
     /// Converts this time to a signed millisecond representation.
     #[must_use]
-    pub const fn into_millis(self) -> i32 {
+    pub const fn into_millis(self) -> i64 {
         self.0
     }
+
+    /// Adds `rhs` to this time, failing on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::Time;
+    ///
+    /// let t = Time::from_millis(1);
+    /// assert_eq!(Time::from_millis(2), t.checked_add(t).unwrap());
+    /// assert!(Time::MAX.checked_add(t).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::Overflow`] if the result is out of range.
+    pub fn checked_add(self, rhs: Self) -> Result<Self> {
+        self.0.checked_add(rhs.0).map(Self).ok_or(Error::Overflow)
+    }
+
+    /// Subtracts `rhs` from this time, failing on overflow.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::Overflow`] if the result is out of range.
+    pub fn checked_sub(self, rhs: Self) -> Result<Self> {
+        self.0.checked_sub(rhs.0).map(Self).ok_or(Error::Overflow)
+    }
+
+    /// Sums `times`, failing on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::Time;
+    ///
+    /// let t = Time::from_millis(1);
+    /// assert_eq!(Time::from_millis(3), Time::checked_sum([t, t, t]).unwrap());
+    /// assert!(Time::checked_sum([Time::MAX, t]).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::Overflow`] if any partial sum is out of range.
+    pub fn checked_sum(times: impl IntoIterator<Item = Self>) -> Result<Self> {
+        times
+            .into_iter()
+            .try_fold(Self::default(), Self::checked_add)
+    }
 }
 
+/// Sums saturate; use [`Time::checked_sum`] to detect overflow.
 impl Sum for Time {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        Self::from_millis(iter.map(Self::into_millis).sum())
+        iter.fold(Self::default(), Add::add)
     }
 }
 
+/// Addition saturates; use [`Time::checked_add`] to detect overflow.
 impl Add for Time {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0)
+        Self(self.0.saturating_add(rhs.0))
     }
 }
 
+/// Subtraction saturates; use [`Time::checked_sub`] to detect overflow.
 impl Sub for Time {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Self(self.0 - rhs.0)
+        Self(self.0.saturating_sub(rhs.0))
     }
 }
 
 impl AddAssign for Time {
     fn add_assign(&mut self, rhs: Self) {
-        self.0 -= rhs.0;
+        *self = *self + rhs;
     }
 }
 
 impl SubAssign for Time {
     fn sub_assign(&mut self, rhs: Self) {
-        self.0 -= rhs.0;
+        *self = *self - rhs;
     }
 }
 
 impl rusqlite::types::FromSql for Time {
     fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
-        i32::column_result(value).map(Self)
+        i64::column_result(value).map(Self)
     }
 }
 
 impl rusqlite::ToSql for Time {
     fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
         Ok(rusqlite::types::ToSqlOutput::Owned(
-            rusqlite::types::Value::Integer(i64::from(self.0)),
+            rusqlite::types::Value::Integer(self.0),
         ))
     }
 }
@@ -111,6 +179,32 @@ mod tests {
     fn time_sub_no_sat() {
         let t1: Time = Time::from_millis(15100);
         let t2: Time = Time::from_millis(6410);
-        assert_eq!(t2, (t2 - t1) + t2);
+        assert_eq!(t2, (t2 - t1) + t1);
+    }
+
+    /// Tests that `+=` adds, rather than subtracting.
+    #[test]
+    fn time_add_assign() {
+        let mut t = Time::from_millis(15100);
+        t += Time::from_millis(6410);
+        assert_eq!(Time::from_millis(21510), t);
+    }
+
+    /// Tests that the operators saturate rather than overflowing.
+    #[test]
+    fn time_saturates() {
+        let one = Time::from_millis(1);
+        assert_eq!(Time::MAX, Time::MAX + one);
+        assert_eq!(Time::MIN, Time::MIN - one);
+        assert_eq!(Time::MAX, [Time::MAX, one].into_iter().sum());
+    }
+
+    /// Tests that the checked operations report overflow.
+    #[test]
+    fn time_checked() {
+        let one = Time::from_millis(1);
+        assert_eq!(Err(Error::Overflow), Time::MAX.checked_add(one));
+        assert_eq!(Err(Error::Overflow), Time::MIN.checked_sub(one));
+        assert_eq!(Ok(Time::MAX), Time::MAX.checked_sub(Time::default()));
     }
 }
@@ -15,8 +15,34 @@ pub enum Error {
     FieldTooBig { pos: super::Position, val: u32 },
     #[error("millisecond value {0} too large")]
     SecOverflow(i32),
+    #[error("time arithmetic overflowed")]
+    Overflow,
     #[error("couldn't convert {0} to millisecond value; too large to fit")]
     MsecOverflow(super::human::Time),
+    #[error("expected digits at column {column}")]
+    ExpectedDigits { column: usize },
+    #[error("unexpected {found:?} at column {column}")]
+    Unexpected { column: usize, found: char },
+    #[error("number at column {column} needs a unit (h, m, s, or ms)")]
+    MissingUnit { column: usize },
+    #[error("{pos} at column {column} are out of order or repeated")]
+    OutOfOrder { column: usize, pos: super::Position },
+    #[error("{pos} at column {column} are out of range")]
+    OutOfRange { column: usize, pos: super::Position },
+    #[error("only seconds can have a fractional part, but {pos} at column {column} do")]
+    FractionNotSeconds { column: usize, pos: super::Position },
+    #[error(
+        "too many colon-separated fields, starting at column {column}; expected at most h:m:s"
+    )]
+    TooManyFields { column: usize },
+    #[error("invalid frame rate: {0}")]
+    FrameRate(String),
+    #[error("frame times must look like [[h:]m:]s:f")]
+    FrameNotation,
+    #[error("frame field failed parsing: {0}")]
+    FrameParse(ParseIntError),
+    #[error("frame {frame} doesn't exist; this second only has {per_second} frames")]
+    FrameTooBig { frame: i64, per_second: i64 },
 }
 
 /// Shorthand for parse results.
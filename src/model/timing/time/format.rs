@@ -2,6 +2,10 @@
 
 These structures and related support let users tell zombiesplit how to lay out times on the UI. */
 
+pub mod render;
+
+pub use render::{Options, Overflow, Rendered, Rounding, Sign};
+
 use super::Position;
 use itertools::Itertools;
 use num_integer::Integer;
@@ -84,7 +88,9 @@ impl Parser {
                     self.is_escaping = rem != 0;
                 }
 
-                if let Some(index) = parse_position_char(c) {
+                if c == CHAR_FRAME {
+                    self.result.push(Component::Frames { width: count });
+                } else if let Some(index) = parse_position_char(c) {
                     self.push_position(count, index);
                 } else {
                     (0..count).for_each(|_| self.push_delimiter(c));
@@ -144,8 +150,11 @@ const CHAR_HOUR: char = 'h';
 const CHAR_MIN: char = 'm';
 const CHAR_SEC: char = 's';
 const CHAR_MSEC: char = 'u';
+const CHAR_FRAME: char = 'f';
 const CHAR_ESC: char = '\\';
-const SPECIAL_CHARS: [char; 5] = [CHAR_HOUR, CHAR_MIN, CHAR_SEC, CHAR_MSEC, CHAR_ESC];
+const SPECIAL_CHARS: [char; 6] = [
+    CHAR_HOUR, CHAR_MIN, CHAR_SEC, CHAR_MSEC, CHAR_FRAME, CHAR_ESC,
+];
 
 /// Layout information for one component in a time layout.
 ///
@@ -159,6 +168,13 @@ pub enum Component {
         /// The number of digits to display for this index.
         width: usize,
     },
+    /// A frame component, showing the frame within the current second.
+    ///
+    /// Frames render at the frame rate given when rendering the time.
+    Frames {
+        /// The number of digits to display.
+        width: usize,
+    },
     /// A delimiter.
     Delimiter(char),
 }
@@ -167,6 +183,7 @@ impl Display for Component {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match *self {
             Self::Position { position, width } => emit_position(f, position, width),
+            Self::Frames { width } => (0..width).try_for_each(|_| f.write_char(CHAR_FRAME)),
             Self::Delimiter(c) => emit_delimiter(f, c),
         }
     }
@@ -224,6 +241,17 @@ mod tests {
         assert_eq!(Error::UnbalancedEscape, e);
     }
 
+    /// Tests that frame layouts round-trip.
+    #[test]
+    fn test_time_parse_frames_round_trip() {
+        let actual: Format = "m:ss:ff".parse().expect("parse failure");
+        assert_eq!(
+            Some(&Component::Frames { width: 2 }),
+            actual.components().last()
+        );
+        assert_eq!("m:ss:ff", actual.to_string());
+    }
+
     /// Tests parsing a 2-minute/2-second/2-millisecond layout with a fairly contrived use of / as
     /// delimiter.
     #[test]
@@ -0,0 +1,417 @@
+/*! Rendering times through a [Format].
+
+A [Format] says which fields of a time to show and how wide each should be; [Options] say what to
+do about the parts of the time that don't fit: digits of precision the format drops, signs, leading
+zero fields, and fields larger than the format can show. */
+
+use super::{
+    super::{frame, human, Time},
+    Component, Format, Position,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Options for rendering a time through a [Format].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// What to do with precision that the format doesn't display.
+    pub rounding: Rounding,
+    /// When to display a sign.
+    pub sign: Sign,
+    /// Whether to drop leading fields that are zero, above the seconds field.
+    ///
+    /// When collapsing, the first field shown also loses its zero padding, so that (for instance)
+    /// `0'05"3` renders as `5"3`.
+    pub collapse_leading_zeros: bool,
+    /// What to do when the time is too large for the most significant field in the format.
+    pub overflow: Overflow,
+    /// The frame rate at which to render any frame fields in the format.
+    ///
+    /// If absent, frame fields render at the default rate of 60 frames per second.
+    pub frame_rate: Option<frame::Rate>,
+}
+
+/// How to deal with precision that a format doesn't display.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Drop undisplayed precision (so `1"999` shown to tenths is `1"9`).
+    Truncate,
+    /// Round to the nearest displayable value, with halves rounding away from zero.
+    Nearest,
+}
+
+/// By default, we truncate, as most speedrun timing does.
+impl Default for Rounding {
+    fn default() -> Self {
+        Self::Truncate
+    }
+}
+
+/// When to display a sign.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Sign {
+    /// Display `-` for negative times, and nothing otherwise.
+    Negative,
+    /// Always display either `+` or `-`; useful for deltas.
+    Always,
+    /// Never display a sign, showing the magnitude of the time only.
+    Never,
+}
+
+/// By default, only negative times get a sign.
+impl Default for Sign {
+    fn default() -> Self {
+        Self::Negative
+    }
+}
+
+/// What to do when a time is too large for the most significant field in a format.
+///
+/// This usually happens when a time has hours, but the format has no hours field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Overflow {
+    /// Fold the excess into the most significant field, widening it as needed (so 1 hour, 2
+    /// minutes in `mm'ss` is `62'00`).
+    Absorb,
+    /// Clamp the time to the largest one the format can display (so 1 hour, 40 minutes in
+    /// `mm'ss` is `99'59`).
+    Saturate,
+}
+
+/// By default, we absorb overflow, so that no information is lost.
+impl Default for Overflow {
+    fn default() -> Self {
+        Self::Absorb
+    }
+}
+
+impl Format {
+    /// Prepares `time` for rendering through this format with the given `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{format::{Format, Options, Sign}, human};
+    ///
+    /// let fmt: Format = "m'ss\"u".parse().unwrap();
+    /// let time = human::Time::new(0, 0, 5, 349);
+    /// assert_eq!("0'05\"3", fmt.render(time, Options::default()).to_string());
+    ///
+    /// let opts = Options { sign: Sign::Always, collapse_leading_zeros: true, ..Options::default() };
+    /// assert_eq!("+5\"3", fmt.render(time, opts).to_string());
+    /// ```
+    #[must_use]
+    pub fn render(&self, time: impl Into<human::Time>, options: Options) -> Rendered<'_> {
+        Rendered {
+            format: self,
+            time: time.into(),
+            options,
+        }
+    }
+
+    /// Gets the least significant position in this format, and its width.
+    fn least_significant(&self) -> Option<(Position, usize)> {
+        self.positions().min_by_key(|(p, _)| millis_in(*p))
+    }
+
+    /// Gets the most significant position in this format, and its width.
+    fn most_significant(&self) -> Option<(Position, usize)> {
+        self.positions().max_by_key(|(p, _)| millis_in(*p))
+    }
+
+    /// Gets the number of milliseconds in the smallest step this format can display.
+    fn unit(&self) -> u64 {
+        if self.has_frames() {
+            // Frames are always rendered to the nearest frame, so there is nothing to round here.
+            1
+        } else {
+            self.least_significant().map_or(1, |(p, w)| precision(p, w))
+        }
+    }
+
+    fn has_frames(&self) -> bool {
+        self.components()
+            .any(|c| matches!(c, Component::Frames { .. }))
+    }
+
+    fn positions(&self) -> impl Iterator<Item = (Position, usize)> + '_ {
+        self.components().filter_map(|c| match *c {
+            Component::Position { position, width } => Some((position, width)),
+            Component::Delimiter(_) | Component::Frames { .. } => None,
+        })
+    }
+
+    /// Gets the value of `position` within `millis`, given the positions present in this format.
+    ///
+    /// Each position absorbs any more significant positions missing from the format.
+    fn value_at(&self, millis: u64, position: Position, width: usize) -> u64 {
+        let unit = millis_in(position);
+        let above = self
+            .positions()
+            .map(|(p, _)| millis_in(p))
+            .filter(|m| unit < *m)
+            .min();
+        let value = above.map_or(millis, |m| millis % m) / unit;
+
+        // Milliseconds are displayed as a fraction, so their width affects their value.
+        if position == Position::Milliseconds {
+            scale_millis(value, width)
+        } else {
+            value
+        }
+    }
+}
+
+/// A time, ready to render through a [Format].
+///
+/// This implements [Display]; use `to_string` to get the rendered time as a string.
+#[derive(Clone, Debug)]
+pub struct Rendered<'f> {
+    format: &'f Format,
+    time: human::Time,
+    options: Options,
+}
+
+impl Display for Rendered<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.options.sign, self.time.is_negative) {
+            (Sign::Negative | Sign::Always, true) => f.write_char('-')?,
+            (Sign::Always, false) => f.write_char('+')?,
+            _ => {}
+        }
+
+        let millis = self.millis();
+        let mut is_collapsing = self.options.collapse_leading_zeros;
+        let mut is_skipping_delimiters = false;
+        for c in self.format.components() {
+            match *c {
+                Component::Delimiter(d) => {
+                    if !is_skipping_delimiters {
+                        f.write_char(d)?;
+                    }
+                }
+                Component::Position { position, width } => {
+                    let value = self.format.value_at(millis, position, width);
+                    if is_collapsing
+                        && value == 0
+                        && millis_in(Position::Seconds) < millis_in(position)
+                    {
+                        is_skipping_delimiters = true;
+                        continue;
+                    }
+                    let width = if is_collapsing { 1 } else { width };
+                    write!(f, "{value:0>width$}")?;
+                    is_collapsing = false;
+                    is_skipping_delimiters = false;
+                }
+                Component::Frames { width } => {
+                    let rate = self.options.frame_rate.unwrap_or_default();
+                    let time = Time::from_millis(i64::try_from(millis).unwrap_or(i64::MAX));
+                    let value = frame::frame_of_second(time, rate);
+                    write!(f, "{value:0>width$}")?;
+                    is_collapsing = false;
+                    is_skipping_delimiters = false;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Rendered<'_> {
+    /// Gets the magnitude of the time in milliseconds, after rounding and overflow handling.
+    fn millis(&self) -> u64 {
+        let t = self.time;
+        let millis = u64::from(t.hours) * millis_in(Position::Hours)
+            + u64::from(t.mins) * millis_in(Position::Minutes)
+            + u64::from(t.secs) * millis_in(Position::Seconds)
+            + u64::from(t.millis);
+
+        let millis = self.round(millis);
+        match self.options.overflow {
+            Overflow::Absorb => millis,
+            Overflow::Saturate => millis.min(self.max_millis()),
+        }
+    }
+
+    /// Rounds `millis` to the precision of the format's least significant position.
+    fn round(&self, millis: u64) -> u64 {
+        let unit = self.format.unit();
+        let millis = match self.options.rounding {
+            Rounding::Truncate => millis,
+            Rounding::Nearest => millis + unit / 2,
+        };
+        millis - (millis % unit)
+    }
+
+    /// Gets the largest number of milliseconds the format can display without overflowing.
+    fn max_millis(&self) -> u64 {
+        let Some((top, width)) = self.format.most_significant() else {
+            return 0;
+        };
+        let top_max = 10_u64.saturating_pow(u32::try_from(width).unwrap_or(u32::MAX)) - 1;
+        let top_max = if top == Position::Milliseconds {
+            // Milliseconds can't overflow into anything, but their displayed digits are a fraction.
+            top_max.min(999)
+        } else {
+            top_max
+        };
+        let unit = self.format.unit();
+        // Everything up to the next unit of the top position, minus the smallest displayable step.
+        (top_max * millis_in(top) + millis_in(top)).saturating_sub(unit)
+    }
+}
+
+/// The number of milliseconds in one unit of `position`.
+const fn millis_in(position: Position) -> u64 {
+    match position {
+        Position::Hours => 60 * 60 * 1000,
+        Position::Minutes => 60 * 1000,
+        Position::Seconds => 1000,
+        Position::Milliseconds => 1,
+    }
+}
+
+/// The number of milliseconds in the smallest step displayable by `position` at `width`.
+fn precision(position: Position, width: usize) -> u64 {
+    if position == Position::Milliseconds && width < 3 {
+        10_u64.pow(3 - u32::try_from(width).unwrap_or(3))
+    } else {
+        millis_in(position)
+    }
+}
+
+/// Scales a millisecond value to fit in `width` digits, as a decimal fraction of a second.
+fn scale_millis(millis: u64, width: usize) -> u64 {
+    let width = u32::try_from(width).unwrap_or(u32::MAX);
+    if width < 3 {
+        millis / 10_u64.pow(3 - width)
+    } else {
+        millis.saturating_mul(10_u64.saturating_pow(width - 3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn render(format: &str, time: human::Time, options: Options) -> String {
+        let format: Format = format.parse().expect("format should parse");
+        format.render(time, options).to_string()
+    }
+
+    /// Tests rendering through the default format.
+    #[test]
+    fn default_format() {
+        let time = human::Time::new(0, 1, 2, 345);
+        assert_eq!(
+            "01'02\"345",
+            Format::default()
+                .render(time, Options::default())
+                .to_string()
+        );
+    }
+
+    /// Tests that truncation and rounding differ when dropping millisecond digits.
+    #[test]
+    fn truncate_vs_round() {
+        let time = human::Time::new(0, 0, 1, 999);
+        assert_eq!("01.9", render("ss.u", time, Options::default()));
+
+        let nearest = Options {
+            rounding: Rounding::Nearest,
+            ..Options::default()
+        };
+        assert_eq!("02.0", render("ss.u", time, nearest));
+        // Rounding can carry all the way up through the fields.
+        let time = human::Time::new(0, 0, 59, 960);
+        assert_eq!("1'00\"0", render("m'ss\"u", time, nearest));
+        // Formats without milliseconds round to the second.
+        assert_eq!("1'00", render("m'ss", time, nearest));
+        assert_eq!("0'59", render("m'ss", time, Options::default()));
+    }
+
+    /// Tests sign handling.
+    #[test]
+    fn signs() {
+        let mut time = human::Time::new(0, 0, 5, 0);
+        let always = Options {
+            sign: Sign::Always,
+            ..Options::default()
+        };
+        let never = Options {
+            sign: Sign::Never,
+            ..Options::default()
+        };
+
+        assert_eq!("05", render("ss", time, Options::default()));
+        assert_eq!("+05", render("ss", time, always));
+
+        time.is_negative = true;
+        assert_eq!("-05", render("ss", time, Options::default()));
+        assert_eq!("-05", render("ss", time, always));
+        assert_eq!("05", render("ss", time, never));
+    }
+
+    /// Tests collapsing of leading zero fields.
+    #[test]
+    fn collapse_leading_zeros() {
+        let collapse = Options {
+            collapse_leading_zeros: true,
+            ..Options::default()
+        };
+
+        let time = human::Time::new(0, 0, 5, 300);
+        assert_eq!("5\"3", render("h:mm'ss\"u", time, collapse));
+        // Seconds never collapse, even if zero.
+        assert_eq!(
+            "0\"3",
+            render("m'ss\"u", human::Time::new(0, 0, 0, 300), collapse)
+        );
+        // Only leading zeros collapse.
+        let time = human::Time::new(1, 0, 5, 0);
+        assert_eq!("1:00'05", render("h:mm'ss", time, collapse));
+    }
+
+    /// Tests what happens when hours exist but the format has no hours field.
+    #[test]
+    fn overflow() {
+        let time = human::Time::new(1, 2, 3, 456);
+        assert_eq!("62'03\"456", render("mm'ss\"uuu", time, Options::default()));
+
+        let saturate = Options {
+            overflow: Overflow::Saturate,
+            ..Options::default()
+        };
+        assert_eq!("062'03\"456", render("mmm'ss\"uuu", time, saturate));
+        let time = human::Time::new(1, 40, 0, 0);
+        assert_eq!("99'59\"999", render("mm'ss\"uuu", time, saturate));
+        assert_eq!("99'59\"9", render("mm'ss\"u", time, saturate));
+    }
+
+    /// Tests rendering frame fields.
+    #[test]
+    fn frames() {
+        let time = human::Time::new(0, 1, 5, 500);
+        assert_eq!("1:05:30", render("m:ss:ff", time, Options::default()));
+
+        let rate = frame::Rate::new(30, 1).expect("rate should be valid");
+        let options = Options {
+            frame_rate: Some(rate),
+            ..Options::default()
+        };
+        assert_eq!("1:05:15", render("m:ss:ff", time, options));
+    }
+
+    /// Tests that a missing middle field is absorbed by the field below it.
+    #[test]
+    fn missing_middle_field() {
+        let time = human::Time::new(1, 2, 3, 0);
+        assert_eq!("1h123s", render(r"h\hs\s", time, Options::default()));
+    }
+}
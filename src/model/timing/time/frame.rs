@@ -0,0 +1,329 @@
+/*! Frame-count based timing.
+
+Many games report in-game time in frames rather than milliseconds.  A [Rate] captures the frame
+rate of such a game exactly (as a rational number of frames per second), and converts between
+frame counts and the millisecond [Time]s zombiesplit stores.
+
+Converting a frame count to milliseconds generally involves rounding, and summing rounded times
+accumulates that rounding as drift.  To avoid this, times recorded at a frame rate should be summed
+through [`Rate::sum`], which adds up frame counts and only converts the total. */
+
+use super::{
+    error::{Error, Result},
+    Position, Time,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A frame rate, stored exactly as a fraction of frames per second.
+///
+/// Rates parse from, and display as, either a whole number of frames per second (`60`), a fraction
+/// (`60000/1001`, for NTSC's 59.94), or a decimal (`59.94`, taken literally).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Repr", into = "String")]
+pub struct Rate {
+    /// The number of frames that elapse in `seconds` seconds.
+    frames: u32,
+    /// The number of seconds in which `frames` frames elapse.
+    seconds: u32,
+}
+
+/// The default rate is 60 frames per second.
+impl Default for Rate {
+    fn default() -> Self {
+        Self {
+            frames: 60,
+            seconds: 1,
+        }
+    }
+}
+
+impl Rate {
+    /// The highest supported frame rate.
+    ///
+    /// Above this rate, a frame would be shorter than a millisecond, and so times stored in
+    /// milliseconds would no longer convert back to the frame counts from which they came.
+    pub const MAX_PER_SECOND: u32 = 1000;
+
+    /// Constructs a frame rate of `frames` frames every `seconds` seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::frame::Rate;
+    ///
+    /// let ntsc = Rate::new(60_000, 1_001).expect("rate should be valid");
+    /// assert_eq!("60000/1001", ntsc.to_string());
+    /// assert!(Rate::new(60, 0).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if either part of the rate is zero, or the rate is above [`Rate::MAX_PER_SECOND`].
+    pub fn new(frames: u32, seconds: u32) -> Result<Self> {
+        if frames == 0
+            || seconds == 0
+            || u64::from(seconds) * u64::from(Self::MAX_PER_SECOND) < u64::from(frames)
+        {
+            Err(Error::FrameRate(format!("{frames}/{seconds}")))
+        } else {
+            Ok(Self { frames, seconds })
+        }
+    }
+
+    /// Converts `time` to the nearest whole number of frames at this rate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{frame::Rate, Time};
+    ///
+    /// let rate = Rate::default();
+    /// assert_eq!(60, rate.frames(Time::from_millis(1000)));
+    /// assert_eq!(1, rate.frames(Time::from_millis(17)));
+    /// assert_eq!(-1, rate.frames(Time::from_millis(-17)));
+    /// ```
+    #[must_use]
+    pub fn frames(self, time: Time) -> i64 {
+        let frames = i128::from(time.into_millis()) * i128::from(self.frames);
+        let frames = div_round(frames, i128::from(self.seconds) * 1000);
+        i64::try_from(frames).unwrap_or(if frames < 0 { i64::MIN } else { i64::MAX })
+    }
+
+    /// Converts `frames` frames at this rate to the nearest millisecond time.
+    ///
+    /// Times too large to store saturate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{frame::Rate, Time};
+    ///
+    /// let rate = Rate::default();
+    /// assert_eq!(Time::from_millis(1000), rate.time(60));
+    /// assert_eq!(Time::from_millis(17), rate.time(1));
+    /// assert_eq!(Time::from_millis(33), rate.time(2));
+    /// ```
+    #[must_use]
+    pub fn time(self, frames: i64) -> Time {
+        let millis = i128::from(frames) * i128::from(self.seconds) * 1000;
+        let millis = div_round(millis, i128::from(self.frames));
+        Time::from_millis(i64::try_from(millis).unwrap_or(if millis < 0 {
+            i64::MIN
+        } else {
+            i64::MAX
+        }))
+    }
+
+    /// Rounds `time` to the nearest frame boundary at this rate.
+    #[must_use]
+    pub fn snap(self, time: Time) -> Time {
+        self.time(self.frames(time))
+    }
+
+    /// Sums `times` by frame count, rather than by millisecond, to avoid drift.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{frame::Rate, Time};
+    ///
+    /// // Three frames at 60fps are 50ms, but each frame alone rounds to 17ms.
+    /// let rate = Rate::default();
+    /// let frame = rate.time(1);
+    /// assert_eq!(Time::from_millis(51), [frame; 3].into_iter().sum());
+    /// assert_eq!(Time::from_millis(50), rate.sum([frame; 3]));
+    /// ```
+    #[must_use]
+    pub fn sum(self, times: impl IntoIterator<Item = Time>) -> Time {
+        self.time(
+            times
+                .into_iter()
+                .fold(0, |acc, t| acc.saturating_add(self.frames(t))),
+        )
+    }
+
+    /// Gets the number of frames starting in the second starting at `second`.
+    ///
+    /// For non-integral rates, this varies from second to second.
+    fn frames_in_second(self, second: i64) -> i64 {
+        self.first_frame(second.saturating_add(1)) - self.first_frame(second)
+    }
+
+    /// Gets the index of the first frame starting in the second starting at `second`.
+    fn first_frame(self, second: i64) -> i64 {
+        let start = i128::from(second) * i128::from(self.frames);
+        i64::try_from(ceil_div(start, i128::from(self.seconds))).unwrap_or(i64::MAX)
+    }
+}
+
+/// Sums times, optionally by frame count at a given rate.
+///
+/// This is shorthand for choosing between [`Rate::sum`] and [`Time::checked_sum`].
+///
+/// # Errors
+///
+/// Fails if summing by milliseconds overflows; sums by frame count saturate.
+pub fn sum(rate: Option<Rate>, times: impl IntoIterator<Item = Time>) -> Result<Time> {
+    match rate {
+        Some(rate) => Ok(rate.sum(times)),
+        None => Time::checked_sum(times),
+    }
+}
+
+/// Divides `n` by the positive `d`, rounding halves away from zero.
+fn div_round(n: i128, d: i128) -> i128 {
+    let (q, r) = (n / d, n % d);
+    if d <= 2 * r.abs() {
+        q + n.signum()
+    } else {
+        q
+    }
+}
+
+/// Divides the non-negative `n` by the positive `d`, rounding up.
+fn ceil_div(n: i128, d: i128) -> i128 {
+    (n + d - 1) / d
+}
+
+impl Display for Rate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.seconds == 1 {
+            write!(f, "{}", self.frames)
+        } else {
+            write!(f, "{}/{}", self.frames, self.seconds)
+        }
+    }
+}
+
+impl FromStr for Rate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bad = || Error::FrameRate(s.to_owned());
+        let s = s.trim();
+        if let Some((frames, seconds)) = s.split_once('/') {
+            let frames = frames.trim().parse().map_err(|_| bad())?;
+            let seconds = seconds.trim().parse().map_err(|_| bad())?;
+            return Self::new(frames, seconds);
+        }
+
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let digits = u32::try_from(fraction.len()).map_err(|_| bad())?;
+        let seconds = 10_u32.checked_pow(digits).ok_or_else(bad)?;
+        let frames: u32 = format!("{whole}{fraction}").parse().map_err(|_| bad())?;
+        let gcd = num_integer::gcd(frames, seconds).max(1);
+        Self::new(frames / gcd, seconds / gcd)
+    }
+}
+
+impl From<Rate> for String {
+    fn from(rate: Rate) -> Self {
+        rate.to_string()
+    }
+}
+
+impl rusqlite::types::FromSql for Rate {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e| rusqlite::types::FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl rusqlite::ToSql for Rate {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(rusqlite::types::ToSqlOutput::Owned(
+            rusqlite::types::Value::Text(self.to_string()),
+        ))
+    }
+}
+
+/// Rates may appear in configuration as either numbers or strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Whole(u32),
+    Text(String),
+}
+
+impl TryFrom<Repr> for Rate {
+    type Error = Error;
+
+    fn try_from(repr: Repr) -> Result<Self> {
+        match repr {
+            Repr::Whole(frames) => Self::new(frames, 1),
+            Repr::Text(s) => s.parse(),
+        }
+    }
+}
+
+/// Parses a time in frame notation (`[[h:]m:]s:f`) at the given `rate`.
+///
+/// # Examples
+///
+/// ```
+/// use zombiesplit::model::timing::time::{frame, Time};
+///
+/// let rate = frame::Rate::default();
+/// assert_eq!(Time::from_millis(65_500), frame::parse("1:05:30", rate).unwrap());
+/// assert!(frame::parse("1:05:60", rate).is_err());
+/// assert!(frame::parse("1:05:-3", rate).is_err());
+/// ```
+///
+/// # Errors
+///
+/// Fails if the notation is malformed, if any field is too large for its position, or if the
+/// frame field names a frame that doesn't exist in its second at this rate.
+pub fn parse(s: &str, rate: Rate) -> Result<Time> {
+    let (rest, frame) = s.rsplit_once(DELIMITER).ok_or(Error::FrameNotation)?;
+    // Frames count up from the start of the second, so can't be negative.
+    let frame = i64::from(frame.parse::<u32>().map_err(Error::FrameParse)?);
+
+    let mut fields = rest.rsplit(DELIMITER);
+    let mut second = 0;
+    for (pos, secs_in_pos) in [
+        (Position::Seconds, 1),
+        (Position::Minutes, 60),
+        (Position::Hours, 60 * 60),
+    ] {
+        if let Some(field) = fields.next() {
+            second += i64::from(pos.parse_field(field)?) * secs_in_pos;
+        }
+    }
+    if fields.next().is_some() {
+        return Err(Error::FrameNotation);
+    }
+
+    let per_second = rate.frames_in_second(second);
+    if per_second <= frame {
+        return Err(Error::FrameTooBig { frame, per_second });
+    }
+    Ok(rate.time(rate.first_frame(second) + frame))
+}
+
+/// Gets the frame within its second at which `time` falls, at `rate`.
+///
+/// This is the inverse of the frame field in [parse], and ignores the sign of `time`.
+///
+/// # Examples
+///
+/// ```
+/// use zombiesplit::model::timing::time::{frame, Time};
+///
+/// let rate = frame::Rate::default();
+/// assert_eq!(30, frame::frame_of_second(Time::from_millis(65_500), rate));
+/// ```
+#[must_use]
+pub fn frame_of_second(time: Time, rate: Rate) -> i64 {
+    let frame = rate.frames(time).abs();
+    let second = i128::from(frame) * i128::from(rate.seconds) / i128::from(rate.frames);
+    frame - rate.first_frame(i64::try_from(second).unwrap_or(i64::MAX))
+}
+
+/// The delimiter between fields in frame notation.
+pub const DELIMITER: char = ':';
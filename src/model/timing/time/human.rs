@@ -2,7 +2,7 @@
 
 use super::{
     error::{Error, Result},
-    Position,
+    frame, Position,
 };
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
@@ -35,7 +35,7 @@ use std::{
 pub struct Time {
     /// Whether the time is negative.
     pub is_negative: bool,
-    /// Number of hours (0-596).
+    /// Number of hours (0-65535).
     pub hours: u16,
     /// Number of minutes (0-59).
     pub mins: u16,
@@ -60,10 +60,7 @@ impl Time {
     /// assert_eq!(45, t1.secs);
     /// assert_eq!(678, t1.millis);
     /// ```
-    ///
-    /// # Errors
-    ///
-    /// Errors if the amount for any field is too high to store.
+    #[must_use]
     pub fn new(hours: u16, mins: u16, secs: u16, millis: u16) -> Self {
         Self {
             is_negative: false,
@@ -74,7 +71,18 @@ impl Time {
         }
     }
 
-    /// Converts a time given as a 32-bit millisecond timestamp to a human time.
+    /// The largest human time.
+    pub const MAX: Self = Self {
+        is_negative: false,
+        hours: u16::MAX,
+        mins: 59,
+        secs: 59,
+        millis: 999,
+    };
+
+    /// Converts a time given as a 64-bit millisecond timestamp to a human time.
+    ///
+    /// Timestamps too large to represent saturate to [`Time::MAX`] (or its negation).
     ///
     /// # Example
     ///
@@ -89,12 +97,25 @@ impl Time {
     /// assert_eq!(u16::from(time.secs), 56);
     /// assert_eq!(u16::from(time.millis), 789);
     /// ```
-    pub fn from_millis(millis: i32) -> Self {
+    ///
+    /// # Panics
+    ///
+    /// Panics only if the field positions are inconsistent with each other, which is a bug.
+    #[must_use]
+    pub fn from_millis(millis: i64) -> Self {
+        let magnitude = millis.unsigned_abs();
+        if Self::MAX.magnitude() < magnitude {
+            return Self {
+                is_negative: millis.is_negative(),
+                ..Self::MAX
+            };
+        }
+
         let mut result = Self::default();
         // What we put here is not important, as it'll get overwritten immediately.
         // The exception is the overflow, which needs to start off as the whole timestamp.
         let mut fit = super::position::Fit {
-            overflow: millis.unsigned_abs(),
+            overflow: magnitude,
             input: 0,
             result: super::position::Value {
                 value: 0,
@@ -102,24 +123,42 @@ impl Time {
             },
         };
 
-        // Reverse iterator gives us millis, secs, mins, hours.
-        for p in Position::ALL.iter().rev() {
+        // Reverse iterator gives us millis, secs, mins; hours take whatever is left.
+        for p in Position::ALL.iter().skip(1).rev() {
             fit = p.fit(fit.overflow);
             assert_eq!(fit.result.field, *p, "fit should return the same field");
             result[*p] = fit.result.value;
         }
+        result.hours = u16::try_from(fit.overflow).expect("saturation should bound hours");
 
         result.is_negative = millis.is_negative();
         result
     }
 
-    /// Converts a [Time] into a 32-bit millisecond timestamp.
+    /// Converts a [Time] into a 64-bit millisecond timestamp.
     ///
     /// # Errors
     ///
-    /// Fails if the time doesn't fit into a signed 32-bit integer.
-    pub fn try_into_millis(self) -> Result<i32> {
-        Position::ALL.iter().map(|f| self.field_ms(*f)).sum()
+    /// Fails if the time doesn't fit into a signed 64-bit integer.
+    pub fn try_into_millis(self) -> Result<i64> {
+        let magnitude = Position::ALL.iter().try_fold(0_i64, |acc, f| {
+            acc.checked_add(self.field_ms(*f)?)
+                .ok_or(Error::MsecOverflow(self))
+        })?;
+        Ok(if self.is_negative {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+
+    /// Gets the magnitude of this time in milliseconds.
+    #[must_use]
+    pub(super) fn magnitude(self) -> u64 {
+        Position::ALL
+            .iter()
+            .map(|f| u64::from(f.ms_offset()) * u64::from(self[*f]))
+            .sum()
     }
 
     /// Tries to construct a [Time] from a given number of seconds.
@@ -148,28 +187,50 @@ impl Time {
     /// Errors if the number of seconds is too high to store in this time.
     pub fn seconds(amount: i32) -> Result<Self> {
         Ok(Self::from_millis(
-            amount.checked_mul(1000).ok_or(Error::SecOverflow(amount))?,
+            i64::from(amount)
+                .checked_mul(1000)
+                .ok_or(Error::SecOverflow(amount))?,
         ))
     }
 
+    /// Parses a time in frame notation (`[[h:]m:]s:f`), at the frame rate `rate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{frame, human};
+    ///
+    /// let rate = frame::Rate::new(30, 1).expect("rate should be valid");
+    /// let t = human::Time::parse_frames("1:02:15", rate).expect("should be valid");
+    /// assert_eq!(1, t.mins);
+    /// assert_eq!(2, t.secs);
+    /// assert_eq!(500, t.millis);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if `s` isn't valid frame notation at `rate`.
+    pub fn parse_frames(s: &str, rate: frame::Rate) -> Result<Self> {
+        frame::parse(s, rate).map(Self::from)
+    }
+
     /// Gets whether this time is zero.
     ///
     /// # Example
     ///
     /// ```
     /// use zombiesplit::model::timing::time::human;
-    /// use std::convert::TryFrom;
-    /// assert!(human::Time::try_from(0).unwrap().is_zero());
-    /// assert!(!human::Time::try_from(1).unwrap().is_zero());
+    /// assert!(human::Time::from_millis(0).is_zero());
+    /// assert!(!human::Time::from_millis(1).is_zero());
     /// ```
     #[must_use]
     pub const fn is_zero(&self) -> bool {
         self.hours == 0 && self.mins == 0 && self.secs == 0 && self.millis == 0
     }
 
-    fn field_ms(&self, field: Position) -> Result<i32> {
-        let offset = i32::from(field.ms_offset());
-        let base = i32::from(self[field]);
+    fn field_ms(&self, field: Position) -> Result<i64> {
+        let offset = i64::from(field.ms_offset());
+        let base = i64::from(self[field]);
         offset.checked_mul(base).ok_or(Error::MsecOverflow(*self))
     }
 }
@@ -179,6 +240,8 @@ impl Time {
 //
 
 /// Conversion from internal times to human times.
+///
+/// Times too large to represent as human times saturate.
 impl From<super::Time> for Time {
     fn from(time: super::Time) -> Self {
         Self::from_millis(time.into_millis())
@@ -194,18 +257,17 @@ impl TryFrom<Time> for super::Time {
     /// # Example
     ///
     /// ```
-    /// use zombiesplit::model::timing::time::human;
-    /// use std::convert::TryFrom;
-    /// let msec = 1234567;
-    /// let time = human::Time::try_from(msec).expect("should not overflow");
-    /// assert_eq!(u32::from(time), msec);
+    /// use zombiesplit::model::timing::time::{self, human};
+    /// let msec = 1_234_567;
+    /// let time = time::Time::try_from(human::Time::from_millis(msec)).expect("should not overflow");
+    /// assert_eq!(time.into_millis(), msec);
     /// ```
     ///
     /// # Errors
     ///
     /// Fails if the time is too large to fit into a timestamps.
     fn try_from(time: Time) -> Result<super::Time> {
-        let millis: Result<i32> = time.try_into_millis();
+        let millis: Result<i64> = time.try_into_millis();
         millis.map(super::Time::from_millis)
     }
 }
@@ -221,15 +283,9 @@ impl fmt::Display for Time {
 impl FromStr for Time {
     type Err = Error;
 
+    /// Parses a time in any of the notations accepted by [`super::parse::human`].
     fn from_str(s: &str) -> Result<Self> {
-        let mut result = Self::default();
-        let mut rest = s;
-        for p in Position::ALL {
-            let (val, r) = p.split_and_parse(s)?;
-            result[*p] = val;
-            rest = r;
-        }
-        Ok(result)
+        super::parse::human(s)
     }
 }
 
@@ -265,65 +321,65 @@ mod tests {
     #[test]
     fn time_from_str_empty() {
         let t: Time = "".parse().expect("should be valid");
-        assert_eq!(u16::from(t.hours), 0);
-        assert_eq!(u16::from(t.mins), 0);
-        assert_eq!(u16::from(t.secs), 0);
-        assert_eq!(u16::from(t.millis), 0);
+        assert_eq!(t.hours, 0);
+        assert_eq!(t.mins, 0);
+        assert_eq!(t.secs, 0);
+        assert_eq!(t.millis, 0);
     }
 
     #[test]
     fn time_from_str_msec_only() {
         // This case may be removed later on, it's a bit weird.
         let t: Time = "123".parse().expect("should be valid");
-        assert_eq!(u16::from(t.hours), 0);
-        assert_eq!(u16::from(t.mins), 0);
-        assert_eq!(u16::from(t.secs), 0);
-        assert_eq!(u16::from(t.millis), 123);
+        assert_eq!(t.hours, 0);
+        assert_eq!(t.mins, 0);
+        assert_eq!(t.secs, 0);
+        assert_eq!(t.millis, 123);
     }
 
     #[test]
     fn time_from_str_msec_short() {
         let t: Time = "02".parse().expect("should be valid");
-        assert_eq!(u16::from(t.hours), 0);
-        assert_eq!(u16::from(t.mins), 0);
-        assert_eq!(u16::from(t.secs), 0);
-        assert_eq!(u16::from(t.millis), 20);
+        assert_eq!(t.hours, 0);
+        assert_eq!(t.mins, 0);
+        assert_eq!(t.secs, 0);
+        assert_eq!(t.millis, 20);
     }
 
     #[test]
     fn time_from_str_secs_only() {
         let t: Time = "10s".parse().expect("should be valid");
-        assert_eq!(u16::from(t.hours), 0);
-        assert_eq!(u16::from(t.mins), 0);
-        assert_eq!(u16::from(t.secs), 10);
-        assert_eq!(u16::from(t.millis), 0);
+        assert_eq!(t.hours, 0);
+        assert_eq!(t.mins, 0);
+        assert_eq!(t.secs, 10);
+        assert_eq!(t.millis, 0);
     }
 
     #[test]
     fn time_from_str_secs_msec() {
         let t: Time = "10s50".parse().expect("should be valid");
-        assert_eq!(u16::from(t.hours), 0);
-        assert_eq!(u16::from(t.mins), 0);
-        assert_eq!(u16::from(t.secs), 10);
-        assert_eq!(u16::from(t.millis), 500);
+        assert_eq!(t.hours, 0);
+        assert_eq!(t.mins, 0);
+        assert_eq!(t.secs, 10);
+        assert_eq!(t.millis, 500);
     }
 
     #[test]
     fn time_from_str_all() {
         let t: Time = "1h2m3s456".parse().expect("should be valid");
-        assert_eq!(u16::from(t.hours), 1);
-        assert_eq!(u16::from(t.mins), 2);
-        assert_eq!(u16::from(t.secs), 3);
-        assert_eq!(u16::from(t.millis), 456);
+        assert_eq!(t.hours, 1);
+        assert_eq!(t.mins, 2);
+        assert_eq!(t.secs, 3);
+        assert_eq!(t.millis, 456);
     }
 
     /// Tests that indexing seems to work properly.
     #[test]
     fn index() {
         let t: Time = "1h2m3s456".parse().expect("should be valid");
-        assert_eq!("01", t[Position::Hours].to_string());
-        assert_eq!("02", t[Position::Minutes].to_string());
-        assert_eq!("03", t[Position::Seconds].to_string());
-        assert_eq!("456", t[Position::Milliseconds].to_string());
+        assert_eq!(1, t[Position::Hours]);
+        assert_eq!(2, t[Position::Minutes]);
+        assert_eq!(3, t[Position::Seconds]);
+        assert_eq!(456, t[Position::Milliseconds]);
     }
 }
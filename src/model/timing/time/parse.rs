@@ -0,0 +1,404 @@
+//! Tolerant parsing of human-entered times.
+//!
+//! Times reach zombiesplit from many places: run files, game configuration, and whatever the user
+//! pastes into a client from speedrun.com, `LiveSplit`, or a game's own results screen.  The parser
+//! here accepts all of the following (each optionally preceded by `-`):
+//!
+//! - colon notation, `[[h:]m:]s[.fff]`, as in `1:02:03.456`, `62:03.45`, or `3.456`;
+//! - unit notation, as in `1h2m3s456`, `1h 2m 3.456s`, or `3s 500ms`;
+//! - a bare number of milliseconds, zero-padded on the right, as in `456` or `02`.
+//!
+//! The most significant field given may exceed its usual range (so `62:03.45` is one hour, two
+//! minutes, three seconds, and 450 milliseconds), but every other field must be in range.  Digits
+//! past the third in a fraction of a second are truncated.
+//!
+//! Errors report the 1-based column of the character at fault.
+
+use super::{
+    error::{Error, Result},
+    human, Position,
+};
+use std::{iter::Peekable, str::CharIndices};
+
+/// Parses `s` as a human time in any of the notations described in the [module](self) docs.
+///
+/// # Examples
+///
+/// ```
+/// use zombiesplit::model::timing::time::{human, parse};
+///
+/// let want = human::Time::new(1, 2, 3, 456);
+/// assert_eq!(want, parse::human("1:02:03.456").expect("colon notation"));
+/// assert_eq!(want, parse::human("62:03.456").expect("overlong minutes"));
+/// assert_eq!(want, parse::human("1h 2m 3.456s").expect("unit notation"));
+/// assert_eq!(want, parse::human("1h2m3s456").expect("legacy notation"));
+/// ```
+///
+/// # Errors
+///
+/// Fails if `s` isn't in any of the accepted notations, or describes a time too large to store;
+/// the error gives the column of the offending character.
+pub fn human(s: &str) -> Result<human::Time> {
+    let mut cur = Cursor::new(s);
+    cur.skip_whitespace();
+    let is_negative = cur.eat('-');
+
+    let rest = &s[cur.offset()..];
+    let fields = if rest.contains(':') {
+        colons(&mut cur)?
+    } else if rest.contains(|c: char| c.is_ascii_alphabetic()) {
+        units(&mut cur)?
+    } else if rest.contains('.') {
+        colons(&mut cur)?
+    } else {
+        bare(&mut cur)?
+    };
+
+    let magnitude = total(&fields)?;
+    // The total is bounded by human::Time::MAX, so this can't saturate in practice.
+    let millis = i64::try_from(magnitude).unwrap_or(i64::MAX);
+    Ok(human::Time::from_millis(if is_negative {
+        -millis
+    } else {
+        millis
+    }))
+}
+
+/// A field value read from the input, with the column at which it started.
+struct Field {
+    position: Position,
+    value: u64,
+    column: usize,
+}
+
+/// Parses the remainder of `cur` in colon notation.
+fn colons(cur: &mut Cursor) -> Result<Vec<Field>> {
+    let mut numbers = vec![cur.number()?];
+    while cur.eat(':') {
+        numbers.push(cur.number()?);
+    }
+    let fraction = if cur.eat('.') {
+        Some(cur.fraction()?)
+    } else {
+        None
+    };
+    cur.skip_whitespace();
+    cur.expect_end()?;
+
+    // Colon notation counts from seconds upwards, so assign positions from the right.
+    let positions = [Position::Seconds, Position::Minutes, Position::Hours];
+    if let Some((column, _)) = numbers.iter().rev().nth(positions.len()) {
+        return Err(Error::TooManyFields { column: *column });
+    }
+    let mut fields: Vec<Field> = numbers
+        .into_iter()
+        .rev()
+        .zip(positions)
+        .map(|((column, value), position)| Field {
+            position,
+            value,
+            column,
+        })
+        .collect();
+    fields.reverse();
+    fields.extend(fraction);
+    Ok(fields)
+}
+
+/// Parses the remainder of `cur` in unit notation.
+fn units(cur: &mut Cursor) -> Result<Vec<Field>> {
+    let mut fields: Vec<Field> = vec![];
+    loop {
+        cur.skip_whitespace();
+        if cur.peek().is_none() {
+            return Ok(fields);
+        }
+
+        let column = cur.column;
+        let digits = cur.digits()?;
+        let fraction = if cur.eat('.') {
+            Some(cur.fraction()?)
+        } else {
+            None
+        };
+
+        cur.skip_whitespace();
+        if cur.peek().is_none() {
+            // A trailing number straight after seconds is a fraction, as in `10s50`.
+            let after_seconds = fields
+                .last()
+                .is_some_and(|f| f.position == Position::Seconds);
+            if after_seconds && fraction.is_none() {
+                fields.push(fraction_field(digits, column)?);
+                return Ok(fields);
+            }
+            return Err(Error::MissingUnit { column });
+        }
+
+        let unit_column = cur.column;
+        let position = cur.unit()?;
+        if fraction.is_some() && position != Position::Seconds {
+            return Err(Error::FractionNotSeconds {
+                column: unit_column,
+                pos: position,
+            });
+        }
+        if fields
+            .last()
+            .is_some_and(|f| significance(f.position) <= significance(position))
+        {
+            return Err(Error::OutOfOrder {
+                column,
+                pos: position,
+            });
+        }
+
+        fields.push(Field {
+            position,
+            value: whole(digits),
+            column,
+        });
+        fields.extend(fraction);
+    }
+}
+
+/// Parses the remainder of `cur` as a bare, right-padded millisecond count.
+fn bare(cur: &mut Cursor) -> Result<Vec<Field>> {
+    cur.skip_whitespace();
+    if cur.peek().is_none() {
+        return Ok(vec![]);
+    }
+    let field = cur.fraction()?;
+    cur.skip_whitespace();
+    cur.expect_end()?;
+    Ok(vec![field])
+}
+
+/// Sums `fields` into a millisecond magnitude, range-checking each field.
+fn total(fields: &[Field]) -> Result<u64> {
+    let mut total: u64 = 0;
+    for (i, field) in fields.iter().enumerate() {
+        // Only the most significant field may overflow into the fields above it.
+        let in_range = i == 0 || field.value < u64::from(field.position.capacity());
+        total = field
+            .value
+            .checked_mul(u64::from(field.position.ms_offset()))
+            .and_then(|ms| total.checked_add(ms))
+            .filter(|total| in_range && *total <= human::Time::MAX.magnitude())
+            .ok_or(Error::OutOfRange {
+                column: field.column,
+                pos: field.position,
+            })?;
+    }
+    Ok(total)
+}
+
+/// Parses a non-empty run of digits as a whole number.
+fn whole(digits: &str) -> u64 {
+    // Digit runs can only fail to parse by being too large, which the range checks catch.
+    digits.parse().unwrap_or(u64::MAX)
+}
+
+/// Interprets `digits`, starting at `column`, as the digits of a fraction of a second.
+fn fraction_field(digits: &str, column: usize) -> Result<Field> {
+    // Anything past millisecond precision is truncated.
+    let digits = &digits[..digits.len().min(Position::Milliseconds.default_width())];
+    let (value, _) = Position::Milliseconds.split_and_parse(digits)?;
+    Ok(Field {
+        position: Position::Milliseconds,
+        value: u64::from(value),
+        column,
+    })
+}
+
+fn significance(position: Position) -> usize {
+    Position::ALL.len()
+        - Position::ALL
+            .iter()
+            .position(|p| *p == position)
+            .expect("all positions should be in ALL")
+}
+
+/// A cursor over the characters of an input string, tracking 1-based columns for diagnostics.
+struct Cursor<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
+            column: 1,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.input.len(), |(i, _)| *i)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let (_, c) = self.chars.next()?;
+        self.column += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, want: char) -> bool {
+        let found = self.peek() == Some(want);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(found) => Err(Error::Unexpected {
+                column: self.column,
+                found,
+            }),
+        }
+    }
+
+    /// Reads a non-empty run of ASCII digits.
+    fn digits(&mut self) -> Result<&'a str> {
+        let start = self.offset();
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+        let end = self.offset();
+        if start == end {
+            Err(match self.peek() {
+                None => Error::ExpectedDigits {
+                    column: self.column,
+                },
+                Some(found) => Error::Unexpected {
+                    column: self.column,
+                    found,
+                },
+            })
+        } else {
+            Ok(&self.input[start..end])
+        }
+    }
+
+    /// Reads a whole number, returning it along with its starting column.
+    fn number(&mut self) -> Result<(usize, u64)> {
+        let column = self.column;
+        Ok((column, whole(self.digits()?)))
+    }
+
+    /// Reads the digits of a fraction of a second.
+    fn fraction(&mut self) -> Result<Field> {
+        let column = self.column;
+        fraction_field(self.digits()?, column)
+    }
+
+    /// Reads a unit suffix.
+    fn unit(&mut self) -> Result<Position> {
+        let column = self.column;
+        match self.bump() {
+            Some('h') => Ok(Position::Hours),
+            Some('m') if self.eat('s') => Ok(Position::Milliseconds),
+            Some('m') => Ok(Position::Minutes),
+            Some('s') => Ok(Position::Seconds),
+            Some(found) => Err(Error::Unexpected { column, found }),
+            None => Err(Error::MissingUnit { column }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(s: &str) -> human::Time {
+        human(s).unwrap_or_else(|e| panic!("{s:?} should parse: {e}"))
+    }
+
+    #[test]
+    fn colon_notation() {
+        assert_eq!(human::Time::new(1, 2, 3, 456), ok("1:02:03.456"));
+        assert_eq!(human::Time::new(1, 2, 3, 450), ok("62:03.45"));
+        assert_eq!(human::Time::new(0, 0, 3, 456), ok("3.456"));
+        assert_eq!(human::Time::new(0, 1, 5, 0), ok("1:05"));
+        assert_eq!(human::Time::new(0, 0, 3, 456), ok("3.4567890"));
+    }
+
+    #[test]
+    fn unit_notation() {
+        assert_eq!(human::Time::new(1, 2, 3, 456), ok("1h 2m 3.456s"));
+        assert_eq!(human::Time::new(1, 2, 3, 456), ok("1h2m3s456"));
+        assert_eq!(human::Time::new(0, 0, 10, 500), ok("10s50"));
+        assert_eq!(human::Time::new(0, 0, 3, 500), ok("3s 500ms"));
+        assert_eq!(human::Time::new(0, 1, 30, 0), ok("90s"));
+    }
+
+    #[test]
+    fn bare_and_signed() {
+        assert_eq!(human::Time::new(0, 0, 0, 20), ok("02"));
+        assert_eq!(human::Time::default(), ok("  "));
+        let t = ok(" -1:00 ");
+        assert!(t.is_negative);
+        assert_eq!(1, t.mins);
+    }
+
+    #[test]
+    fn errors_point_at_offending_character() {
+        assert_eq!(
+            Err(Error::Unexpected {
+                column: 5,
+                found: 'x'
+            }),
+            human("1:02x")
+        );
+        assert_eq!(Err(Error::ExpectedDigits { column: 3 }), human("1:"));
+        assert_eq!(
+            Err(Error::OutOfRange {
+                column: 3,
+                pos: Position::Seconds
+            }),
+            human("1:75")
+        );
+        assert_eq!(
+            Err(Error::OutOfOrder {
+                column: 4,
+                pos: Position::Hours
+            }),
+            human("2m 1h")
+        );
+        assert_eq!(Err(Error::TooManyFields { column: 1 }), human("1:2:3:4"));
+        assert_eq!(Err(Error::MissingUnit { column: 3 }), human("1h2"));
+        assert_eq!(
+            Err(Error::FractionNotSeconds {
+                column: 4,
+                pos: Position::Minutes
+            }),
+            human("1.5m")
+        );
+    }
+
+    #[test]
+    fn too_large() {
+        assert!(matches!(
+            human("65536:00:00"),
+            Err(Error::OutOfRange {
+                column: 1,
+                pos: Position::Hours
+            })
+        ));
+    }
+}
@@ -60,11 +60,12 @@ impl Position {
     }
 
     /// The multiplier needed to convert this position to milliseconds.
-    pub(super) const fn ms_offset(self) -> u16 {
+    pub(super) const fn ms_offset(self) -> u32 {
+        // Widening casts; `u32::from` isn't const.
         match self {
-            Self::Hours => MINS_IN_HOUR * SECS_IN_MIN * MSECS_IN_SEC,
-            Self::Minutes => SECS_IN_MIN * MSECS_IN_SEC,
-            Self::Seconds => MSECS_IN_SEC,
+            Self::Hours => MINS_IN_HOUR as u32 * SECS_IN_MIN as u32 * MSECS_IN_SEC as u32,
+            Self::Minutes => SECS_IN_MIN as u32 * MSECS_IN_SEC as u32,
+            Self::Seconds => MSECS_IN_SEC as u32,
             Self::Milliseconds => 1,
         }
     }
@@ -97,12 +98,29 @@ impl Position {
             .map(|v| (v, after))
     }
 
+    /// Parses `s` as an undelimited, unpadded value at this position, as in frame notation.
+    ///
+    /// Unlike [`Position::split_and_parse`], this rejects values too large for the position.
+    pub(super) fn parse_field(self, s: &str) -> Result<u16> {
+        let val: u16 = s
+            .parse()
+            .map_err(|err: ParseIntError| Error::FieldParse { pos: self, err })?;
+        if val < self.capacity() {
+            Ok(val)
+        } else {
+            Err(Error::FieldTooBig {
+                pos: self,
+                val: u32::from(val),
+            })
+        }
+    }
+
     /// Performs any preprocessing that should be done to a string before
     /// parsing it as a field.
     ///
     /// For milliseconds, this involves left-padding it.
     #[must_use]
-    fn preprocess_string(self, s: &str) -> Cow<str> {
+    fn preprocess_string(self, s: &str) -> Cow<'_, str> {
         let digits = self.default_width();
         if matches!(self, Self::Milliseconds) && s.len() < digits {
             Cow::Owned(format!("{s:0<digits$}"))
@@ -133,11 +151,11 @@ impl Position {
 
         // Truncate any unneeded rightmost zeroes from a milliseconds display.
         if self == Self::Milliseconds && width < nd {
-            let to_drop = width - nd;
-            v /= (10_u16).saturating_pow(to_drop.try_into().unwrap_or(1))
+            let to_drop = nd - width;
+            v /= (10_u16).saturating_pow(to_drop.try_into().unwrap_or(1));
         }
 
-        write!(f, "{:0>width$}", v, width = width)
+        write!(f, "{v:0>width$}")
     }
 
     /// Formats the value `v` with a delimiter, if nonzero.
@@ -156,33 +174,8 @@ impl Position {
     }
 
     /// Tries to fit `input` into this position, returning the result as well as any carry.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use zombiesplit::model::timing::time::position::{Position, Fit, Value};
-    ///
-    /// // 12345 milliseconds is 12 seconds 345 milliseconds
-    /// assert_eq!(
-    ///     Fit {
-    ///         input: 1234,
-    ///         result: Value {field: Position::Milliseconds, value: 345},
-    ///         overflow: 12
-    ///     },
-    ///     Position::Milliseconds::fit(12345)
-    /// );
-    /// // 320 seconds is 5 minutes 20 seconds
-    /// assert_eq!(
-    ///     Fit {
-    ///         input: 320,
-    ///         result: Value {field: Position::Seconds, value: 20},
-    ///         overflow: 5
-    ///     },
-    ///     Position::Seconds::fit(320)
-    /// );
-    /// ```
-    pub(super) fn fit(self, input: u32) -> Fit {
-        let divisor = u32::from(self.capacity());
+    pub(super) fn fit(self, input: u64) -> Fit {
+        let divisor = u64::from(self.capacity());
         let (overflow, rem) = input.div_rem(&divisor);
         let value = rem.try_into().expect("position overflows should be <u16");
         let result = Value { field: self, value };
@@ -199,9 +192,9 @@ pub struct Fit {
     /// The result (input modulo the capacity of the field).
     pub result: Value,
     /// Any overflow into the next position.
-    pub overflow: u32,
+    pub overflow: u64,
     /// The original value, stored for error reporting.
-    pub input: u32,
+    pub input: u64,
 }
 
 /// A combination of a field and value.
@@ -225,6 +218,26 @@ impl Display for Value {
 mod tests {
     use super::*;
 
+    /// Tests that fitting milliseconds carries whole seconds.
+    #[test]
+    fn fit_milliseconds() {
+        let fit = Position::Milliseconds.fit(12345);
+        assert_eq!(12345, fit.input);
+        assert_eq!(Position::Milliseconds, fit.result.field);
+        assert_eq!(345, fit.result.value);
+        assert_eq!(12, fit.overflow);
+    }
+
+    /// Tests that fitting seconds carries whole minutes.
+    #[test]
+    fn fit_seconds() {
+        let fit = Position::Seconds.fit(320);
+        assert_eq!(320, fit.input);
+        assert_eq!(Position::Seconds, fit.result.field);
+        assert_eq!(20, fit.result.value);
+        assert_eq!(5, fit.overflow);
+    }
+
     /// Tests that the unusual parsing behaviour of milliseconds works properly.
     mod msec {
         use super::*;
@@ -234,14 +247,13 @@ mod tests {
             let (t, rest) = Position::Milliseconds
                 .split_and_parse(from)
                 .expect("should be valid");
-            assert_eq!(u16::from(t), want);
+            assert_eq!(t, want);
             // We should consume the whole string.
             assert_eq!("", rest);
         }
 
         /// Tests a millisecond display.
         fn test_display(from: u16, want: &'static str) {
-            let mut buf = String::new();
             let t = Value {
                 field: Position::Milliseconds,
                 value: from,
@@ -341,9 +353,9 @@ mod tests {
         /// Tests that truncating a millisecond field to two digits drops digits from the right.
         #[test]
         fn display_three_digits_as_two() {
-            let t = Field {
-                val: 123,
-                position: Position::Milliseconds,
+            let t = Value {
+                field: Position::Milliseconds,
+                value: 123,
             };
             assert_eq!(format!("{t:2}"), "12");
         }
@@ -351,9 +363,9 @@ mod tests {
         /// Tests that stretching a millisecond field to four digits zero-pads on the left.
         #[test]
         fn display_three_digits_as_four() {
-            let t: Field = Field {
-                position: Position::Milliseconds,
-                val: 123,
+            let t = Value {
+                field: Position::Milliseconds,
+                value: 123,
             };
             assert_eq!(format!("{t:4}"), "0123");
         }
@@ -3,10 +3,15 @@
 zombiesplit uses a client/server separation whereby the client and server talk to each other using
 a protocol defined in `proto`.  The netcode is asynchronous and built using tokio. */
 
+// `tonic` fixes the error type, and its `Status` is large.
+#![allow(clippy::result_large_err)]
+
 pub mod client;
 pub mod metadata;
 pub mod proto;
 pub mod server;
+pub mod tls;
+pub mod version;
 
 pub use client::Client;
 pub use server::Manager;
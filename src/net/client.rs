@@ -6,9 +6,12 @@ thread in a synchronous context). */
 mod error;
 
 use super::{
-    super::model::session::{self, action::Handler},
+    super::{
+        config::tls,
+        model::session::{self, action::Handler},
+    },
     client::error::Error,
-    proto,
+    metadata, proto, version,
 };
 use error::Result;
 use std::sync::Arc;
@@ -30,7 +33,7 @@ impl<O: session::Observer> Handler for Sync<O> {
         self.rt.block_on(self.inner.dump())
     }
 
-    fn handle(&mut self, a: session::Action) -> Result<()> {
+    fn handle(&mut self, a: session::Action) -> Result<Option<session::sink::Outcome>> {
         self.rt.block_on(self.inner.handle_action(a))
     }
 }
@@ -42,6 +45,34 @@ impl<O: session::Observer> Sync<O> {
     ///
     /// Fails if we can't create a TCP connection to `addr`.
     pub fn new<A: TryInto<tonic::transport::Uri>>(addr: A, observer: O) -> Result<Self>
+    where
+        error::Error: From<<A as TryInto<tonic::transport::Uri>>::Error>,
+    {
+        Self::connect(addr, None, observer)
+    }
+
+    /// Creates a new client listening to the server at `addr` over TLS, configured by `tls`, and
+    /// observing events with `observer`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if we can't load the TLS configuration, or create a TCP connection to `addr`.
+    pub fn new_tls<A: TryInto<tonic::transport::Uri>>(
+        addr: A,
+        tls: &tls::Client,
+        observer: O,
+    ) -> Result<Self>
+    where
+        error::Error: From<<A as TryInto<tonic::transport::Uri>>::Error>,
+    {
+        Self::connect(addr, Some(tls), observer)
+    }
+
+    fn connect<A: TryInto<tonic::transport::Uri>>(
+        addr: A,
+        tls: Option<&tls::Client>,
+        observer: O,
+    ) -> Result<Self>
     where
         error::Error: From<<A as TryInto<tonic::transport::Uri>>::Error>,
     {
@@ -50,7 +81,7 @@ impl<O: session::Observer> Sync<O> {
                 .enable_all()
                 .build()?,
         );
-        let inner = rt.block_on(Client::new(addr, observer))?;
+        let inner = rt.block_on(Client::connect(addr, tls, observer))?;
         Ok(Self { inner, rt })
     }
 
@@ -101,7 +132,7 @@ impl<O: session::Observer + Clone + Send + 'static> Sync<O> {
 
         (client_code)(state, self)?;
 
-        csend.send(()).map_err(|_| Error::ObserverCancelFail)?;
+        csend.send(()).map_err(|()| Error::ObserverCancelFail)?;
 
         // TODO(@MattWindsor91): make this work
 
@@ -115,13 +146,24 @@ impl<O: session::Observer + Clone + Send + 'static> Sync<O> {
     }
 }
 
+/// The `gRPC` client type, which declares our protocol version on every request.
+type Grpc = proto::zombiesplit_client::ZombiesplitClient<
+    tonic::codegen::InterceptedService<tonic::transport::Channel, Interceptor>,
+>;
+
+type Interceptor = fn(tonic::Request<()>) -> std::result::Result<tonic::Request<()>, tonic::Status>;
+
 /// An asynchronous zombiesplit network client.
 #[derive(Clone)]
 pub struct Client<O> {
     /// The gRPC channel connecting to the server.
-    grpc: proto::zombiesplit_client::ZombiesplitClient<tonic::transport::Channel>,
+    grpc: Grpc,
+    /// Information about the server, retrieved on connection.
+    server: metadata::Server,
     /// The observer we use to send events from the server.
     observer: O,
+    /// The filter the server applies to the events it sends us.
+    filter: session::event::Filter,
 }
 
 impl<O: session::Observer> Client<O> {
@@ -129,18 +171,70 @@ impl<O: session::Observer> Client<O> {
     ///
     /// # Errors
     ///
-    /// Fails if we can't create a TCP connection to `addr`.
+    /// Fails if we can't create a TCP connection to `addr`, or the server speaks an incompatible
+    /// protocol version.
     pub async fn new<A>(addr: A, observer: O) -> Result<Self>
     where
         A: TryInto<tonic::transport::Uri>,
         error::Error: From<<A as TryInto<tonic::transport::Uri>>::Error>,
     {
+        Self::connect(addr, None, observer).await
+    }
+
+    /// Creates a new client listening to the server at `addr` over TLS, configured by `tls`, and
+    /// observing events with `observer`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if we can't load the TLS configuration, create a TCP connection to `addr`, or the
+    /// server speaks an incompatible protocol version.
+    pub async fn new_tls<A>(addr: A, tls: &tls::Client, observer: O) -> Result<Self>
+    where
+        A: TryInto<tonic::transport::Uri>,
+        error::Error: From<<A as TryInto<tonic::transport::Uri>>::Error>,
+    {
+        Self::connect(addr, Some(tls), observer).await
+    }
+
+    async fn connect<A>(addr: A, tls: Option<&tls::Client>, observer: O) -> Result<Self>
+    where
+        A: TryInto<tonic::transport::Uri>,
+        error::Error: From<<A as TryInto<tonic::transport::Uri>>::Error>,
+    {
+        let mut endpoint = tonic::transport::Endpoint::from(addr.try_into()?);
+        if let Some(tls) = tls {
+            endpoint = endpoint.tls_config(super::tls::client_config(tls)?)?;
+        }
+        let mut grpc = proto::zombiesplit_client::ZombiesplitClient::with_interceptor(
+            endpoint.connect().await?,
+            version::declare as Interceptor,
+        );
+        let server = server_info(&mut grpc).await?;
         Ok(Self {
-            grpc: proto::zombiesplit_client::ZombiesplitClient::connect(addr.try_into()?).await?,
+            grpc,
+            server,
             observer,
+            filter: session::event::Filter::default(),
         })
     }
 
+    /// Restricts the events this client observes to those allowed by `filter`.
+    ///
+    /// By default, the client observes every event, which clients mirroring the whole session
+    /// state need; overlays and bots that only care about some events can filter the rest out
+    /// on the server.
+    #[must_use]
+    pub fn with_filter(mut self, filter: session::event::Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Gets the information the server gave about itself when we connected.
+    #[must_use]
+    pub fn server_info(&self) -> &metadata::Server {
+        &self.server
+    }
+
     /// Runs an observer loop for the client.
     ///
     /// The loop will close when the given one-shot is called.
@@ -171,7 +265,7 @@ impl<O: session::Observer> Client<O> {
     async fn event_stream(&mut self) -> Result<tonic::codec::Streaming<proto::Event>> {
         Ok(self
             .grpc
-            .observe(proto::ObserveRequest {})
+            .observe(proto::encode::event::filter(&self.filter))
             .await?
             .into_inner())
     }
@@ -192,32 +286,98 @@ impl<O: session::Observer> Client<O> {
         Ok(self.grpc.dump(proto::DumpRequest {}).await?.into_inner())
     }
 
+    /// Checks that the server is alive and its session is responding.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the server can't be reached, or its session has stopped.
+    pub async fn health(&mut self) -> Result<metadata::Health> {
+        let reply = self.grpc.health(proto::HealthRequest {}).await?;
+        Ok(proto::decode::health(&reply.into_inner()))
+    }
+
+    /// Lists the clients currently observing the server.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is a network error, or the list can't be decoded.
+    pub async fn observers(&mut self) -> Result<Vec<metadata::Observer>> {
+        let reply = self
+            .grpc
+            .list_observers(proto::ListObserversRequest {})
+            .await?;
+        Ok(proto::decode::observers(&reply.into_inner())?)
+    }
+
+    /// Asks the server to reload its configuration.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is a network error, or the server can't load its new configuration.
+    pub async fn reload(&mut self) -> Result<metadata::Reload> {
+        let reply = self.grpc.reload(proto::ReloadRequest {}).await?;
+        Ok(proto::decode::reload(&reply.into_inner()))
+    }
+
+    /// Asks the server to shut down, doing `dest` with the current attempt first.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is a network error, or the server stops before handling the request.
+    pub async fn shutdown(&mut self, dest: session::action::OldDestination) -> Result<()> {
+        self.grpc
+            .shutdown(proto::ShutdownRequest {
+                save: dest == session::action::OldDestination::Save,
+            })
+            .await?;
+        Ok(())
+    }
+
     /// Asks the server to perform an action.
     ///
+    /// If the action sent a run to be saved, returns what the server did with it.
+    ///
     /// # Errors
     ///
-    /// Fails if any part of the dumping process fails (primarily network or transcoding errors).
-    pub async fn handle_action(&mut self, action: session::Action) -> Result<()> {
-        match action {
+    /// Fails if any part of the action process fails (primarily network or transcoding errors), or
+    /// if the server couldn't save a run sent to be saved.
+    pub async fn handle_action(
+        &mut self,
+        action: session::Action,
+    ) -> Result<Option<session::sink::Outcome>> {
+        let save = match action {
             session::Action::NewRun(dest) => {
-                self.grpc
+                let reply = self
+                    .grpc
                     .new_attempt(proto::NewAttemptRequest {
                         save: dest == session::action::OldDestination::Save,
                     })
                     .await?;
+                reply.into_inner().save
+            }
+            session::Action::RetrySave => {
+                let reply = self.grpc.retry_save(proto::RetrySaveRequest {}).await?;
+                reply.into_inner().save
             }
             session::Action::Push(index, time) => {
                 self.grpc
                     .push(proto::encode::action::push(index, time)?)
                     .await?;
+                None
             }
             session::Action::Pop(index, ty) => {
                 self.grpc
                     .pop(proto::encode::action::pop(index, ty)?)
                     .await?;
+                None
             }
+        };
+
+        match save.as_ref().map(proto::decode::save).transpose()? {
+            None => Ok(None),
+            Some(session::event::Save::Accepted(outcome)) => Ok(Some(outcome)),
+            Some(session::event::Save::Failed(reason)) => Err(Error::Save(reason)),
         }
-        Ok(())
     }
 
     fn handle_event(&mut self, event_if_open: Option<proto::Event>) -> Result<bool> {
@@ -232,3 +392,12 @@ impl<O: session::Observer> Client<O> {
         }
     }
 }
+
+/// Asks the server for its information, and checks that we can talk to it.
+async fn server_info(grpc: &mut Grpc) -> Result<metadata::Server> {
+    let reply = grpc.server_info(proto::ServerInfoRequest {}).await?;
+    let server = proto::decode::server_info(&reply.into_inner())?;
+    version::check(&server.version)?;
+    log::info!("connected to {} {}", server.ident, server.version);
+    Ok(server)
+}
@@ -10,6 +10,9 @@ pub enum Error {
     /// A general I/O error.
     #[error("i/o error")]
     Io(#[from] std::io::Error),
+    /// Couldn't set up TLS.
+    #[error("TLS error")]
+    Tls(#[from] crate::net::tls::Error),
     /// A gRPC transport error.
     #[error("transport error")]
     Transport(#[from] tonic::transport::Error),
@@ -19,6 +22,12 @@ pub enum Error {
     /// A client-side decoding error.
     #[error("error decoding response from server")]
     Decode(#[from] super::proto::decode::Error),
+    /// The server speaks a protocol version we don't support.
+    #[error("server is incompatible with this client")]
+    Version(#[from] super::super::version::Error),
+    /// The server couldn't save a run, and is keeping it in memory so that saving can be retried.
+    #[error("server couldn't save run: {0}")]
+    Save(String),
     /// Couldn't cancel the server's observation loop.
     #[error("couldn't cancel the observer")]
     ObserverCancelFail,
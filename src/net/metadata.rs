@@ -8,3 +8,28 @@ pub struct Server {
     /// Server protocol semantic version.
     pub version: semver::Version,
 }
+
+/// Dump of information about a client observing the server's events.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Observer {
+    /// Identifier assigned to the observer by the server; unique for the server's lifetime.
+    pub id: u64,
+    /// The remote address of the observer, if known.
+    pub address: Option<std::net::SocketAddr>,
+    /// When the observer subscribed to events.
+    pub since: chrono::DateTime<chrono::Utc>,
+}
+
+/// Result of a server health check.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Health {
+    /// How long the server's session has been running.
+    pub uptime: std::time::Duration,
+}
+
+/// Result of asking the server to reload its configuration.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reload {
+    /// Names of configuration sections that changed, but only take effect on restart.
+    pub needs_restart: Vec<String>,
+}
@@ -8,9 +8,66 @@ pub mod error;
 pub mod event;
 pub mod timing;
 
-use super::super::super::model::{game::category, session};
+use super::super::{
+    super::model::{game::category, session},
+    metadata,
+};
+use chrono::TimeZone;
 pub use error::{Error, Missing, Result, Unknown};
 
+/// Decodes a protobuf server information response.
+///
+/// # Errors
+///
+/// Fails if the response is missing its version.
+pub fn server_info(info: &super::ServerInfoResponse) -> Result<metadata::Server> {
+    let version = Missing::ServerVersion.require(info.version.as_ref())?;
+    Ok(metadata::Server {
+        ident: info.ident.clone(),
+        version: semver::Version::new(version.major, version.minor, version.patch),
+    })
+}
+
+/// Decodes a protobuf health check response.
+#[must_use]
+pub fn health(health: &super::HealthResponse) -> metadata::Health {
+    metadata::Health {
+        uptime: std::time::Duration::from_millis(health.uptime_ms),
+    }
+}
+
+/// Decodes a protobuf configuration reload response.
+#[must_use]
+pub fn reload(reload: &super::ReloadResponse) -> metadata::Reload {
+    metadata::Reload {
+        needs_restart: reload.needs_restart.clone(),
+    }
+}
+
+/// Decodes a protobuf observer list.
+///
+/// # Errors
+///
+/// Fails if any observer has a malformed address or connection timestamp.
+pub fn observers(observers: &super::ListObserversResponse) -> Result<Vec<metadata::Observer>> {
+    observers.observers.iter().map(observer).collect()
+}
+
+fn observer(observer: &super::list_observers_response::Observer) -> Result<metadata::Observer> {
+    Ok(metadata::Observer {
+        id: observer.id,
+        address: if observer.address.is_empty() {
+            None
+        } else {
+            Some(observer.address.parse()?)
+        },
+        since: chrono::Utc
+            .timestamp_opt(observer.connected_at, 0)
+            .single()
+            .ok_or(Error::Timestamp(observer.connected_at))?,
+    })
+}
+
 /// Decodes a protobuf representation of attempt information into its model form.
 ///
 /// # Errors
@@ -23,6 +80,23 @@ pub fn attempt_info(attempt: &super::AttemptInfo) -> Result<category::AttemptInf
     })
 }
 
+/// Decodes a protobuf representation of what happened to a run sent to be saved.
+///
+/// # Errors
+///
+/// Fails with `Unknown` if the outcome isn't one we know about.
+pub fn save(save: &super::Save) -> Result<session::event::Save> {
+    use session::{event::Save, sink::Outcome};
+    Ok(
+        match Unknown::SaveOutcome.require(super::save::Outcome::from_i32(save.outcome))? {
+            super::save::Outcome::Saved => Save::Accepted(Outcome::Saved),
+            super::save::Outcome::Ignored => Save::Accepted(Outcome::Ignored),
+            super::save::Outcome::Queued => Save::Accepted(Outcome::Queued),
+            super::save::Outcome::Failed => Save::Failed(save.error.clone()),
+        },
+    )
+}
+
 /// Tries to interpret `pop_index` as a protobuf pop type, and decode it into the model form.
 ///
 /// # Errors
@@ -1,6 +1,9 @@
 //! Decodes protobufs into zombiesplit action information.
 
-use super::{super::super::super::model::session, Result};
+use super::{
+    super::super::super::model::{session, timing},
+    Result,
+};
 
 /// Decodes a push action.
 ///
@@ -10,11 +13,7 @@ use super::{super::super::super::model::session, Result};
 pub fn push(request: &super::super::PushRequest) -> Result<session::Action> {
     Ok(session::Action::Push(
         super::split_index(request.index)?,
-        request
-            .time
-            .as_ref()
-            .map(super::timing::time)
-            .unwrap_or_default(),
+        timing::time::Time::from_millis(request.time),
     ))
 }
 
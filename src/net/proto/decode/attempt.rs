@@ -2,7 +2,7 @@
 
 use super::{
     super::{
-        super::super::model::{game, session, short, timing::time::human},
+        super::super::model::{game, session, short, timing},
         dump_response,
     },
     error::{Missing, Result},
@@ -12,33 +12,50 @@ use super::{
 ///
 /// # Errors
 ///
-/// Fails if the counts in the attempt information overflow `usize`s on this machine, or if any of
-/// the times supplied for the splits are ill-formed.
+/// Fails if the counts in the attempt information or segment sizes overflow `usize`s on this
+/// machine, or if any of the times supplied for the splits are ill-formed.
 pub fn decode(run: &dump_response::Attempt) -> Result<session::Attempt> {
+    let category = target(Missing::AttemptInfo.require(run.target.as_ref())?)?;
+    let splits = splits(&run.splits)?
+        .with_frame_rate(category.frame_rate)
+        .with_segments(segments(&run.segments)?);
     Ok(session::Attempt {
-        category: target(Missing::AttemptInfo.require(run.game_category.as_ref())?),
+        category,
         info: run
             .attempt_info
             .as_ref()
             .map(super::attempt_info)
             .transpose()?
             .unwrap_or_default(),
-        splits: splits(&run.splits)?,
+        splits,
     })
 }
 
-fn target(info: &dump_response::attempt::Target) -> game::category::Target {
-    game::category::Target {
+fn target(info: &dump_response::attempt::Target) -> Result<game::category::Target> {
+    Ok(game::category::Target {
         game: info.game_name.clone(),
         category: info.category_name.clone(),
         short: game::category::ShortDescriptor::new(&info.game_sid, &info.category_sid),
-    }
+        frame_rate: info.frame_rate.as_deref().map(str::parse).transpose()?,
+    })
 }
 
 fn splits(splits: &[dump_response::attempt::Split]) -> Result<session::split::Set> {
     splits.iter().map(split).collect()
 }
 
+fn segments(segments: &[dump_response::attempt::Segment]) -> Result<Vec<(game::Segment, usize)>> {
+    segments
+        .iter()
+        .map(|s| {
+            Ok((
+                game::Segment::new(short::Name::from(&s.sid), &s.name),
+                usize::try_from(s.num_splits)?,
+            ))
+        })
+        .collect()
+}
+
 fn split(split: &dump_response::attempt::Split) -> Result<session::split::Split> {
     Ok(session::split::Split {
         info: game::Split {
@@ -46,15 +63,15 @@ fn split(split: &dump_response::attempt::Split) -> Result<session::split::Split>
             name: split.name.clone(),
             nickname: split.nickname.clone(),
         },
-        times: times(split)?,
+        times: times(split),
     })
 }
 
-fn times(split: &dump_response::attempt::Split) -> Result<Vec<human::Time>> {
+fn times(split: &dump_response::attempt::Split) -> Vec<timing::time::Time> {
     split
         .times
         .iter()
         .copied()
-        .map(crate::model::timing::time::Time::from_millis)
+        .map(timing::time::Time::from_millis)
         .collect()
 }
@@ -14,19 +14,14 @@ pub fn decode(cmp: &dump_response::Comparison) -> Result<timing::comparison::Com
     Ok(timing::comparison::Comparison {
         run: cmp.run.as_ref().map(run).transpose()?.unwrap_or_default(),
         splits: segments(&cmp.splits)?,
+        segments: segments(&cmp.segments)?,
     })
 }
 
 fn run(run: &dump_response::comparison::Run) -> Result<timing::comparison::Run> {
     Ok(timing::comparison::Run {
-        total_in_pb_run: run
-            .total_in_pb_run
-            .map(timing::time::Time::from_millis)
-            .transpose()?,
-        sum_of_best: run
-            .sum_of_best
-            .map(timing::time::Time::from_millis)
-            .transpose()?,
+        total_in_pb_run: run.total_in_pb_run.map(timing::time::Time::from_millis),
+        sum_of_best: run.sum_of_best.map(timing::time::Time::from_millis),
     })
 }
 
@@ -45,8 +40,7 @@ fn segment(segment: &dump_response::comparison::Segment) -> Result<timing::compa
             .in_pb_run
             .as_ref()
             .map(super::timing::aggregate)
-            .transpose()?
             .unwrap_or_default(),
-        split_pb: timing::time::Time::from_millis(segment.split_pb)?,
+        split_pb: timing::time::Time::from_millis(segment.split_pb),
     })
 }
@@ -26,11 +26,17 @@ pub fn dump(dump: &DumpResponse) -> Result<session::State> {
             .transpose()?
             .unwrap_or_default(),
         notes: notes(&dump.notes)?,
+        segment_notes: notes(&dump.segment_notes)?,
         total: total(dump)?,
     })
 }
 
-fn notes(
+/// Decodes a map of split or segment notes.
+///
+/// # Errors
+///
+/// Fails if any of the notes has a malformed delta.
+pub fn notes(
     notes: &HashMap<String, dump_response::SplitNote>,
 ) -> Result<short::Map<session::state::SplitNote>> {
     notes
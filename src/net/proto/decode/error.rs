@@ -19,6 +19,12 @@ pub enum Error {
     /// Couldn't decode a time.
     #[error("couldn't decode time")]
     Time(#[from] time::Error),
+    /// Couldn't decode a network address.
+    #[error("couldn't decode address")]
+    Address(#[from] std::net::AddrParseError),
+    /// A timestamp was out of range.
+    #[error("invalid timestamp: {0}")]
+    Timestamp(i64),
     /// Something we needed was missing.
     #[error("missing data in response: {0:?}")]
     Missing(Missing),
@@ -33,6 +39,8 @@ impl From<Error> for tonic::Status {
         match e {
             Error::IndexConversion(_) => tonic::Status::out_of_range(e.to_string()),
             Error::Time(e) => adapt_time_error(e),
+            Error::Address(_) => tonic::Status::invalid_argument(e.to_string()),
+            Error::Timestamp(_) => tonic::Status::out_of_range(e.to_string()),
             Error::Missing(_) => tonic::Status::data_loss(e.to_string()),
             Error::Unknown(_) => tonic::Status::out_of_range(e.to_string()),
         }
@@ -58,6 +66,8 @@ pub enum Missing {
     AttemptInfo,
     /// A split event was missing its payload.
     SplitEvent,
+    /// The server information was missing its version.
+    ServerVersion,
 }
 
 impl Missing {
@@ -80,6 +90,12 @@ pub enum Unknown {
     ComparisonTotalType,
     /// The pop type was missing in a pop event or request.
     Pop,
+    /// The outcome of a save was out of range.
+    SaveOutcome,
+    /// An event kind in an observe request was out of range.
+    EventKind,
+    /// An aggregate source in an observe request was out of range.
+    Source,
 }
 
 impl Unknown {
@@ -3,7 +3,7 @@
 use super::{
     super::{
         super::super::model::{session, short, timing},
-        event, Event,
+        event, observe_request, Event, ObserveRequest,
     },
     error::{Missing, Result},
 };
@@ -19,17 +19,73 @@ pub fn decode(e: Event) -> Result<Option<session::Event>> {
     e.payload.map(payload).transpose()
 }
 
+/// Decodes an observe request into the event filter it asks for.
+///
+/// # Errors
+///
+/// Fails with `Unknown` if any of the requested kinds or sources isn't one we know about.
+pub fn filter(request: &ObserveRequest) -> Result<session::event::Filter> {
+    Ok(session::event::Filter {
+        kinds: request
+            .kinds
+            .iter()
+            .map(|k| kind(*k))
+            .collect::<Result<_>>()?,
+        splits: request.splits.iter().map(short::Name::from).collect(),
+        sources: request
+            .sources
+            .iter()
+            .map(|s| source(*s))
+            .collect::<Result<_>>()?,
+    })
+}
+
+fn kind(kind_num: i32) -> Result<session::event::filter::Kind> {
+    use session::event::filter::Kind;
+    Ok(
+        match Unknown::EventKind.require(observe_request::Kind::from_i32(kind_num))? {
+            observe_request::Kind::Total => Kind::Total,
+            observe_request::Kind::Reset => Kind::Reset,
+            observe_request::Kind::Time => Kind::Time,
+            observe_request::Kind::Notes => Kind::Notes,
+            observe_request::Kind::Pop => Kind::Pop,
+            observe_request::Kind::Save => Kind::Save,
+        },
+    )
+}
+
+fn source(source_num: i32) -> Result<timing::aggregate::Source> {
+    Ok(
+        match Unknown::Source.require(observe_request::Source::from_i32(source_num))? {
+            observe_request::Source::Attempt => timing::aggregate::Source::Attempt,
+            observe_request::Source::Comparison => timing::aggregate::Source::Comparison,
+        },
+    )
+}
+
 fn payload(e: event::Payload) -> Result<session::Event> {
     Ok(match e {
         event::Payload::Total(t) => total(&t)?,
         event::Payload::Reset(info) => session::Event::Reset(super::attempt_info(&info)?),
-        event::Payload::Split(s) => split(&s)?,
+        event::Payload::Split(s) => {
+            let (sid, event) = split(&s)?;
+            session::Event::Split(sid, event)
+        }
+        event::Payload::Segment(s) => {
+            let (sid, event) = split(&s)?;
+            session::Event::Segment(sid, event)
+        }
+        event::Payload::Save(s) => session::Event::Save(super::save(&s)?),
+        event::Payload::Notes(n) => session::Event::Notes(session::event::Notes {
+            splits: super::dump::notes(&n.splits)?,
+            segments: super::dump::notes(&n.segments)?,
+        }),
     })
 }
 
 fn total(t: &event::Total) -> Result<session::Event> {
     let ty = total_type(Unknown::TotalType.require(t.r#type.as_ref())?)?;
-    let value = t.value.map(timing::time::Time::from_millis).transpose()?;
+    let value = t.value.map(timing::time::Time::from_millis);
     Ok(session::Event::Total(ty, value))
 }
 
@@ -63,18 +119,18 @@ fn comparison_total_type_inner(
     }
 }
 
-fn split(s: &event::Split) -> Result<session::Event> {
+/// Decodes a split event, which may belong to either a split or a segment.
+fn split(s: &event::Split) -> Result<(short::Name, session::event::Split)> {
     let sid = short::Name::from(&s.sid);
     let event = match Missing::SplitEvent.require(s.payload.as_ref())? {
         event::split::Payload::Time(t) => split_time(t)?,
-        event::split::Payload::Delta(p) => split_delta(p)?,
         event::split::Payload::Pop(p) => split_pop(*p)?,
     };
-    Ok(session::Event::Split(sid, event))
+    Ok((sid, event))
 }
 
 fn split_time(t: &event::split::Time) -> Result<session::event::Split> {
-    let time = timing::time::Time::from_millis(t.time)?;
+    let time = timing::time::Time::from_millis(t.time);
     Ok(session::event::split::Split::Time(
         time,
         split_time_type(t.r#type()),
@@ -100,12 +156,6 @@ fn split_time_type(ty: event::split::time::Type) -> session::event::Time {
     }
 }
 
-fn split_delta(delta: &super::super::SplitDelta) -> Result<session::event::Split> {
-    Ok(session::event::Split::Delta(super::timing::split_delta(
-        delta,
-    )?))
-}
-
 fn split_pop(pop_index: i32) -> Result<session::event::Split> {
     Ok(session::event::Split::Popped(super::pop(pop_index)?))
 }
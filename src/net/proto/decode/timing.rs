@@ -7,6 +7,7 @@ use super::{
     },
     error::Result,
 };
+use timing::{comparison::delta, time::Time};
 
 /// Decodes a run-wide total.
 ///
@@ -14,18 +15,17 @@ use super::{
 ///
 /// Fails with `out_of_range` if any timestamp is too large to represent a valid times or deltas,
 /// and `invalid_argument` if there is any other error in decoding paces or times.
-pub(super) fn total(t: &Total) -> Result<timing::comparison::delta::Time> {
+pub(super) fn total(t: &Total) -> Result<delta::Time> {
     let delta = t
         .delta
         .as_ref()
         .map(run_delta)
         .transpose()?
         .unwrap_or_default();
-    let time = time(t.time);
-    timing::comparison::delta::Time {
+    Ok(delta::Time {
         delta,
-        time: t.time,
-    }
+        time: Time::from_millis(t.time),
+    })
 }
 
 /// Decodes a split-level time delta.
@@ -34,23 +34,11 @@ pub(super) fn total(t: &Total) -> Result<timing::comparison::delta::Time> {
 ///
 /// Fails with `out_of_range` if the timestamp is too large to represent a valid delta amount,
 /// and `invalid_argument` if there is any other error in decoding the amount or the pace.
-pub(super) fn split_delta(d: &SplitDelta) -> Result<timing::comparison::delta::Split> {
-    Ok(timing::comparison::delta::Split {
-        pace: split_in_run_pace(d.pace()),
-        split_abs_delta: timing::time::Time::from_millis(d.split_abs_delta)?,
-        run_abs_delta: timing::time::Time::from_millis(d.run_abs_delta)?,
-    })
-}
-
-fn split_in_run_pace(pace: Pace) -> timing::comparison::pace::SplitInRun {
-    match pace {
-        Pace::None => timing::comparison::pace::SplitInRun::Inconclusive,
-        Pace::Behind => timing::comparison::pace::SplitInRun::BehindAndLosing,
-        Pace::BehindButGaining => timing::comparison::pace::SplitInRun::BehindAndGaining,
-        Pace::AheadButLosing => timing::comparison::pace::SplitInRun::AheadAndLosing,
-        Pace::Ahead => timing::comparison::pace::SplitInRun::AheadAndGaining,
-        Pace::PersonalBest => timing::comparison::pace::SplitInRun::SplitPersonalBest,
-    }
+pub(super) fn split_delta(d: &SplitDelta) -> Result<delta::Split> {
+    // The deltas carry the rest of the pace, but whether the split is gold needs the comparison.
+    let split = delta::Delta::new(Time::from_millis(d.split_delta));
+    let run = delta::Delta::new(Time::from_millis(d.run_delta));
+    Ok(delta::Split::new(split, run).with_personal_best(d.pace() == Pace::PersonalBest))
 }
 
 /// Decodes a run-level time delta.
@@ -59,30 +47,15 @@ fn split_in_run_pace(pace: Pace) -> timing::comparison::pace::SplitInRun {
 ///
 /// Fails with `out_of_range` if the timestamp is too large to represent a valid delta amount,
 /// and `invalid_argument` if there is any other error in decoding the amount or the pace.
-pub(super) fn run_delta(d: &RunDelta) -> Result<timing::comparison::Delta> {
-    let pace = pace(d.pace());
-    let abs_delta = timing::time::Time::from_millis(d.abs_delta)?;
-    Ok(timing::comparison::Delta { pace, abs_delta })
-}
-
-fn pace(pace: Pace) -> timing::comparison::Pace {
-    match pace {
-        Pace::None => timing::comparison::Pace::Inconclusive,
-        Pace::Behind | Pace::BehindButGaining => timing::comparison::Pace::Behind,
-        Pace::Ahead | Pace::AheadButLosing => timing::comparison::Pace::Ahead,
-        Pace::PersonalBest => timing::comparison::Pace::PersonalBest,
-    }
+pub(super) fn run_delta(d: &RunDelta) -> Result<delta::Delta> {
+    // The pace follows from the sign of the delta, so we needn't decode it.
+    Ok(delta::Delta::new(Time::from_millis(d.delta)))
 }
 
 /// Decodes an aggregate set.
 pub(super) fn aggregate(agg: &Aggregate) -> timing::aggregate::Set {
     timing::aggregate::Set {
-        split: agg.split.as_ref().map(time).unwrap_or_default(),
-        cumulative: agg.cumulative.as_ref().map(time).unwrap_or_default(),
+        split: Time::from_millis(agg.split),
+        cumulative: Time::from_millis(agg.cumulative),
     }
 }
-
-/// Decodes a time.
-pub(super) fn time(t: &super::super::Time) -> timing::Time {
-    timing::Time::from_millis(t.millis)
-}
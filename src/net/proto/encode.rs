@@ -32,6 +32,49 @@ fn version(version: &semver::Version) -> super::server_info_response::Version {
     }
 }
 
+/// Encodes a health check result into a protobuf response.
+///
+/// # Errors
+///
+/// Fails with `out_of_range` if the uptime in milliseconds cannot be stored in 64 bits.
+pub fn health(health: &metadata::Health) -> Result<super::HealthResponse> {
+    Ok(super::HealthResponse {
+        uptime_ms: try_from_range(health.uptime.as_millis())?,
+    })
+}
+
+/// Encodes a configuration reload result into a protobuf response.
+///
+/// # Errors
+///
+/// None as of yet, but this may change in future.
+pub fn reload(reload: &metadata::Reload) -> Result<super::ReloadResponse> {
+    Ok(super::ReloadResponse {
+        needs_restart: reload.needs_restart.clone(),
+    })
+}
+
+/// Encodes a list of observers into a protobuf response.
+///
+/// # Errors
+///
+/// None as of yet, but this may change in future.
+pub fn observers(observers: &[metadata::Observer]) -> Result<super::ListObserversResponse> {
+    Ok(super::ListObserversResponse {
+        observers: observers.iter().map(observer).collect(),
+    })
+}
+
+fn observer(observer: &metadata::Observer) -> super::list_observers_response::Observer {
+    super::list_observers_response::Observer {
+        id: observer.id,
+        address: observer
+            .address
+            .map_or_else(String::new, |addr| addr.to_string()),
+        connected_at: observer.since.timestamp(),
+    }
+}
+
 /// Encodes attempt information into its protobuf form.
 ///
 /// # Errors
@@ -44,6 +87,22 @@ pub fn attempt_info(attempt: &category::AttemptInfo) -> Result<super::AttemptInf
     })
 }
 
+/// Encodes what happened to a run sent to be saved into its protobuf form.
+#[must_use]
+pub fn save(save: &session::event::Save) -> super::Save {
+    use session::{event::Save, sink::Outcome};
+    let (outcome, error) = match save {
+        Save::Accepted(Outcome::Saved) => (super::save::Outcome::Saved, String::new()),
+        Save::Accepted(Outcome::Ignored) => (super::save::Outcome::Ignored, String::new()),
+        Save::Accepted(Outcome::Queued) => (super::save::Outcome::Queued, String::new()),
+        Save::Failed(error) => (super::save::Outcome::Failed, error.clone()),
+    };
+    super::Save {
+        outcome: outcome as i32,
+        error,
+    }
+}
+
 /// Encodes `pop_index` as a protobuf pop type.
 /// Fails with `Missing` if `pop_index` doesn't correspond to a valid pop type.
 fn pop(pop: session::action::Pop) -> i32 {
@@ -13,12 +13,14 @@ use itertools::Itertools;
 ///
 /// # Errors
 ///
-/// Fails if any of the indices in the attempt information are too large to store as `u64`.
+/// Fails if any of the indices in the attempt information, or segment sizes, are too large to
+/// store as `u64`.
 pub fn encode(run: &session::Attempt) -> Result<dump_response::Attempt> {
     Ok(dump_response::Attempt {
         target: Some(target(&run.category)),
         attempt_info: Some(super::attempt_info(&run.info)?),
         splits: splits(&run.splits),
+        segments: segments(&run.splits)?,
     })
 }
 
@@ -28,6 +30,7 @@ fn target(info: &category::Target) -> dump_response::attempt::Target {
         game_name: info.game.clone(),
         category_sid: info.short.category.to_string(),
         game_sid: info.short.game.to_string(),
+        frame_rate: info.frame_rate.map(|r| r.to_string()),
     }
 }
 
@@ -35,6 +38,18 @@ fn splits(splits: &session::split::Set) -> Vec<dump_response::attempt::Split> {
     splits.iter().map(split).collect_vec()
 }
 
+fn segments(splits: &session::split::Set) -> Result<Vec<dump_response::attempt::Segment>> {
+    splits.segments().iter().map(segment).collect()
+}
+
+fn segment(segment: &session::split::Segment) -> Result<dump_response::attempt::Segment> {
+    Ok(dump_response::attempt::Segment {
+        sid: segment.info.short.to_string(),
+        name: segment.info.name.clone(),
+        num_splits: super::try_from_range(segment.splits.len())?,
+    })
+}
+
 fn split(split: &session::Split) -> dump_response::attempt::Split {
     dump_response::attempt::Split {
         sid: split.info.short.to_string(),
@@ -44,6 +59,6 @@ fn split(split: &session::Split) -> dump_response::attempt::Split {
     }
 }
 
-fn times(split: &session::Split) -> Vec<u32> {
-    split.times.iter().map(|x| u32::from(*x)).collect_vec()
+fn times(split: &session::Split) -> Vec<i64> {
+    split.times.iter().map(|x| x.into_millis()).collect_vec()
 }
@@ -11,13 +11,14 @@ pub fn encode(cmp: &timing::comparison::Comparison) -> dump_response::Comparison
     dump_response::Comparison {
         run: Some(run(&cmp.run)),
         splits: segments(&cmp.splits),
+        segments: segments(&cmp.segments),
     }
 }
 
 fn run(run: &timing::comparison::Run) -> dump_response::comparison::Run {
     dump_response::comparison::Run {
-        sum_of_best: run.sum_of_best.map(u32::from),
-        total_in_pb_run: run.total_in_pb_run.map(u32::from),
+        sum_of_best: run.sum_of_best.map(timing::time::Time::into_millis),
+        total_in_pb_run: run.total_in_pb_run.map(timing::time::Time::into_millis),
     }
 }
 
@@ -33,6 +34,6 @@ fn segments(
 fn segment(split: &timing::comparison::Segment) -> dump_response::comparison::Segment {
     dump_response::comparison::Segment {
         in_pb_run: Some(super::timing::aggregate(&split.in_pb_run)),
-        split_pb: super::timing::time(&split.split_pb),
+        split_pb: split.split_pb.into_millis(),
     }
 }
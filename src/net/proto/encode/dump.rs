@@ -20,10 +20,12 @@ pub fn encode(dump: &session::State) -> Result<DumpResponse> {
         comparison: Some(comparison::encode(&dump.comparison)),
         notes: notes(&dump.notes),
         total: dump.total.map(super::timing::total),
+        segment_notes: notes(&dump.segment_notes),
     })
 }
 
-fn notes(
+/// Encodes a map of split or segment notes.
+pub fn notes(
     notes: &short::Map<session::state::SplitNote>,
 ) -> HashMap<String, dump_response::SplitNote> {
     notes
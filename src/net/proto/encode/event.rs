@@ -1,8 +1,8 @@
 //! Encoding logic for events.
 
 use super::super::{
-    super::super::model::{session, short, timing, timing::time::human},
-    event, Event,
+    super::super::model::{session, short, timing},
+    event, observe_request, Event, ObserveRequest,
 };
 
 /// Encodes an observer-level event into a protobuf event.
@@ -16,15 +16,50 @@ pub fn encode(event: &session::Event) -> super::Result<Event> {
             session::Event::Total(ty, time) => event::Payload::Total(total(*ty, *time)),
             session::Event::Reset(info) => event::Payload::Reset(super::attempt_info(info)?),
             session::Event::Split(sid, event) => event::Payload::Split(split(sid, event)),
+            session::Event::Segment(sid, event) => event::Payload::Segment(split(sid, event)),
+            session::Event::Save(save) => event::Payload::Save(super::save(save)),
+            session::Event::Notes(notes) => event::Payload::Notes(event::Notes {
+                splits: super::dump::notes(&notes.splits),
+                segments: super::dump::notes(&notes.segments),
+            }),
         }),
     })
 }
 
+/// Encodes an event filter into the observe request that asks for it.
+#[must_use]
+pub fn filter(filter: &session::event::Filter) -> ObserveRequest {
+    ObserveRequest {
+        kinds: filter.kinds.iter().map(|k| kind(*k) as i32).collect(),
+        splits: filter.splits.iter().map(ToString::to_string).collect(),
+        sources: filter.sources.iter().map(|s| source(*s) as i32).collect(),
+    }
+}
+
+fn kind(kind: session::event::filter::Kind) -> observe_request::Kind {
+    use session::event::filter::Kind;
+    match kind {
+        Kind::Total => observe_request::Kind::Total,
+        Kind::Reset => observe_request::Kind::Reset,
+        Kind::Time => observe_request::Kind::Time,
+        Kind::Notes => observe_request::Kind::Notes,
+        Kind::Pop => observe_request::Kind::Pop,
+        Kind::Save => observe_request::Kind::Save,
+    }
+}
+
+fn source(source: timing::aggregate::Source) -> observe_request::Source {
+    match source {
+        timing::aggregate::Source::Attempt => observe_request::Source::Attempt,
+        timing::aggregate::Source::Comparison => observe_request::Source::Comparison,
+    }
+}
+
 /// Encodes a total event into its protobuf form.
-fn total(ty: session::event::Total, time: Option<human::Time>) -> event::Total {
+fn total(ty: session::event::Total, time: Option<timing::time::Time>) -> event::Total {
     event::Total {
         r#type: Some(total_type(ty)),
-        value: time.map(u32::from),
+        value: time.map(timing::time::Time::into_millis),
     }
 }
 
@@ -58,15 +93,14 @@ fn split_payload(event: &session::event::Split) -> event::split::Payload {
     use {event::split::Payload, session::event::Split};
     match event {
         Split::Time(time, ty) => Payload::Time(split_time(*ty, *time)),
-        Split::Delta(delta) => Payload::Delta(super::timing::split_delta(delta)),
         Split::Popped(ty) => Payload::Pop(super::pop(*ty)),
     }
 }
 
-fn split_time(ty: session::event::Time, time: human::Time) -> event::split::Time {
+fn split_time(ty: session::event::Time, time: timing::time::Time) -> event::split::Time {
     event::split::Time {
         r#type: split_time_type(ty) as i32,
-        time: u32::from(time),
+        time: time.into_millis(),
     }
 }
 
@@ -8,16 +8,16 @@ use super::super::{
 /// Encodes a run total.
 pub(super) fn total(total: timing::comparison::delta::Time) -> Total {
     let delta = Some(run_delta(&total.delta));
-    let time = u32::from(total.time);
+    let time = total.time.into_millis();
     Total { delta, time }
 }
 
 /// Encodes a split-level time delta.
 pub(super) fn split_delta(d: &timing::comparison::delta::Split) -> SplitDelta {
     SplitDelta {
-        pace: split_in_run_pace(d.pace) as i32,
-        split_abs_delta: u32::from(d.split_abs_delta),
-        run_abs_delta: u32::from(d.run_abs_delta),
+        pace: split_in_run_pace(d.pace()) as i32,
+        split_delta: d.split.amount().into_millis(),
+        run_delta: d.run.amount().into_millis(),
     }
 }
 
@@ -35,9 +35,9 @@ fn split_in_run_pace(pace: timing::comparison::pace::SplitInRun) -> Pace {
 
 /// Encodes a run-level time delta.
 pub(super) fn run_delta(d: &timing::comparison::Delta) -> RunDelta {
-    let pace = pace(d.pace) as i32;
-    let abs_delta = u32::from(d.abs_delta);
-    RunDelta { pace, abs_delta }
+    let pace = pace(d.pace()) as i32;
+    let delta = d.amount().into_millis();
+    RunDelta { pace, delta }
 }
 
 fn pace(pace: timing::comparison::Pace) -> Pace {
@@ -49,10 +49,10 @@ fn pace(pace: timing::comparison::Pace) -> Pace {
     }
 }
 
-/// Encodes a timestamp into a time.
+/// Encodes an aggregate set.
 pub(super) fn aggregate(agg: &timing::aggregate::Set) -> Aggregate {
     Aggregate {
-        split: u32::from(agg.split),
-        cumulative: u32::from(agg.cumulative),
+        split: agg.split.into_millis(),
+        cumulative: agg.cumulative.into_millis(),
     }
 }
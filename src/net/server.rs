@@ -2,11 +2,15 @@
 
 This is a TCP server that hosts a run attempt session, accepts actions to perform on that attempt,
 and emits observations that reflect changes to the attempt.
+
+The server also exposes a small control plane, through which clients can check its health, list
+the other clients observing it, reload its configuration, and shut it down.  Shutting down on
+SIGINT or SIGTERM goes through the same path as a client-requested shutdown.
 */
 
 use std::sync::{Arc, Weak};
 
-use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
 pub use error::{Error, Result};
 
@@ -17,8 +21,7 @@ use crate::model::{
 };
 
 use super::super::{
-    config,
-    db::{self, inspect::Inspector},
+    config, db,
     model::{
         self,
         game::category::ShortDescriptor,
@@ -28,21 +31,26 @@ use super::super::{
 
 mod error;
 mod grpc;
+pub mod hook;
+mod observers;
+mod saver;
+mod signal;
 
 /// A manager of a zombiesplit server.
 ///
 /// This holds the configuration and database handles that will be used by the server proper.
 pub struct Manager {
     cfg: config::Server,
+    /// The custom configuration path, if any, from which `cfg` was loaded.
+    ///
+    /// This is used when reloading the configuration.
+    cfg_path: Option<std::path::PathBuf>,
 
     //
     // Database
     //
-
-    // Reader for acquiring comparisons.
-    reader: db::Reader,
-    // Sink for completed runs.
-    sink: db::Sink,
+    /// Handle to the database, used for comparisons and for saving runs.
+    db: Backend,
 
     //
     // Message routing
@@ -59,6 +67,65 @@ pub struct Manager {
     //
     observers: Vec<Arc<dyn session::Observer>>,
     obs_mux: session::event::Mux,
+    /// The observer running any configured hooks.
+    hooks: Option<Arc<hook::Observer>>,
+    /// The observer recording the session's journal, if one is configured.
+    journal: Option<Arc<Journal>>,
+}
+
+/// The type of recorder writing the session's journal.
+type Journal = session::journal::Recorder<std::fs::File>;
+
+/// The store from which the manager gets categories and comparisons, and into which it saves runs.
+#[derive(Clone)]
+enum Backend {
+    /// The database.
+    Sqlite(db::Db),
+    /// An in-memory store, used for running without a database file.
+    Memory(db::memory::Shared),
+}
+
+impl Backend {
+    /// Gets a fresh attempt at the category `desc`, along with its game-category ID.
+    fn attempt(
+        &self,
+        desc: &ShortDescriptor,
+    ) -> db::Result<(session::Attempt, db::category::GcID)> {
+        match self {
+            Self::Sqlite(db) => attempt(&mut db.reader()?.getters()?, desc),
+            Self::Memory(store) => attempt(
+                &mut *store
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner),
+                desc,
+            ),
+        }
+    }
+
+    /// Constructs a provider of comparisons for the game-category `id`.
+    fn comparison_provider(&self, id: db::category::GcID) -> Box<dyn provider::Provider> {
+        match self {
+            Self::Sqlite(db) => Box::new(db::comparison::Provider::new(db.clone(), id)),
+            Self::Memory(store) => Box::new(db::memory::Provider::new(store.clone(), id)),
+        }
+    }
+
+    /// Constructs a sink that saves runs into this backend.
+    fn sink(&self) -> saver::Inner {
+        match self {
+            Self::Sqlite(db) => Box::new(db::Sink::new(db.clone())),
+            Self::Memory(store) => Box::new(db::memory::Sink(store.clone())),
+        }
+    }
+}
+
+fn attempt(
+    source: &mut impl db::category::Source,
+    desc: &ShortDescriptor,
+) -> db::Result<(session::Attempt, db::category::GcID)> {
+    use db::category::Locator;
+    let info = desc.locate(source)?;
+    Ok((source.attempt(&info)?, info.id))
 }
 
 struct Broadcast(tokio::sync::broadcast::Sender<session::event::Event>);
@@ -77,21 +144,52 @@ impl Manager {
     ///
     /// Returns any errors from trying to open the database.
     pub fn new(cfg: config::Server) -> Result<Self> {
-        let db = std::rc::Rc::new(db::Db::new(&cfg.db.path)?);
-        let reader = db.reader()?;
+        let db = db::Db::new(&cfg.db.path)?;
+        Self::with_backend(cfg, Backend::Sqlite(db))
+    }
 
+    /// Constructs a new server that uses `store` in place of the database.
+    ///
+    /// The database configuration is ignored; categories and comparisons come from `store`, and
+    /// any runs saved into the database go into `store` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors from trying to open the journal.
+    pub fn in_memory(cfg: config::Server, store: db::memory::Shared) -> Result<Self> {
+        Self::with_backend(cfg, Backend::Memory(store))
+    }
+
+    fn with_backend(cfg: config::Server, db: Backend) -> Result<Self> {
         let debug_obs: Arc<dyn session::Observer> = Arc::new(Debug);
 
         let bcast = tokio::sync::broadcast::channel(BCAST_CAPACITY);
         let bcast_obs: Arc<dyn session::Observer> = Arc::new(Broadcast(bcast.0.clone()));
 
+        let hooks =
+            (!cfg.hooks.is_empty()).then(|| Arc::new(hook::Observer::new(cfg.hooks.clone())));
+        let mut observers = vec![debug_obs, bcast_obs];
+        if let Some(hooks) = &hooks {
+            observers.push(hooks.clone());
+        }
+        let journal = cfg
+            .journal
+            .as_ref()
+            .map(|path| Journal::create(path).map(Arc::new))
+            .transpose()?;
+        if let Some(journal) = &journal {
+            observers.push(journal.clone());
+        }
+
         let mut m = Self {
             cfg,
-            reader,
+            cfg_path: None,
+            db,
             bcast,
-            sink: db::Sink::new(db),
-            observers: vec![debug_obs, bcast_obs],
+            observers,
             obs_mux: session::event::Mux::default(),
+            hooks,
+            journal,
         };
 
         for obs in &m.observers {
@@ -101,47 +199,107 @@ impl Manager {
         Ok(m)
     }
 
+    /// Records that the manager's configuration came from the custom path `path`.
+    ///
+    /// Servers created from this manager will re-read this path (as well as the global
+    /// configuration) when asked to reload their configuration.
+    #[must_use]
+    pub fn with_config_path(mut self, path: Option<std::path::PathBuf>) -> Self {
+        self.cfg_path = path;
+        self
+    }
+
     /// Creates a server for the given game.
     ///
     /// # Errors
     ///
     /// Returns any database or UI errors caught during the session.
-    pub fn server(&self, desc: &ShortDescriptor) -> Result<Server> {
-        let insp = self.reader.inspect(desc)?;
+    pub fn server(&self, desc: &ShortDescriptor) -> Result<Server<'_>> {
+        let (attempt, category) = self.db.attempt(desc)?;
         let (message_send, message_recv) = tokio::sync::mpsc::channel(MPSC_CAPACITY);
+        let (sink, saver, saving) = saver::new(self.sink(), message_send.clone());
+        let (stop_send, stop_recv) = watch::channel(false);
         Ok(Server {
             addr: self.cfg.net.address,
+            tls: self
+                .cfg
+                .net
+                .tls
+                .as_ref()
+                .map(super::tls::server_config)
+                .transpose()?,
             handler: grpc::Handler {
                 message_send,
                 event_broadcast: self.bcast.0.clone(),
+                observers: Arc::new(observers::Registry::default()),
+                stop: stop_recv,
             },
+            stop: stop_send,
+            saver,
             state: State {
-                session: self.session(insp)?,
+                session: self.session(attempt, category, sink)?,
+                saving,
                 message_recv,
+                manager: self,
+                category,
+                cfg: self.cfg.clone(),
+                started: std::time::Instant::now(),
             },
         })
     }
 
-    fn session<'a, 'db>(
-        &'a self,
-        mut insp: Inspector<'db>,
-    ) -> Result<session::Session<'db, 'a, model::session::event::Mux>> {
-        let mut session = insp.init_session(&self.obs_mux)?;
-        session.set_comparison_provider(self.comparison_provider(insp));
-        session.set_sink(self.sink());
+    fn session(
+        &self,
+        attempt: session::Attempt,
+        category: db::category::GcID,
+        sink: saver::Sink,
+    ) -> Result<session::Session<'_, '_, model::session::event::Mux>> {
+        if let Some(hooks) = &self.hooks {
+            hooks.set_splits(attempt.splits.iter().map(|s| s.info.short));
+        }
+        let session = session::Session::builder(attempt, &self.obs_mux)
+            .comparison_provider(comparison_provider(
+                self.cfg.comparison,
+                &self.db,
+                category,
+            ))
+            .sink(Box::new(sink))
+            .build()?;
+        // Anything the setup caused isn't down to an action, so goes in an entry of its own.
+        self.record(None);
         Ok(session)
     }
 
-    fn comparison_provider<'a>(&self, insp: Inspector<'a>) -> Box<dyn provider::Provider + 'a> {
-        match self.cfg.comparison.provider {
-            config::server::comparison::Provider::Database => Box::new(insp),
-            _ => Box::new(provider::Null),
+    /// Writes a journal entry for `action`, if there is a journal.
+    fn record(&self, action: Option<session::Action>) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.record(action) {
+                log::error!("couldn't write journal entry: {e}");
+            }
         }
     }
 
-    fn sink(&self) -> Box<dyn sink::Sink> {
-        // TODO(@MattWindsor91): let users turn off database sinking
-        Box::new(self.sink.clone())
+    /// Builds the sink into which runs are saved, as configured.
+    fn sink(&self) -> saver::Inner {
+        let Some(cfgs) = &self.cfg.sinks else {
+            return self.db.sink();
+        };
+        let sinks = cfgs.iter().map(|cfg| self.configured_sink(cfg)).collect();
+        Box::new(sink::Fanout::new(sinks))
+    }
+
+    fn configured_sink(&self, cfg: &config::server::sink::Sink) -> saver::Inner {
+        use config::server::sink::Target;
+        let target: saver::Inner = match &cfg.target {
+            Target::Database => self.db.sink(),
+            Target::File { path } => Box::new(sink::File::new(path)),
+        };
+        let filter = sink::Filter::new(target).min_splits(cfg.min_splits);
+        if cfg.only_completed {
+            Box::new(filter.only_completed())
+        } else {
+            Box::new(filter)
+        }
     }
 }
 
@@ -152,40 +310,101 @@ impl Observable for Manager {
     }
 }
 
+fn comparison_provider(
+    cfg: config::server::comparison::Comparison,
+    db: &Backend,
+    id: db::category::GcID,
+) -> Box<dyn provider::Provider> {
+    match cfg.provider {
+        config::server::comparison::Provider::Database => db.comparison_provider(id),
+        _ => Box::new(provider::Null),
+    }
+}
+
 /// A server, wrapping a session with the means to control it (through `gRPC`).
 ///
 /// A server owns a running session, as well as the various observers attached to it, and performs
 /// many of the tasks of bringing up, maintaining, and tearing down those elements.
 ///
-/// In future, a server will also expose its control plane to clients.
-///
 /// The lifetime `m` generally reflects that of its underlying `Manager`.
 pub struct Server<'m> {
     addr: std::net::SocketAddr,
+    tls: Option<tonic::transport::ServerTlsConfig>,
     handler: grpc::Handler,
+    /// Tells the `gRPC` side of the server to stop once the session has shut down.
+    stop: watch::Sender<bool>,
+    /// Saves runs handed over by the session.
+    saver: saver::Saver,
     state: State<'m>,
 }
 
-impl<'cmp> Server<'cmp> {
+impl Server<'_> {
     /// Runs the server, consuming it.
+    ///
+    /// The server runs until it is asked to shut down, either by a client or by a signal.
     pub async fn run(self) {
-        tokio::spawn(run_grpc(self.addr, self.handler));
+        let stop = self.stop.subscribe();
+        tokio::spawn(signal::forward(self.handler.message_send.clone()));
+        let grpc = tokio::spawn(run_grpc(self.addr, self.tls, self.handler, stop));
+        let saver = tokio::spawn(self.saver.run());
 
         let mut state = self.state;
         state.run().await;
+
+        // Dropping the session lets the saver finish off any runs it still has queued.
+        drop(state);
+        if let Err(e) = saver.await {
+            log::error!("couldn't join run saver: {e}");
+        }
+
+        // The session is gone, so stop accepting requests and close any observation streams.
+        let _res = self.stop.send(true);
+        if let Err(e) = grpc.await {
+            log::error!("couldn't join server task: {e}");
+        }
+    }
+}
+
+async fn run_grpc(
+    addr: std::net::SocketAddr,
+    tls: Option<tonic::transport::ServerTlsConfig>,
+    handler: grpc::Handler,
+    stop: watch::Receiver<bool>,
+) {
+    if let Err(e) = serve_grpc(addr, tls, handler, stop).await {
+        // TODO(@MattWindsor91): handle error properly
+        log::error!("error in server: {e}");
     }
 }
 
-async fn run_grpc(addr: std::net::SocketAddr, handler: grpc::Handler) {
-    let srv = super::proto::zombiesplit_server::ZombiesplitServer::new(handler);
-    if let Err(e) = tonic::transport::server::Server::builder()
+async fn serve_grpc(
+    addr: std::net::SocketAddr,
+    tls: Option<tonic::transport::ServerTlsConfig>,
+    handler: grpc::Handler,
+    stop: watch::Receiver<bool>,
+) -> std::result::Result<(), tonic::transport::Error> {
+    let srv = super::proto::zombiesplit_server::ZombiesplitServer::with_interceptor(
+        handler,
+        super::version::enforce,
+    );
+    let mut builder = tonic::transport::server::Server::builder();
+    if let Some(tls) = tls {
+        log::info!("serving over TLS");
+        builder = builder.tls_config(tls)?;
+    }
+    builder
         .concurrency_limit_per_connection(256)
         .add_service(srv)
-        .serve(addr)
+        .serve_with_shutdown(addr, stopped(stop))
         .await
-    {
-        // TODO(@MattWindsor91): handle error properly
-        log::error!("error in server: {e}");
+}
+
+/// Resolves once `stop` has been set, or its sender has gone away.
+pub(crate) async fn stopped(mut stop: watch::Receiver<bool>) {
+    while !*stop.borrow() {
+        if stop.changed().await.is_err() {
+            return;
+        }
     }
 }
 
@@ -193,59 +412,201 @@ async fn run_grpc(addr: std::net::SocketAddr, handler: grpc::Handler) {
 struct State<'m> {
     /// The session being wrapped by this server.
     session: session::Session<'m, 'm, session::event::Mux>,
+    /// Counts the runs the session has queued for saving whose results it hasn't yet handled.
+    saving: saver::Pending,
     /// Receives messages from the server handler.
     message_recv: mpsc::Receiver<Message>,
+    /// The manager that created this server, used to reload configuration.
+    manager: &'m Manager,
+    /// The game-category ID of the game/category being run.
+    category: db::category::GcID,
+    /// The configuration currently in effect.
+    cfg: config::Server,
+    /// When the server was started.
+    started: std::time::Instant,
 }
 
+/// The result of a session handling an action.
+pub type ActionResult = std::result::Result<Option<session::sink::Outcome>, session::Error>;
+
 /// A message to the server.
 #[derive(Debug)]
 pub enum Message {
-    /// An action to send to the session; no direct reply expected.
-    Action(session::Action),
+    /// An action to send to the session, which replies through the given oneshot with the outcome
+    /// of any save the action caused.
+    Action(session::Action, oneshot::Sender<ActionResult>),
     /// A dumping query, which expects a reply through the given oneshot.
     Dump(oneshot::Sender<session::State>),
     /// A query for server information, which expects a reply through the given oneshot.
     ServerInfo(oneshot::Sender<super::metadata::Server>),
+    /// A health check, which expects a reply through the given oneshot.
+    Health(oneshot::Sender<super::metadata::Health>),
+    /// A request to reload the configuration, which expects a reply through the given oneshot.
+    Reload(oneshot::Sender<Result<super::metadata::Reload>>),
+    /// The eventual result of saving a run that the session handed over to be saved.
+    Saved(session::sink::Run, session::sink::Result),
+    /// A request to shut down the server, doing the given thing with the current attempt.
+    ///
+    /// The oneshot fires once the attempt has been dealt with, just before the session stops.
+    Shutdown(session::action::OldDestination, oneshot::Sender<()>),
+    /// The process received a signal asking it to stop.
+    ///
+    /// The server shuts down, doing with the current attempt whatever the control configuration
+    /// in effect at the time says.
+    Signal,
 }
 
-impl<'m> State<'m> {
+impl State<'_> {
     /// Runs the state main loop, which constantly drains messages from clients and applies them.
     ///
     /// These messages, in turn, give rise to observations that will bubble up through the broadcast
     /// channel and into clients.
+    ///
+    /// The loop ends when a shutdown message arrives, or every handle to the server is dropped.
+    /// Before it does, it waits for any queued runs to finish saving, so that their results get
+    /// handled.
     async fn run(&mut self) {
         while let Some(msg) = self.message_recv.recv().await {
-            match msg {
-                Message::Action(act) => infallible(self.session.handle(act)),
-                Message::Dump(rx) => {
-                    // TODO(@MattWindsor91): handle drop?
-                    let _res = rx.send(infallible(self.session.dump()));
+            if !self.handle_message(msg) {
+                break;
+            }
+        }
+        self.drain_saves().await;
+    }
+
+    /// Handles the results of any runs still being saved, ignoring every other message.
+    async fn drain_saves(&mut self) {
+        while !self.saving.is_empty() {
+            match self.message_recv.recv().await {
+                Some(msg @ Message::Saved(..)) => {
+                    self.handle_message(msg);
                 }
-                Message::ServerInfo(rx) => {
-                    // TODO(@MattWindsor91): handle drop?
-                    let _res = rx.send(info());
+                // Dropping the message drops any reply channel, which tells the client we stopped.
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+
+    /// Handles a single message, returning whether the main loop should keep going.
+    fn handle_message(&mut self, msg: Message) -> bool {
+        match msg {
+            Message::Action(act, rx) => {
+                let result = self.session.handle(act);
+                self.manager.record(Some(act));
+                let _res = rx.send(result);
+            }
+            Message::Dump(rx) => {
+                // TODO(@MattWindsor91): handle drop?
+                let _res = rx.send(self.session.state().clone());
+            }
+            Message::ServerInfo(rx) => {
+                // TODO(@MattWindsor91): handle drop?
+                let _res = rx.send(info());
+            }
+            Message::Health(rx) => {
+                let _res = rx.send(super::metadata::Health {
+                    uptime: self.started.elapsed(),
+                });
+            }
+            Message::Reload(rx) => {
+                let _res = rx.send(self.reload());
+            }
+            Message::Saved(run, result) => {
+                self.saving.finish();
+                if let Ok(session::sink::Outcome::Saved) = self.session.finish_save(run, result) {
+                    self.session.refresh_comparison();
                 }
+                self.manager.record(None);
+            }
+            Message::Shutdown(dest, rx) => {
+                self.shutdown(dest);
+                let _res = rx.send(());
+                return false;
+            }
+            Message::Signal => {
+                use config::server::control::OnSignal;
+                // Read this now, rather than at startup, so that reloads take effect.
+                self.shutdown(match self.cfg.control.on_signal {
+                    OnSignal::Save => session::action::OldDestination::Save,
+                    OnSignal::Discard => session::action::OldDestination::Discard,
+                });
+                return false;
             }
         }
+        true
+    }
+
+    /// Re-reads the server configuration, applying whatever parts of it can be changed live.
+    ///
+    /// Currently, only the comparison and control-plane configuration can be changed live;
+    /// changes to the database, network, hook, sink, or journal configuration are reported back as
+    /// needing a restart.
+    fn reload(&mut self) -> Result<super::metadata::Reload> {
+        let cfg = config::Server::load(self.manager.cfg_path.clone())?;
+
+        let mut needs_restart = vec![];
+        if cfg.db != self.cfg.db {
+            needs_restart.push("db".to_string());
+        }
+        if cfg.net != self.cfg.net {
+            needs_restart.push("net".to_string());
+        }
+        if cfg.hooks != self.cfg.hooks {
+            needs_restart.push("hook".to_string());
+        }
+        if cfg.sinks != self.cfg.sinks {
+            needs_restart.push("sink".to_string());
+        }
+        if cfg.journal != self.cfg.journal {
+            needs_restart.push("journal".to_string());
+        }
+
+        if cfg.comparison != self.cfg.comparison {
+            self.session.set_comparison_provider(comparison_provider(
+                cfg.comparison,
+                &self.manager.db,
+                self.category,
+            ));
+            self.manager.record(None);
+        }
+
+        // Keep the old database, network, hook, sink, and journal settings, as they are still in
+        // effect.
+        self.cfg = config::Server {
+            db: std::mem::take(&mut self.cfg.db),
+            net: std::mem::take(&mut self.cfg.net),
+            hooks: std::mem::take(&mut self.cfg.hooks),
+            sinks: std::mem::take(&mut self.cfg.sinks),
+            journal: self.cfg.journal.take(),
+            ..cfg
+        };
+
+        log::info!("reloaded configuration");
+        Ok(super::metadata::Reload { needs_restart })
+    }
+
+    /// Deals with the current attempt according to `dest`, in preparation for shutting down.
+    fn shutdown(&mut self, dest: session::action::OldDestination) {
+        log::info!("shutting down; current attempt destination: {dest:?}");
+        if dest == session::action::OldDestination::Save {
+            let action = session::Action::NewRun(dest);
+            if let Err(e) = self.session.handle(action) {
+                log::error!("couldn't save attempt before shutting down: {e}");
+            }
+            self.manager.record(Some(action));
+        }
     }
 }
 
 fn info() -> super::metadata::Server {
     super::metadata::Server {
         ident: SERVER_IDENT.to_string(),
-        version: SERVER_VERSION,
-    }
-}
-
-fn infallible<T>(x: std::result::Result<T, std::convert::Infallible>) -> T {
-    match x {
-        Ok(t) => t,
-        Err(e) => match e {},
+        version: super::version::CURRENT,
     }
 }
 
 const SERVER_IDENT: &str = "zsserver";
-const SERVER_VERSION: semver::Version = semver::Version::new(0, 1, 0);
 
 // TODO(@MattWindsor91): https://github.com/MattWindsor91/zombiesplit/issues/23
 
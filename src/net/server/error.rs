@@ -7,8 +7,16 @@ use thiserror::Error;
 pub enum Error {
     #[error("database error")]
     Db(#[from] crate::db::Error),
+    #[error("configuration error")]
+    Config(#[from] ::config::ConfigError),
+    #[error("couldn't set up session")]
+    Session(#[from] crate::model::session::builder::Error),
+    #[error("journal error")]
+    Journal(#[from] crate::model::session::journal::Error),
     #[error("i/o error")]
     IO(#[from] std::io::Error),
+    #[error("TLS error")]
+    Tls(#[from] crate::net::tls::Error),
     #[error("couldn't join task")]
     Join(#[from] tokio::task::JoinError),
     #[error("couldn't send action to session")]
@@ -6,8 +6,12 @@ use super::super::{
 };
 use crate::net::proto::{decode, encode};
 use futures::StreamExt;
-use std::pin::Pin;
-use tokio::sync::{broadcast, mpsc, oneshot};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
 type EventStream =
     Pin<Box<dyn futures::Stream<Item = std::result::Result<proto::Event, tonic::Status>> + Send>>;
@@ -19,6 +23,10 @@ pub struct Handler {
     pub message_send: mpsc::Sender<super::Message>,
     /// A broadcast channel head for events, from which we subscribe new event receivers.
     pub event_broadcast: broadcast::Sender<session::event::Event>,
+    /// The registry of clients currently observing events.
+    pub observers: Arc<super::observers::Registry>,
+    /// Becomes `true` when the server is shutting down, at which point event streams close.
+    pub stop: watch::Receiver<bool>,
 }
 
 type Result<T> = std::result::Result<tonic::Response<T>, tonic::Status>;
@@ -57,9 +65,20 @@ impl Zombiesplit for Handler {
             session::action::OldDestination::Discard
         };
 
-        self.act(session::Action::NewRun(dest)).await?;
-        // TODO(@MattWindsor91): report back whether the save occurred.
-        Ok(tonic::Response::new(proto::NewAttemptResponse {}))
+        let result = self.act(session::Action::NewRun(dest)).await?;
+        Ok(tonic::Response::new(proto::NewAttemptResponse {
+            save: encode_save(&result),
+        }))
+    }
+
+    async fn retry_save(
+        &self,
+        _request: tonic::Request<proto::RetrySaveRequest>,
+    ) -> Result<proto::RetrySaveResponse> {
+        let result = self.act(session::Action::RetrySave).await?;
+        Ok(tonic::Response::new(proto::RetrySaveResponse {
+            save: encode_save(&result),
+        }))
     }
 
     async fn push(
@@ -67,39 +86,118 @@ impl Zombiesplit for Handler {
         request: tonic::Request<proto::PushRequest>,
     ) -> Result<proto::PushResponse> {
         self.act(decode::action::push(&request.into_inner())?)
-            .await?;
+            .await?
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
         Ok(tonic::Response::new(proto::PushResponse {}))
     }
 
     async fn pop(&self, request: tonic::Request<proto::PopRequest>) -> Result<proto::PopResponse> {
         self.act(decode::action::pop(&request.into_inner())?)
-            .await?;
+            .await?
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
         Ok(tonic::Response::new(proto::PopResponse {}))
     }
 
     async fn observe(
         &self,
-        _request: tonic::Request<proto::ObserveRequest>,
+        request: tonic::Request<proto::ObserveRequest>,
     ) -> Result<Self::ObserveStream> {
+        let filter = decode::event::filter(request.get_ref())?;
+        let guard = self.observers.register(request.remote_addr());
         let recv = self.event_broadcast.subscribe();
         let recv_stream = tokio_stream::wrappers::BroadcastStream::new(recv);
-        let mapped_stream = recv_stream.map(|x| map_event_result(&x));
-        let response = Pin::new(Box::new(mapped_stream));
-        Ok(tonic::Response::new(response))
+        let mapped_stream = recv_stream
+            .filter_map(move |x| futures::future::ready(apply_filter(&filter, x)))
+            .map(|x| map_event_result(&x))
+            .take_until(super::stopped(self.stop.clone()));
+        let response = Tracked {
+            inner: Box::pin(mapped_stream),
+            _guard: guard,
+        };
+        Ok(tonic::Response::new(Box::pin(response)))
+    }
+
+    async fn shutdown(
+        &self,
+        request: tonic::Request<proto::ShutdownRequest>,
+    ) -> Result<proto::ShutdownResponse> {
+        let dest = if request.into_inner().save {
+            session::action::OldDestination::Save
+        } else {
+            session::action::OldDestination::Discard
+        };
+
+        let (send, recv) = oneshot::channel();
+        self.message_send
+            .send(super::Message::Shutdown(dest, send))
+            .await
+            .map_err(|e| tonic::Status::internal(format!("couldn't send shutdown message: {e}")))?;
+        recv.await
+            .map_err(|_| tonic::Status::internal("shutdown channel dropped"))?;
+        Ok(tonic::Response::new(proto::ShutdownResponse {}))
+    }
+
+    async fn reload(
+        &self,
+        _request: tonic::Request<proto::ReloadRequest>,
+    ) -> Result<proto::ReloadResponse> {
+        self.query("reload", super::Message::Reload, |reply| match reply {
+            Ok(reload) => encode::reload(reload),
+            Err(e) => Err(tonic::Status::failed_precondition(format!(
+                "couldn't reload configuration: {e}"
+            ))),
+        })
+        .await
+    }
+
+    async fn list_observers(
+        &self,
+        _request: tonic::Request<proto::ListObserversRequest>,
+    ) -> Result<proto::ListObserversResponse> {
+        encode::observers(&self.observers.list()).map(tonic::Response::new)
+    }
+
+    async fn health(
+        &self,
+        _request: tonic::Request<proto::HealthRequest>,
+    ) -> Result<proto::HealthResponse> {
+        self.query("health", super::Message::Health, encode::health)
+            .await
+            .map_err(|e| tonic::Status::unavailable(e.message()))
+    }
+}
+
+/// An event stream that keeps its observer registered for as long as the stream is alive.
+struct Tracked {
+    inner: EventStream,
+    _guard: super::observers::Guard,
+}
+
+impl futures::Stream for Tracked {
+    type Item = std::result::Result<proto::Event, tonic::Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
     }
 }
 
 impl Handler {
-    /// Sends `action` asynchronously to the session.
+    /// Sends `action` asynchronously to the session, returning the session's result.
     ///
     /// # Errors
     ///
-    /// Fails if the underlying send fails.
-    async fn act(&self, action: session::Action) -> std::result::Result<(), tonic::Status> {
+    /// Fails if the underlying send fails, or the session drops the reply channel.
+    async fn act(
+        &self,
+        action: session::Action,
+    ) -> std::result::Result<super::ActionResult, tonic::Status> {
+        let (send, recv) = oneshot::channel();
         self.message_send
-            .send(super::Message::Action(action))
+            .send(super::Message::Action(action, send))
             .await
-            .map_err(|x| tonic::Status::internal(x.to_string()))
+            .map_err(|x| tonic::Status::internal(x.to_string()))?;
+        recv.await
+            .map_err(|_| tonic::Status::internal("action channel dropped"))
     }
 
     /// Handles the main body of a RPC call that just asks the server to provide some information.
@@ -126,6 +224,16 @@ impl Handler {
     }
 }
 
+/// Applies an observer's `filter` to `event`, returning what the observer should receive.
+///
+/// Errors always get through, so that observers know when they have missed events.
+fn apply_filter<E>(
+    filter: &session::event::Filter,
+    event: std::result::Result<session::event::Event, E>,
+) -> Option<std::result::Result<session::event::Event, E>> {
+    event.map(|e| filter.apply(e)).transpose()
+}
+
 fn map_event_result(
     event: &std::result::Result<
         session::event::Event,
@@ -147,3 +255,15 @@ fn map_event_error(
         }
     }
 }
+
+/// Encodes the save, if any, caused by an action.
+///
+/// Save failures aren't errors as far as `gRPC` is concerned: the action still took effect, and
+/// the client needs to know that the run is waiting to be saved again.
+fn encode_save(result: &super::ActionResult) -> Option<proto::Save> {
+    let save = match result {
+        Ok(outcome) => session::event::Save::Accepted((*outcome)?),
+        Err(session::Error::Save(e)) => session::event::Save::Failed(e.to_string()),
+    };
+    Some(encode::save(&save))
+}
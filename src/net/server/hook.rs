@@ -0,0 +1,346 @@
+/*! Hooks: observers that tell other programs about interesting session events.
+
+A hook observer watches the session's event stream, picks out the events that hooks can fire on
+(resets, completed runs, personal bests, and gold splits), and hands them off to a worker thread
+that runs the configured commands and `POST`s to the configured URLs.  This keeps slow hooks from
+holding up the session. */
+
+mod run;
+
+use super::super::super::{
+    config::server::hook::{Hook, Trigger},
+    model::{
+        game::category::AttemptInfo,
+        session::{
+            self,
+            event::{self, Event},
+        },
+        short,
+        timing::{comparison::run::TotalType, time},
+    },
+};
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    sync::{mpsc, Mutex},
+};
+
+/// A description of a hook-worthy event, sent as JSON to each hook that fires on it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Payload {
+    /// The type of event.
+    pub event: Trigger,
+    /// The split involved in the event, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<short::Name>,
+    /// The time involved in the event, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<time::human::Time>,
+    /// Information about the attempt in which the event happened.
+    pub attempt: AttemptInfo,
+}
+
+/// An observer that fires hooks.
+pub struct Observer {
+    /// Works out when hooks should fire.
+    tracker: Mutex<Tracker>,
+    /// The triggers on which at least one hook fires.
+    triggers: HashSet<Trigger>,
+    /// Sends payloads to the worker thread.
+    jobs: Mutex<mpsc::Sender<Payload>>,
+}
+
+impl Observer {
+    /// Creates a hook observer for `hooks`, spawning a worker thread to run them.
+    #[must_use]
+    pub fn new(hooks: Vec<Hook>) -> Self {
+        let triggers = hooks.iter().flat_map(|h| h.on.iter().copied()).collect();
+        let (send, recv) = mpsc::channel::<Payload>();
+        std::thread::spawn(move || {
+            for payload in recv {
+                for hook in hooks.iter().filter(|h| h.on.contains(&payload.event)) {
+                    run::fire(hook, &payload);
+                }
+            }
+        });
+
+        Self {
+            tracker: Mutex::new(Tracker::default()),
+            triggers,
+            jobs: Mutex::new(send),
+        }
+    }
+
+    /// Tells the observer which splits are in the attempt, in order.
+    ///
+    /// This must happen before the observer can tell when a run is completed.
+    pub fn set_splits(&self, splits: impl IntoIterator<Item = short::Name>) {
+        lock(&self.tracker).set_splits(splits);
+    }
+}
+
+impl session::Observer for Observer {
+    fn observe(&self, evt: Event) {
+        let payloads = lock(&self.tracker).observe(&evt);
+        let jobs = lock(&self.jobs);
+        for payload in payloads {
+            if self.triggers.contains(&payload.event) && jobs.send(payload).is_err() {
+                log::error!("hook worker has stopped");
+            }
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Reconstructs enough of the session state from its events to tell when hooks should fire.
+#[derive(Debug, Default)]
+struct Tracker {
+    /// The number of times in each split.
+    times: short::Map<usize>,
+    /// The last split in the attempt, whose cumulative time is the run total.
+    last: Option<short::Name>,
+    /// The splits that have been gold this attempt, and for which we have fired a hook.
+    golds: HashSet<short::Name>,
+    /// The total of the comparison PB run, if there is one.
+    pb_total: Option<time::Time>,
+    /// The current attempt information.
+    attempt: AttemptInfo,
+    /// Whether we have already fired a completion hook for this attempt.
+    is_completed: bool,
+}
+
+impl Tracker {
+    fn set_splits(&mut self, splits: impl IntoIterator<Item = short::Name>) {
+        let splits: Vec<_> = splits.into_iter().collect();
+        self.last = splits.last().copied();
+        self.times = splits.into_iter().map(|s| (s, 0)).collect();
+    }
+
+    /// Observes `evt`, returning payloads for any hooks that should fire.
+    fn observe(&mut self, evt: &Event) -> Vec<Payload> {
+        match evt {
+            Event::Reset(attempt) => {
+                self.reset(*attempt);
+                vec![self.payload(Trigger::Reset, None, None)]
+            }
+            Event::Split(split, evt) => self.observe_split(*split, evt),
+            Event::Notes(notes) => self.observe_notes(notes),
+            Event::Total(event::Total::Comparison(TotalType::TotalInPbRun), time) => {
+                self.pb_total = *time;
+                vec![]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn reset(&mut self, attempt: AttemptInfo) {
+        self.attempt = attempt;
+        self.times.values_mut().for_each(|n| *n = 0);
+        self.golds.clear();
+        self.is_completed = false;
+    }
+
+    fn observe_split(&mut self, split: short::Name, evt: &event::Split) -> Vec<Payload> {
+        match evt {
+            event::Split::Time(_, event::Time::Pushed) => {
+                *self.times.entry(split).or_default() += 1;
+            }
+            event::Split::Popped(pop) => {
+                let times = self.times.entry(split).or_default();
+                *times = match pop {
+                    session::action::Pop::One => times.saturating_sub(1),
+                    _ => 0,
+                };
+                self.golds.remove(&split);
+                self.is_completed = false;
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    /// Fires gold split hooks for any splits that have just become gold in `notes`, and
+    /// completion hooks if `notes` show the run has just been completed.
+    ///
+    /// Notes are recalculated often, so a split that stays gold only fires once.
+    fn observe_notes(&mut self, notes: &event::Notes) -> Vec<Payload> {
+        let mut payloads = vec![];
+        for (split, note) in &notes.splits {
+            let is_gold = matches!(note.delta, Some(d) if d.is_personal_best);
+            if is_gold && self.golds.insert(*split) {
+                let time = Some(note.aggregates.split);
+                payloads.push(self.payload(Trigger::GoldSplit, Some(*split), time));
+            }
+        }
+        // We can't use the attempt total events here, as they only carry a time when there is a
+        // comparison to take a delta against.
+        if let Some(last) = self.last.and_then(|s| notes.splits.get(&s)) {
+            payloads.extend(self.observe_completion(last.aggregates.cumulative));
+        }
+        payloads
+    }
+
+    /// Fires completion hooks if every split has a time, given the run total `time`.
+    fn observe_completion(&mut self, time: time::Time) -> Vec<Payload> {
+        if self.is_completed || self.times.is_empty() || self.times.values().any(|n| *n == 0) {
+            return vec![];
+        }
+        self.is_completed = true;
+
+        let mut result = vec![self.payload(Trigger::RunCompleted, None, Some(time))];
+        if self.pb_total.is_none_or(|pb| time < pb) {
+            result.push(self.payload(Trigger::PersonalBest, None, Some(time)));
+        }
+        result
+    }
+
+    fn payload(
+        &self,
+        event: Trigger,
+        split: Option<short::Name>,
+        time: Option<time::Time>,
+    ) -> Payload {
+        Payload {
+            event,
+            split,
+            time: time.map(time::human::Time::from),
+            attempt: self.attempt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        session::state::SplitNote,
+        timing::{aggregate, comparison::delta},
+    };
+
+    fn notes(split: short::Name, time: time::Time, cumulative: time::Time, is_gold: bool) -> Event {
+        let note = SplitNote {
+            aggregates: aggregate::Set {
+                split: time,
+                cumulative,
+            },
+            delta: Some(delta::Split::default().with_personal_best(is_gold)),
+        };
+        Event::Notes(event::Notes {
+            splits: [(split, note)].into_iter().collect(),
+            segments: short::Map::default(),
+        })
+    }
+
+    fn tracker() -> Tracker {
+        let mut t = Tracker::default();
+        t.set_splits(["s1".into(), "s2".into()]);
+        t
+    }
+
+    /// Pushes `millis` to `split`, as the session would, making the run total `total`.
+    fn push(t: &mut Tracker, split: &str, millis: i64, total: i64, is_gold: bool) -> Vec<Trigger> {
+        let split: short::Name = split.into();
+        let time = time::Time::from_millis(millis);
+        let total = time::Time::from_millis(total);
+        [
+            Event::Split(split, event::Split::Time(time, event::Time::Pushed)),
+            notes(split, time, total, is_gold),
+            Event::Total(event::Total::Attempt(delta::Delta::default()), None),
+        ]
+        .iter()
+        .flat_map(|e| t.observe(e))
+        .map(|p| p.event)
+        .collect()
+    }
+
+    /// Tests that completing a run with no comparison fires both completion and PB hooks, once.
+    #[test]
+    fn completion_without_comparison() {
+        let mut t = tracker();
+        assert!(push(&mut t, "s1", 1000, 1000, false).is_empty());
+        assert_eq!(
+            push(&mut t, "s2", 1000, 2000, false),
+            vec![Trigger::RunCompleted, Trigger::PersonalBest]
+        );
+        assert!(push(&mut t, "s2", 1000, 3000, false).is_empty());
+    }
+
+    /// Tests that completing a run slower than the PB doesn't fire a PB hook.
+    #[test]
+    fn completion_behind_pb() {
+        let mut t = tracker();
+        t.observe(&Event::Total(
+            event::Total::Comparison(TotalType::TotalInPbRun),
+            Some(time::Time::from_millis(1500)),
+        ));
+        push(&mut t, "s1", 1000, 1000, false);
+        assert_eq!(
+            push(&mut t, "s2", 1000, 2000, false),
+            vec![Trigger::RunCompleted]
+        );
+    }
+
+    /// Tests that completing a run faster than the PB fires a PB hook.
+    #[test]
+    fn completion_ahead_of_pb() {
+        let mut t = tracker();
+        t.observe(&Event::Total(
+            event::Total::Comparison(TotalType::TotalInPbRun),
+            Some(time::Time::from_millis(2500)),
+        ));
+        push(&mut t, "s1", 1000, 1000, false);
+        assert_eq!(
+            push(&mut t, "s2", 1000, 2000, false),
+            vec![Trigger::RunCompleted, Trigger::PersonalBest]
+        );
+    }
+
+    /// Tests that gold splits fire once, and again after being popped.
+    #[test]
+    fn gold_split_fires_once() {
+        let mut t = tracker();
+        assert_eq!(
+            push(&mut t, "s1", 1000, 1000, true),
+            vec![Trigger::GoldSplit]
+        );
+
+        // Later note recalculations re-send the gold delta, but shouldn't re-fire.
+        let time = time::Time::from_millis(1000);
+        let again = notes("s1".into(), time, time, true);
+        assert!(t.observe(&again).is_empty());
+
+        t.observe(&Event::Split(
+            "s1".into(),
+            event::Split::Popped(session::action::Pop::One),
+        ));
+        assert_eq!(push(&mut t, "s1", 900, 900, true), vec![Trigger::GoldSplit]);
+    }
+
+    /// Tests that resets fire, and clear completion.
+    #[test]
+    fn reset_clears_completion() {
+        let mut t = tracker();
+        push(&mut t, "s1", 1000, 1000, false);
+        push(&mut t, "s2", 1000, 2000, false);
+
+        let attempt = AttemptInfo {
+            total: 1,
+            completed: 1,
+        };
+        let payloads = t.observe(&Event::Reset(attempt));
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].event, Trigger::Reset);
+        assert_eq!(payloads[0].attempt, attempt);
+
+        assert!(push(&mut t, "s1", 1000, 1000, false).is_empty());
+        assert_eq!(
+            push(&mut t, "s2", 1000, 2000, false)[0],
+            Trigger::RunCompleted
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Running hooks: executing commands and posting to URLs.
+
+use super::{super::super::super::config::server::hook::Hook, Payload};
+use std::{
+    io::{BufRead, Write},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// Fires `hook` with `payload`, logging any failures.
+pub fn fire(hook: &Hook, payload: &Payload) {
+    let body = match serde_json::to_string(payload) {
+        Ok(body) => body,
+        Err(e) => {
+            log::error!("couldn't serialise hook payload: {e}");
+            return;
+        }
+    };
+
+    if !hook.command.is_empty() {
+        if let Err(e) = command(&hook.command, &body) {
+            log::warn!("hook command {:?} failed: {e}", hook.command);
+        }
+    }
+    if let Some(url) = &hook.url {
+        if let Err(e) = post(url, &body) {
+            log::warn!("hook URL {url} failed: {e}");
+        }
+    }
+}
+
+/// Runs `command`, sending `body` to its standard input and waiting for it to finish.
+fn command(command: &[String], body: &str) -> Result<()> {
+    let (program, args) = command.split_first().ok_or(Error::EmptyCommand)?;
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::CommandStatus(status))
+    }
+}
+
+/// Posts `body` as JSON to `url`, which must be a plain `http` URL.
+///
+/// We only expect hooks to talk to local services, so this is a deliberately minimal
+/// implementation of HTTP/1.1.
+fn post(url: &str, body: &str) -> Result<()> {
+    let uri: http::Uri = url.parse()?;
+    if uri.scheme_str() != Some("http") {
+        return Err(Error::Scheme);
+    }
+    let host = uri.host().ok_or(Error::Scheme)?;
+    let port = uri.port_u16().unwrap_or(80);
+    let path = uri
+        .path_and_query()
+        .map_or("/", http::uri::PathAndQuery::as_str);
+
+    let mut stream = std::net::TcpStream::connect((host, port))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let request = format!(
+        "POST {path} HTTP/1.1\r\n\
+         Host: {host}:{port}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n\
+         {body}",
+        body.len()
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut status_line = String::new();
+    std::io::BufReader::new(stream).read_line(&mut status_line)?;
+    let code: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|c| c.parse().ok())
+        .ok_or(Error::BadResponse)?;
+    if (200..300).contains(&code) {
+        Ok(())
+    } else {
+        Err(Error::HttpStatus(code))
+    }
+}
+
+/// How long we wait on a hook URL before giving up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors that can occur when running a hook.
+#[derive(Debug, Error)]
+enum Error {
+    #[error("hook command is empty")]
+    EmptyCommand,
+    #[error("command exited with {0}")]
+    CommandStatus(std::process::ExitStatus),
+    #[error("couldn't parse URL")]
+    Uri(#[from] http::uri::InvalidUri),
+    #[error("only http:// URLs with a host are supported")]
+    Scheme,
+    #[error("malformed HTTP response")]
+    BadResponse,
+    #[error("server responded with status {0}")]
+    HttpStatus(u16),
+    #[error("i/o error")]
+    Io(#[from] std::io::Error),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::server::hook::Trigger;
+    use std::io::Read;
+
+    fn payload() -> Payload {
+        Payload {
+            event: Trigger::Reset,
+            split: None,
+            time: None,
+            attempt: crate::model::game::category::AttemptInfo::default(),
+        }
+    }
+
+    /// Tests that commands get the payload on their standard input.
+    #[cfg(unix)]
+    #[test]
+    fn command_receives_payload() {
+        let dir = tempfile::tempdir().expect("couldn't open dir");
+        let out = dir.path().join("out.json");
+        let cmd = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "cat > \"$0\"".to_string(),
+            out.to_string_lossy().into_owned(),
+        ];
+        command(&cmd, "{\"event\":\"reset\"}").expect("command should succeed");
+        let got = std::fs::read_to_string(out).expect("couldn't read output");
+        assert_eq!(got, "{\"event\":\"reset\"}");
+    }
+
+    /// Tests that failing commands are reported as such.
+    #[cfg(unix)]
+    #[test]
+    fn command_failure() {
+        let cmd = vec!["false".to_string()];
+        assert!(matches!(command(&cmd, ""), Err(Error::CommandStatus(_))));
+    }
+
+    /// Tests posting a payload to a local HTTP server.
+    #[test]
+    fn post_payload() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("couldn't bind");
+        let addr = listener.local_addr().expect("no local address");
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("couldn't accept");
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"}") {
+                let n = stream.read(&mut buf).expect("couldn't read");
+                assert_ne!(n, 0, "client closed connection early");
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .expect("couldn't write");
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        // The fake server above reads until the end of the JSON object.
+        let body = serde_json::to_string(&payload()).expect("couldn't serialise");
+        post(&format!("http://{addr}/hook"), &body).expect("post should succeed");
+
+        let request = server.join().expect("server panicked");
+        assert!(request.starts_with("POST /hook HTTP/1.1\r\n"));
+        assert!(request.ends_with(&body));
+    }
+
+    /// Tests that non-HTTP URLs are rejected.
+    #[test]
+    fn post_rejects_https() {
+        assert!(matches!(
+            post("https://localhost/hook", ""),
+            Err(Error::Scheme)
+        ));
+    }
+}
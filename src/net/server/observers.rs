@@ -0,0 +1,91 @@
+//! Tracking of the clients currently observing the server's event stream.
+
+use super::super::metadata;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// A registry of the observers currently subscribed to the server.
+#[derive(Debug, Default)]
+pub struct Registry {
+    /// The identifier to hand out to the next observer.
+    next_id: AtomicU64,
+    /// The currently registered observers, keyed by identifier.
+    observers: Mutex<BTreeMap<u64, metadata::Observer>>,
+}
+
+impl Registry {
+    /// Registers an observer at `address`, returning a guard that unregisters it when dropped.
+    #[must_use]
+    pub fn register(self: &Arc<Self>, address: Option<std::net::SocketAddr>) -> Guard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let observer = metadata::Observer {
+            id,
+            address,
+            since: chrono::Utc::now(),
+        };
+        self.lock().insert(id, observer);
+        log::info!("observer {id} connected from {address:?}");
+
+        Guard {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    /// Lists the currently registered observers, in order of registration.
+    #[must_use]
+    pub fn list(&self) -> Vec<metadata::Observer> {
+        self.lock().values().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, metadata::Observer>> {
+        // The map can't be left in an inconsistent state by a panic, so poisoning is harmless.
+        self.observers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Keeps an observer registered for as long as it is alive.
+#[derive(Debug)]
+pub struct Guard {
+    registry: Arc<Registry>,
+    id: u64,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.registry.lock().remove(&self.id);
+        log::info!("observer {} disconnected", self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that observers appear in the registry until their guards are dropped.
+    #[test]
+    fn register_and_drop() {
+        let registry = Arc::new(Registry::default());
+        let first = registry.register(None);
+        let second = registry.register(Some(crate::config::server::default_addr()));
+
+        let ids: Vec<u64> = registry.list().iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+
+        drop(first);
+        let list = registry.list();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].id, 1);
+        assert_eq!(list[0].address, Some(crate::config::server::default_addr()));
+
+        drop(second);
+        assert!(registry.list().is_empty());
+    }
+}
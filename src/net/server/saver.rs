@@ -0,0 +1,122 @@
+//! Saving runs off the session task.
+//!
+//! Saving a run writes to the database, which can take a while; doing so on the session task would
+//! hold up every action queued behind the reset that triggered it.  Instead, the session hands runs
+//! to a [Sink], which queues them for a [Saver] task to save, in order, on the blocking thread pool.
+//! The saver then sends the result back to the session, which tells clients about it.
+//!
+//! Runs that are queued but whose results the session hasn't yet handled are counted as
+//! [Pending], so that the server can wait for them before shutting down.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use tokio::sync::mpsc;
+
+use super::Message;
+use crate::model::session::sink;
+
+/// The type of sink into which a [Saver] saves runs.
+pub type Inner = Box<dyn sink::Sink + Send>;
+
+/// Creates a linked sink and saver for saving runs into `inner`.
+///
+/// The saver sends the result of each save back to the session through `messages`; the session
+/// should tell the returned [Pending] each time it handles one.
+#[must_use]
+pub fn new(inner: Inner, messages: mpsc::Sender<Message>) -> (Sink, Saver, Pending) {
+    let (send, recv) = mpsc::unbounded_channel();
+    let saver = Saver {
+        sink: Arc::new(Mutex::new(inner)),
+        runs: recv,
+        messages,
+    };
+    let pending = Pending::default();
+    let sink = Sink {
+        runs: send,
+        pending: pending.clone(),
+    };
+    (sink, saver, pending)
+}
+
+/// A session sink that queues runs for a [Saver].
+pub struct Sink {
+    /// Sends runs to the saver.
+    runs: mpsc::UnboundedSender<sink::Run>,
+    /// Counts the runs sent to the saver.
+    pending: Pending,
+}
+
+impl sink::Sink for Sink {
+    fn accept(&mut self, run: sink::Run) -> sink::Result {
+        self.runs
+            .send(run)
+            .map_err(|_| anyhow::anyhow!("the run saver has stopped"))?;
+        self.pending.0.fetch_add(1, Ordering::SeqCst);
+        Ok(sink::Outcome::Queued)
+    }
+}
+
+/// Counts the runs queued by a [Sink] whose results the session has yet to handle.
+#[derive(Clone, Debug, Default)]
+pub struct Pending(Arc<AtomicUsize>);
+
+impl Pending {
+    /// Notes that the session has handled the result of saving one queued run.
+    pub fn finish(&self) {
+        // The closure always returns a value, so this can't fail.
+        let _res = self
+            .0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n.saturating_sub(1)));
+    }
+
+    /// Gets whether every queued run's result has been handled.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.load(Ordering::SeqCst) == 0
+    }
+}
+
+/// A task that saves runs queued by a [Sink] into another sink.
+pub struct Saver {
+    /// The sink into which runs are saved.
+    ///
+    /// This is shared with each blocking task that saves a run, though only one such task runs
+    /// at a time.
+    sink: Arc<Mutex<Inner>>,
+    /// Receives runs from the session.
+    runs: mpsc::UnboundedReceiver<sink::Run>,
+    /// Sends messages back to the session.
+    messages: mpsc::Sender<Message>,
+}
+
+impl Saver {
+    /// Saves runs until every [Sink] linked to this saver has been dropped.
+    pub async fn run(mut self) {
+        while let Some(run) = self.runs.recv().await {
+            let sink = self.sink.clone();
+            let copy = run.clone();
+            let result = match tokio::task::spawn_blocking(move || accept(&sink, copy)).await {
+                Ok(result) => result,
+                Err(e) => Err(anyhow::Error::new(e).into()),
+            };
+            let message = Message::Saved(run, result);
+            if let Err(mpsc::error::SendError(Message::Saved(_, Err(e)))) =
+                self.messages.send(message).await
+            {
+                // The session has stopped, so this is the only place the failure gets reported.
+                log::error!("couldn't save run: {e}");
+            }
+        }
+    }
+}
+
+/// Saves `run` into `sink`.
+fn accept(sink: &Mutex<Inner>, run: sink::Run) -> sink::Result {
+    let mut sink = sink
+        .lock()
+        .map_err(|_| anyhow::anyhow!("an earlier save panicked"))?;
+    sink.accept(run)
+}
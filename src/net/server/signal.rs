@@ -0,0 +1,33 @@
+//! Routing of process signals into the server's graceful shutdown path.
+
+use tokio::sync::mpsc;
+
+/// Waits for SIGINT or SIGTERM, then asks the server to shut down through `send`.
+///
+/// The server decides what to do with the current attempt when the signal arrives, so that
+/// reloading the configuration can change it.
+pub async fn forward(send: mpsc::Sender<super::Message>) {
+    if let Err(e) = wait().await {
+        log::error!("couldn't listen for signals: {e}");
+        return;
+    }
+
+    log::info!("received signal, shutting down");
+    if send.send(super::Message::Signal).await.is_err() {
+        log::warn!("server already stopped before signal could be handled");
+    }
+}
+
+#[cfg(unix)]
+async fn wait() -> std::io::Result<()> {
+    let mut term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    tokio::select! {
+        res = tokio::signal::ctrl_c() => res,
+        _ = term.recv() => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait() -> std::io::Result<()> {
+    tokio::signal::ctrl_c().await
+}
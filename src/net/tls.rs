@@ -0,0 +1,247 @@
+/*! TLS support for the zombiesplit netcode.
+
+This module turns the TLS parts of the zombiesplit configuration into the equivalent `tonic`
+configuration, and also contains a helper for generating self-signed certificates (useful for
+first-time setup of a server that will be accessed remotely). */
+
+use super::super::config::tls;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Loads a server-side TLS configuration from the certificate and key files named in `cfg`.
+///
+/// # Errors
+///
+/// Fails if either file can't be read.
+pub fn server_config(cfg: &tls::Server) -> Result<tonic::transport::ServerTlsConfig> {
+    let cert = read(&cfg.cert)?;
+    let key = read(&cfg.key)?;
+    Ok(tonic::transport::ServerTlsConfig::new()
+        .identity(tonic::transport::Identity::from_pem(cert, key)))
+}
+
+/// Loads a client-side TLS configuration from `cfg`.
+///
+/// # Errors
+///
+/// Fails if `cfg` names a CA certificate that can't be read.
+pub fn client_config(cfg: &tls::Client) -> Result<tonic::transport::ClientTlsConfig> {
+    let mut result = tonic::transport::ClientTlsConfig::new();
+    if let Some(ca) = &cfg.ca {
+        result = result.ca_certificate(tonic::transport::Certificate::from_pem(read(ca)?));
+    }
+    if let Some(domain) = &cfg.domain {
+        result = result.domain_name(domain);
+    }
+    Ok(result)
+}
+
+/// A freshly generated self-signed certificate, along with its private key.
+pub struct SelfSigned {
+    /// The PEM-encoded certificate.
+    pub cert: String,
+    /// The PEM-encoded private key.
+    pub key: String,
+}
+
+impl SelfSigned {
+    /// Generates a self-signed certificate valid for each of the domain names in `names`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the certificate generator can't create or serialise the certificate.
+    pub fn generate(names: impl Into<Vec<String>>) -> Result<Self> {
+        let cert = rcgen::generate_simple_self_signed(names)?;
+        Ok(Self {
+            cert: cert.serialize_pem()?,
+            key: cert.serialize_private_key_pem(),
+        })
+    }
+
+    /// Writes this certificate and key to the paths given in `cfg`, overwriting any existing files.
+    ///
+    /// Both files are written in full before either is moved into place, so a failure doesn't
+    /// leave behind a certificate without its key.  On Unix, the key is only readable and writable
+    /// by its owner.
+    ///
+    /// # Errors
+    ///
+    /// Fails if either file can't be written.
+    pub fn write(&self, cfg: &tls::Server) -> Result<()> {
+        let cert = stage(&cfg.cert, &self.cert, false)?;
+        let key = stage(&cfg.key, &self.key, true)?;
+        persist(cert, &cfg.cert)?;
+        persist(key, &cfg.key).inspect_err(|_| {
+            // Otherwise, the next attempt to generate a pair would refuse to overwrite the cert.
+            let _res = std::fs::remove_file(&cfg.cert);
+        })
+    }
+}
+
+/// Generates a self-signed certificate for `names` at the paths in `cfg`, unless both files
+/// already exist.
+///
+/// Returns whether a certificate was generated.
+///
+/// # Errors
+///
+/// Fails if generation fails, or the certificate files can't be written.  Also fails if only one
+/// of the two files exists: a new certificate wouldn't match the existing key (or vice versa), so
+/// rather than overwrite what might be the user's own file, we leave both alone.
+pub fn ensure_self_signed(cfg: &tls::Server, names: impl Into<Vec<String>>) -> Result<bool> {
+    match (cfg.cert.exists(), cfg.key.exists()) {
+        (true, true) => return Ok(false),
+        (true, false) => {
+            return Err(Error::HalfPair {
+                present: cfg.cert.clone(),
+                missing: cfg.key.clone(),
+            })
+        }
+        (false, true) => {
+            return Err(Error::HalfPair {
+                present: cfg.key.clone(),
+                missing: cfg.cert.clone(),
+            })
+        }
+        (false, false) => (),
+    }
+    log::info!(
+        "generating self-signed certificate at {}",
+        cfg.cert.display()
+    );
+    SelfSigned::generate(names)?.write(cfg)?;
+    Ok(true)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Writes `contents` to a temporary file alongside `path`, ready to be moved onto it.
+///
+/// If `private`, only the file's owner can access it.
+fn stage(path: &Path, contents: &str, private: bool) -> Result<tempfile::NamedTempFile> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    tempfile::NamedTempFile::new_in(dir)
+        .and_then(|mut file| {
+            set_private(file.as_file(), private)?;
+            file.write_all(contents.as_bytes())?;
+            Ok(file)
+        })
+        .map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Moves the temporary file `file` onto `path`.
+fn persist(file: tempfile::NamedTempFile, path: &Path) -> Result<()> {
+    file.persist(path).map(|_| ()).map_err(|e| Error::Io {
+        path: path.to_path_buf(),
+        source: e.error,
+    })
+}
+
+/// Makes `file` accessible only to its owner if `private`, and readable by everyone otherwise.
+#[cfg(unix)]
+fn set_private(file: &std::fs::File, private: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = if private { 0o600 } else { 0o644 };
+    file.set_permissions(std::fs::Permissions::from_mode(mode))
+}
+
+/// Does nothing, as permissions outside Unix don't map onto owner-only access.
+#[cfg(not(unix))]
+fn set_private(_file: &std::fs::File, _private: bool) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Errors that can occur when setting up TLS.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Couldn't read or write a certificate or key file.
+    #[error("couldn't access TLS file {}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Only one of the certificate and key exists, so we can't generate a matching pair.
+    #[error(
+        "{} exists but {} doesn't; move it aside to generate a new pair",
+        present.display(),
+        missing.display()
+    )]
+    HalfPair { present: PathBuf, missing: PathBuf },
+    /// Couldn't generate a self-signed certificate.
+    #[error("couldn't generate certificate")]
+    Generate(#[from] rcgen::RcgenError),
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that generating a self-signed certificate and writing it out produces files that we
+    /// can then load back in as a server configuration.
+    #[test]
+    fn self_signed_round_trip() {
+        let dir = tempfile::tempdir().expect("couldn't open dir");
+        let cfg = tls::Server {
+            cert: dir.path().join("cert.pem"),
+            key: dir.path().join("key.pem"),
+        };
+
+        assert!(ensure_self_signed(&cfg, vec!["localhost".to_string()]).expect("should generate"));
+        assert!(!ensure_self_signed(&cfg, vec!["localhost".to_string()]).expect("should skip"));
+
+        let cert = std::fs::read_to_string(&cfg.cert).expect("couldn't read cert");
+        assert!(cert.starts_with("-----BEGIN CERTIFICATE-----"));
+        server_config(&cfg).expect("couldn't load generated certificate");
+
+        let files = std::fs::read_dir(dir.path()).expect("couldn't list dir");
+        assert_eq!(2, files.count(), "no temporary files should be left over");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&cfg.key)
+                .expect("couldn't stat key")
+                .permissions()
+                .mode();
+            assert_eq!(0o600, mode & 0o777, "key should be private");
+        }
+    }
+
+    /// Tests that we refuse to generate a certificate when only one of the pair exists.
+    #[test]
+    fn self_signed_half_pair() {
+        let dir = tempfile::tempdir().expect("couldn't open dir");
+        let cfg = tls::Server {
+            cert: dir.path().join("cert.pem"),
+            key: dir.path().join("key.pem"),
+        };
+        std::fs::write(&cfg.key, "my precious key").expect("couldn't write key");
+
+        let err = ensure_self_signed(&cfg, vec!["localhost".to_string()])
+            .expect_err("should refuse to overwrite the key");
+        assert!(matches!(err, Error::HalfPair { .. }));
+        assert_eq!(
+            "my precious key",
+            std::fs::read_to_string(&cfg.key).expect("couldn't read key")
+        );
+        assert!(!cfg.cert.exists());
+    }
+}
@@ -0,0 +1,163 @@
+/*! Protocol versioning and compatibility checks.
+
+The zombiesplit protocol is not yet stable, so clients and servers check each other's protocol
+versions before talking.  Both sides use the same rule: two versions are compatible if they match
+under semver caret rules (so, while we are on `0.x`, only versions sharing a minor version are
+compatible).
+
+Clients check the version the server reports through its server-info RPC when they connect, and
+declare their own version in the metadata of every request (under [`METADATA_KEY`]); the server
+rejects requests whose declared version is incompatible.  Requests that don't declare a version
+(for instance, from generic `gRPC` tools) are let through.
+
+The check is only as good as [`CURRENT`], so any change to the protocol that an older peer would
+misread or miss must bump it.  This includes renumbering, retyping, or removing fields; replacing
+messages or events; and adding events that older clients would fail to decode.  While we are
+on `0.x`, such changes bump the minor version.  Several changes between releases can share one
+bump, as long as no build speaking the old protocol has been released with the new version number.
+Changes that old peers can safely ignore, such as new optional fields, only need a patch bump. */
+
+use semver::{Comparator, Op, Version, VersionReq};
+use thiserror::Error;
+
+/// The version of the protocol spoken by this build of zombiesplit.
+pub const CURRENT: Version = Version::new(0, 2, 0);
+
+/// The `gRPC` metadata key under which clients declare their protocol version.
+pub const METADATA_KEY: &str = "zombiesplit-version";
+
+/// Gets the range of protocol versions with which this build of zombiesplit is compatible.
+#[must_use]
+pub fn supported() -> VersionReq {
+    VersionReq {
+        comparators: vec![Comparator {
+            op: Op::Caret,
+            major: CURRENT.major,
+            minor: Some(CURRENT.minor),
+            patch: None,
+            pre: semver::Prerelease::EMPTY,
+        }],
+    }
+}
+
+/// Checks that a peer speaking protocol version `theirs` is compatible with us.
+///
+/// # Errors
+///
+/// Fails with [`Error::Incompatible`] if `theirs` is outside [supported].
+pub fn check(theirs: &Version) -> Result<()> {
+    let supported = supported();
+    if supported.matches(theirs) {
+        Ok(())
+    } else {
+        Err(Error::Incompatible {
+            theirs: theirs.clone(),
+            supported,
+        })
+    }
+}
+
+/// Client-side interceptor that declares [CURRENT] in the metadata of each request.
+///
+/// # Errors
+///
+/// Never fails, but has the signature `tonic` expects of an interceptor.
+pub fn declare(
+    mut request: tonic::Request<()>,
+) -> std::result::Result<tonic::Request<()>, tonic::Status> {
+    // Versions only contain ASCII digits and dots, so this can't fail.
+    if let Ok(value) = CURRENT.to_string().parse() {
+        request.metadata_mut().insert(METADATA_KEY, value);
+    }
+    Ok(request)
+}
+
+/// Server-side interceptor that rejects requests declaring an incompatible protocol version.
+///
+/// # Errors
+///
+/// Fails with `invalid_argument` if the declared version is malformed, and `failed_precondition`
+/// if it is incompatible.
+pub fn enforce(
+    request: tonic::Request<()>,
+) -> std::result::Result<tonic::Request<()>, tonic::Status> {
+    if let Some(value) = request.metadata().get(METADATA_KEY) {
+        let theirs = value
+            .to_str()
+            .ok()
+            .and_then(|x| x.parse::<Version>().ok())
+            .ok_or_else(|| {
+                tonic::Status::invalid_argument(format!("malformed {METADATA_KEY} metadata"))
+            })?;
+        check(&theirs).map_err(|e| tonic::Status::failed_precondition(e.to_string()))?;
+    }
+    Ok(request)
+}
+
+/// Errors that can occur when checking protocol versions.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum Error {
+    /// The peer speaks a version of the protocol we don't support.
+    #[error("peer speaks protocol version {theirs}, but we only support {supported}")]
+    Incompatible {
+        /// The peer's version.
+        theirs: Version,
+        /// The versions we support.
+        supported: VersionReq,
+    },
+}
+
+/// Shorthand for results over [Error].
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that we are compatible with ourselves and with patch-level differences.
+    #[test]
+    fn check_compatible() {
+        check(&CURRENT).expect("should be compatible with ourselves");
+        let mut patched = CURRENT;
+        patched.patch += 1;
+        check(&patched).expect("should be compatible with a newer patch");
+    }
+
+    /// Tests that we reject versions with a different minor or major version.
+    #[test]
+    fn check_incompatible() {
+        let mut minor = CURRENT;
+        minor.minor += 1;
+        assert!(check(&minor).is_err());
+
+        let mut major = CURRENT;
+        major.major += 1;
+        assert!(check(&major).is_err());
+    }
+
+    /// Tests that the server-side interceptor lets through our own requests and requests that
+    /// declare nothing, but rejects incompatible and malformed versions.
+    #[test]
+    fn enforce_round_trip() {
+        let ours = declare(tonic::Request::new(())).expect("declare should succeed");
+        assert!(enforce(ours).is_ok());
+        assert!(enforce(tonic::Request::new(())).is_ok());
+
+        let mut bad = tonic::Request::new(());
+        bad.metadata_mut()
+            .insert(METADATA_KEY, "99.0.0".parse().expect("valid metadata"));
+        assert_eq!(
+            enforce(bad).expect_err("should reject").code(),
+            tonic::Code::FailedPrecondition
+        );
+
+        let mut garbage = tonic::Request::new(());
+        garbage
+            .metadata_mut()
+            .insert(METADATA_KEY, "banana".parse().expect("valid metadata"));
+        assert_eq!(
+            enforce(garbage).expect_err("should reject").code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+}
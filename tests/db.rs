@@ -1,9 +1,16 @@
 //! Tests the database functionality on an in-memory database.
 
-use std::{convert::TryFrom, ops::Add, rc::Rc};
+use std::{
+    ops::Add,
+    sync::atomic::{AtomicI64, Ordering},
+};
 use tempfile::{tempdir, TempDir};
 use zombiesplit::{
-    db::{self, Db, Reader, Sink},
+    db::{
+        self,
+        game::update::{Change, Kind},
+        Db, Reader, Sink,
+    },
     model::{
         game::{self, category::ShortDescriptor},
         history,
@@ -13,7 +20,7 @@ use zombiesplit::{
             event,
         },
         short,
-        timing::time::human::Time,
+        timing::Time,
         Loadable,
     },
 };
@@ -44,15 +51,24 @@ fn short_descriptor() -> ShortDescriptor {
     ShortDescriptor::new(SAMPLE_GAME_NAME, SAMPLE_CATEGORY_NAME)
 }
 
-fn init_session(handle: &Reader, snk: Sink) -> session::Session<event::observer::Null> {
-    let mut insp = handle
+/// Number of weeks into the future at which to timestamp runs saved by sessions.
+static WEEKS_AHEAD: AtomicI64 = AtomicI64::new(0);
+
+fn timestamp() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now().add(chrono::Duration::weeks(WEEKS_AHEAD.load(Ordering::SeqCst)))
+}
+
+fn init_session(handle: &Reader, snk: Sink) -> session::Session<'_, '_, event::observer::Null> {
+    let attempt = handle
         .inspect(&short_descriptor())
-        .expect("couldn't open category db");
-    let mut ses = insp
-        .init_session(&event::observer::Null)
-        .expect("couldn't init session");
-    ses.set_sink(Box::new(snk));
-    ses
+        .expect("couldn't open category db")
+        .attempt()
+        .expect("couldn't get attempt");
+    session::Session::builder(attempt, &event::observer::Null)
+        .sink(Box::new(snk))
+        .timestamper(timestamp)
+        .build()
+        .expect("couldn't build session")
 }
 
 /// Tests initialising the database and getting a session out of it.
@@ -61,7 +77,7 @@ fn test_sample_session() {
     let tdir = tempdir().expect("can't open dir");
 
     let game = load_game();
-    let db = Rc::new(setup_db(&game, &tdir));
+    let db = setup_db(&game, &tdir);
     let handle = db.reader().expect("couldn't open reader");
 
     let mut session = init_session(&handle, Sink::new(db));
@@ -75,6 +91,41 @@ fn test_sample_session() {
     );
 }
 
+/// Tests opening a database initialised with the schema from before versioning.
+#[test]
+fn test_migrate_baseline() {
+    let tdir = tempdir().expect("can't open dir");
+    let mut file = tdir.path().to_path_buf();
+    file.push("baseline.db");
+    rusqlite::Connection::open(&file)
+        .expect("couldn't create baseline db")
+        .execute_batch(include_str!("baseline.sql"))
+        .expect("couldn't populate baseline db");
+
+    let db = Db::new(&file).expect("couldn't migrate baseline db");
+
+    let attempt = db
+        .reader()
+        .expect("couldn't open reader")
+        .inspect(&short_descriptor())
+        .expect("couldn't find baseline category")
+        .attempt()
+        .expect("couldn't get attempt");
+    assert_eq!("Sonic CD (2011)", attempt.category.game);
+    assert_eq!(None, attempt.category.frame_rate);
+    assert_eq!(2, attempt.splits.len());
+
+    let runs = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries");
+    assert_eq!(1, runs.len(), "the baseline run should survive");
+    assert_eq!(Time::from_millis(89_500), runs[0].timing.total);
+
+    // The migrated database should take games that share short names, as new ones do.
+    db.add_game("scd11-copy", &load_game())
+        .expect("couldn't add game to migrated db");
+}
+
 /// Tests initialising the database and adding a run to it.
 #[test]
 fn test_sample_add_run() {
@@ -102,7 +153,7 @@ fn test_sample_observe_run() {
     let tdir = tempdir().expect("can't open dir");
 
     let game = load_game();
-    let db = Rc::new(setup_db(&game, &tdir));
+    let db = setup_db(&game, &tdir);
     let handle = db.reader().expect("couldn't open reader");
 
     let mut session = init_session(&handle, Sink::new(db.clone()));
@@ -112,10 +163,9 @@ fn test_sample_observe_run() {
         .handle(Action::NewRun(OldDestination::Save))
         .unwrap();
 
-    let time = Time::try_from(8675309).expect("time didn't parse");
+    let time = Time::from_millis(8_675_309);
 
     // This also shouldn't.
-    session.set_timestamper(chrono::Utc::now);
     inject_session_actions(
         &mut session,
         vec![Action::Push(0, time)],
@@ -123,7 +173,6 @@ fn test_sample_observe_run() {
     );
 
     // This should.
-    session.set_timestamper(chrono::Utc::now);
     inject_session_actions(
         &mut session,
         vec![Action::Push(0, time)],
@@ -133,7 +182,7 @@ fn test_sample_observe_run() {
     // As should this.
     // (We change the timestamp to avoid having the database reject the run as
     // a duplicate.)
-    session.set_timestamper(|| chrono::Utc::now().add(chrono::Duration::weeks(1)));
+    WEEKS_AHEAD.store(1, Ordering::SeqCst);
     inject_session_actions(
         &mut session,
         vec![Action::Push(0, time), Action::Push(1, time)],
@@ -169,13 +218,137 @@ fn test_sample_observe_run() {
     }
 }
 
+/// Tests that games sharing category and split short names don't interfere.
+#[test]
+fn test_games_share_short_names() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+    db.add_game("scd11-copy", &game)
+        .expect("couldn't add second copy of game");
+
+    let mut run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    run.category_locator = ShortDescriptor::new("scd11-copy", SAMPLE_CATEGORY_NAME);
+    db.add_run(&run).expect("couldn't insert run");
+
+    let runs = |game| {
+        db.runs_for(&ShortDescriptor::new(game, SAMPLE_CATEGORY_NAME))
+            .expect("couldn't get run summaries")
+            .len()
+    };
+    assert_eq!(0, runs(SAMPLE_GAME_NAME));
+    assert_eq!(1, runs("scd11-copy"));
+}
+
+/// Tests updating a stored game with new and renamed components.
+#[test]
+fn test_sample_update_game() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let mut game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    game.splits
+        .get_mut(&short::Name::from("pp1"))
+        .expect("pp1 should exist")
+        .name = "Palmtree Panic Act 1".to_owned();
+    game.categories.insert(
+        short::Name::from("btg-short"),
+        game::config::Category {
+            name: "Beat the Game (Short)".to_owned(),
+            extends: Some(short::Name::from(SAMPLE_CATEGORY_NAME)),
+            segments: vec![],
+            replace: short::Map::new(),
+            remove: vec![short::Name::from("mm")],
+            insert: vec![],
+        },
+    );
+
+    let plan = db
+        .update_game(SAMPLE_GAME_NAME, &game, false)
+        .expect("update shouldn't be destructive");
+    assert!(plan.changes.contains(&Change::Rename {
+        kind: Kind::Split,
+        short: short::Name::from("pp1"),
+        from: "Palmtree Panic 1".to_owned(),
+        to: "Palmtree Panic Act 1".to_owned(),
+    }));
+    assert!(plan.changes.contains(&Change::Add {
+        kind: Kind::Category,
+        short: short::Name::from("btg-short"),
+    }));
+
+    assert_eq!(
+        4,
+        db.game_categories()
+            .expect("couldn't get game categories")
+            .len()
+    );
+    assert!(db
+        .plan_game_update(SAMPLE_GAME_NAME, &game)
+        .expect("couldn't plan no-op update")
+        .is_empty());
+}
+
+/// Tests that updates orphaning stored runs need to be allowed explicitly.
+#[test]
+fn test_sample_update_game_destructive() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let mut game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    db.add_run(&run).expect("couldn't insert run");
+
+    game.categories
+        .get_mut(&short::Name::from(SAMPLE_CATEGORY_NAME))
+        .expect("category should exist")
+        .remove
+        .push(short::Name::from("mm"));
+
+    let plan = db
+        .plan_game_update(SAMPLE_GAME_NAME, &game)
+        .expect("couldn't plan update");
+    assert_eq!(
+        3,
+        plan.destructive().count(),
+        "each mm split should be dropped"
+    );
+
+    assert!(matches!(
+        db.update_game(SAMPLE_GAME_NAME, &game, false),
+        Err(db::Error::DestructiveUpdate { .. })
+    ));
+    assert_eq!(
+        3,
+        db.plan_game_update(SAMPLE_GAME_NAME, &game)
+            .expect("couldn't plan update")
+            .destructive()
+            .count(),
+        "refused update shouldn't change anything"
+    );
+
+    db.update_game(SAMPLE_GAME_NAME, &game, true)
+        .expect("destructive update should be allowed");
+    assert!(!db
+        .plan_game_update(SAMPLE_GAME_NAME, &game)
+        .expect("couldn't plan update")
+        .is_destructive());
+}
+
 fn inject_session_actions(
     session: &mut session::Session<event::observer::Null>,
     actions: Vec<Action>,
     dest: OldDestination,
 ) {
     for action in actions {
-        session.handle(action).unwrap()
+        session.handle(action).unwrap();
     }
-    session.handle(Action::NewRun(dest)).unwrap();
+    let outcome = session.handle(Action::NewRun(dest)).unwrap();
+    let expected = (dest == OldDestination::Save).then_some(session::sink::Outcome::Saved);
+    assert_eq!(expected, outcome);
 }
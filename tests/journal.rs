@@ -0,0 +1,171 @@
+//! Integration tests for recording and replaying session journals.
+
+use zombiesplit::model::{
+    game,
+    session::{
+        action::{Action, Handler, OldDestination, Pop},
+        event, journal, Attempt, Builder, Observer, Session,
+    },
+    short,
+    timing::{
+        aggregate,
+        comparison::{self, Comparison},
+        time::Time,
+    },
+    Loadable,
+};
+
+const SAMPLE_GAME_PATH: &str = "scd11.toml";
+const SAMPLE_GAME_NAME: &str = "scd11";
+const SAMPLE_CATEGORY_NAME: &str = "btg-sonic";
+
+fn load_game() -> game::Config {
+    game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load sample game")
+}
+
+fn category() -> game::category::ShortDescriptor {
+    game::category::ShortDescriptor::new(SAMPLE_GAME_NAME, SAMPLE_CATEGORY_NAME)
+}
+
+fn make_attempt() -> Attempt {
+    Attempt::from_config(&load_game(), category()).expect("couldn't load game/category")
+}
+
+/// Constructs a comparison covering the first act of Sonic CD (2011).
+fn comparison() -> Comparison {
+    let time = Time::from_millis(25_060);
+    let segment = comparison::Segment {
+        split_pb: time,
+        in_pb_run: aggregate::Set {
+            split: time,
+            cumulative: time,
+        },
+    };
+    Comparison {
+        splits: short::Map::from([("pp1".into(), segment)]),
+        ..Comparison::default()
+    }
+}
+
+/// Sets up a session the same way for recording and replaying.
+fn setup<'c, 'o, O: Observer>(builder: Builder<'c, 'o, O>) -> Builder<'c, 'o, O> {
+    builder.comparison_provider(Box::new(Some(comparison())))
+}
+
+/// Runs a short session through a recorder, returning the journal it wrote.
+fn record() -> Vec<u8> {
+    let recorder = journal::Recorder::new(vec![]);
+    {
+        let mut session = setup(Session::builder(make_attempt(), &recorder))
+            .build()
+            .expect("couldn't build session");
+        recorder.record(None).expect("couldn't record setup");
+
+        for action in [
+            Action::Push(0, Time::from_millis(24_500)),
+            Action::Push(1, Time::from_millis(26_000)),
+            Action::Pop(1, Pop::One),
+            Action::Push(1, Time::from_millis(25_300)),
+            Action::NewRun(OldDestination::Discard),
+            Action::Push(0, Time::from_millis(25_700)),
+        ] {
+            session.handle(action).expect("action failed");
+            recorder
+                .record(Some(action))
+                .expect("couldn't record action");
+        }
+    }
+    recorder.into_inner()
+}
+
+/// Tests that a recorded journal replays into a fresh session.
+#[test]
+fn round_trip() {
+    let entries = journal::read(&record()[..]).expect("couldn't read journal");
+    assert!(entries.iter().any(|e| e.action.is_none()));
+
+    let count = journal::replay(make_attempt(), entries, &event::Filter::default(), setup)
+        .expect("replay failed");
+    assert_eq!(6, count);
+}
+
+/// Tests that a journal can be replayed straight from game config.
+#[test]
+fn round_trip_config() {
+    let entries = journal::read(&record()[..]).expect("couldn't read journal");
+    let count = journal::replay_config(
+        &load_game(),
+        category(),
+        entries,
+        &event::Filter::default(),
+        setup,
+    )
+    .expect("replay failed");
+    assert_eq!(6, count);
+}
+
+/// Tests that replaying from game config fails if the category doesn't exist.
+#[test]
+fn missing_category() {
+    let entries = journal::read(&record()[..]).expect("couldn't read journal");
+    let err = journal::replay_config(
+        &load_game(),
+        game::category::ShortDescriptor::new(SAMPLE_GAME_NAME, "no-such-category"),
+        entries,
+        &event::Filter::default(),
+        setup,
+    )
+    .expect_err("replay should have failed");
+    assert!(matches!(err, journal::Error::Config(_)));
+}
+
+/// Tests that replaying a journal whose events don't match the session fails.
+#[test]
+fn mismatch() {
+    let mut entries = journal::read(&record()[..]).expect("couldn't read journal");
+    let tampered = entries
+        .iter_mut()
+        .position(|e| e.action.is_some())
+        .expect("no actions recorded");
+    entries[tampered].events.pop();
+
+    let err = journal::replay(make_attempt(), entries, &event::Filter::default(), setup)
+        .expect_err("replay should have failed");
+    assert!(matches!(
+        err,
+        journal::Error::Mismatch { entry, .. } if entry == tampered
+    ));
+}
+
+/// Tests that filters can leave out events that differ on replay.
+#[test]
+fn filtered() {
+    let mut entries = journal::read(&record()[..]).expect("couldn't read journal");
+    for entry in &mut entries {
+        entry
+            .events
+            .retain(|e| event::filter::Kind::of(e) != event::filter::Kind::Total);
+    }
+
+    let filter = event::Filter {
+        kinds: [
+            event::filter::Kind::Reset,
+            event::filter::Kind::Time,
+            event::filter::Kind::Notes,
+            event::filter::Kind::Pop,
+        ]
+        .into_iter()
+        .collect(),
+        ..event::Filter::default()
+    };
+    journal::replay(make_attempt(), entries, &filter, setup).expect("replay failed");
+}
+
+/// Tests that garbage in a journal is reported with its line number.
+#[test]
+fn bad_line() {
+    let mut bytes = record();
+    bytes.extend_from_slice(b"\nnot json\n");
+    let err = journal::read(&bytes[..]).expect_err("read should have failed");
+    assert!(matches!(err, journal::Error::Parse { .. }));
+}
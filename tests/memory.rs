@@ -0,0 +1,256 @@
+//! Tests the in-memory store against the database.
+
+use tempfile::tempdir;
+use zombiesplit::{
+    db::{
+        self,
+        category::{Locator, Source as _},
+        inspect::{self, Inspector},
+        memory, run, Db,
+    },
+    model::{
+        game::{self, category::ShortDescriptor},
+        history, short,
+        timing::{time::Time, Comparison},
+        Loadable,
+    },
+};
+
+const SAMPLE_GAME_PATH: &str = "scd11.toml";
+const SAMPLE_RUN_PATH: &str = "scd11-pb.toml";
+const SAMPLE_GAME_NAME: &str = "scd11";
+const SAMPLE_CATEGORY_NAME: &str = "btg-sonic";
+
+fn short_descriptor() -> ShortDescriptor {
+    ShortDescriptor::new(SAMPLE_GAME_NAME, SAMPLE_CATEGORY_NAME)
+}
+
+/// Makes a few runs that, between them, exercise ranking and split and segment PBs.
+fn sample_runs() -> Vec<history::run::FullyTimed<ShortDescriptor>> {
+    let pb = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+
+    // A slower complete run, but with a faster split.
+    let mut slower = pb.clone();
+    slower.date = pb.date + chrono::Duration::days(1);
+    slower
+        .timing
+        .times
+        .insert("pp1".into(), vec![Time::from_millis(20_000)]);
+    slower
+        .timing
+        .times
+        .insert("pp2".into(), vec![Time::from_millis(40_000)]);
+
+    // An incomplete run with a gold segment, and a split entered in two parts.
+    let mut reset = pb.clone();
+    reset.date = pb.date + chrono::Duration::days(2);
+    reset.was_completed = false;
+    reset
+        .timing
+        .times
+        .retain(|s, _| s.to_string().starts_with("pp"));
+    reset.timing.times.insert(
+        "pp3".into(),
+        vec![Time::from_millis(10_000), Time::from_millis(5_000)],
+    );
+
+    vec![pb, slower, reset]
+}
+
+fn setup_store(game: game::Config) -> memory::Store {
+    let mut store = memory::Store::new();
+    store.add_game(SAMPLE_GAME_NAME, game);
+    for run in sample_runs() {
+        store.add_run(&run).expect("couldn't add run to store");
+    }
+    store
+}
+
+fn setup_db(game: &game::Config, dir: &tempfile::TempDir) -> Db {
+    let db = Db::new(dir.path().join("test.db")).expect("couldn't open db");
+    db.init().expect("couldn't initialise database");
+    db.add_game(SAMPLE_GAME_NAME, game)
+        .expect("couldn't add game to database");
+    for run in sample_runs() {
+        db.add_run(&run).expect("couldn't add run to database");
+    }
+    db
+}
+
+fn assert_same_comparison(expected: &Comparison, actual: &Comparison) {
+    assert_eq!(expected.run, actual.run, "run totals differ");
+    for (what, exp, act) in [
+        ("split", &expected.splits, &actual.splits),
+        ("segment", &expected.segments, &actual.segments),
+    ] {
+        assert_eq!(exp.len(), act.len(), "{what} counts differ");
+        for (short, e) in exp {
+            let a = act
+                .get(short)
+                .unwrap_or_else(|| panic!("{what} {short} missing"));
+            assert_eq!(e.split_pb, a.split_pb, "{what} {short} PBs differ");
+            assert_eq!(
+                e.in_pb_run, a.in_pb_run,
+                "{what} {short} in-run times differ"
+            );
+        }
+    }
+}
+
+/// Tests that the store computes the same comparison as the database.
+#[test]
+fn comparison_matches_database() {
+    let game = game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load game");
+    let dir = tempdir().expect("couldn't open dir");
+    let db = setup_db(&game, &dir);
+    let mut store = setup_store(game);
+
+    let reader = db.reader().expect("couldn't get reader");
+    let mut getters = reader.getters().expect("couldn't get getters");
+    let db_id = short_descriptor()
+        .locate_gcid(&mut getters)
+        .expect("couldn't find category in database");
+    let expected = db::comparison::build(&mut getters, db_id).expect("couldn't build comparison");
+
+    let mem_id = short_descriptor()
+        .locate_gcid(&mut store)
+        .expect("couldn't find category in store");
+    let actual = db::comparison::build(&mut store, mem_id).expect("couldn't build comparison");
+
+    assert!(actual.run.sum_of_best < actual.run.total_in_pb_run);
+    assert_same_comparison(&expected, &actual);
+}
+
+/// Tests that an inspector over the store finds the same runs as one over the database.
+#[test]
+fn inspector_matches_database() {
+    let game = game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load game");
+    let dir = tempdir().expect("couldn't open dir");
+    let db = setup_db(&game, &dir);
+    let store = setup_store(game);
+
+    let reader = db.reader().expect("couldn't get reader");
+    let mut db_insp = reader
+        .inspect(&short_descriptor())
+        .expect("couldn't inspect database");
+    let mut mem_insp = Inspector::new(store, &short_descriptor()).expect("couldn't inspect store");
+
+    let db_attempt = db_insp.attempt().expect("couldn't get attempt");
+    let mem_attempt = mem_insp.attempt().expect("couldn't get attempt");
+    assert_eq!(db_attempt.info, mem_attempt.info);
+    assert_eq!(db_attempt.category, mem_attempt.category);
+
+    for i in 0..=3 {
+        let db_run = db_insp
+            .run_at_index(i, &inspect::Summary)
+            .expect("couldn't get run");
+        let mem_run = mem_insp
+            .run_at_index(i, &inspect::Summary)
+            .expect("couldn't get run");
+        assert_eq!(
+            db_run.map(|r| r.with_locator(())),
+            mem_run.map(|r| r.with_locator(())),
+            "runs at index {i} differ"
+        );
+    }
+    assert_eq!(
+        db_insp
+            .run_pb(&inspect::Summary)
+            .expect("couldn't get PB")
+            .map(|r| r.with_locator(())),
+        mem_insp
+            .run_pb(&inspect::Summary)
+            .expect("couldn't get PB")
+            .map(|r| r.with_locator(())),
+    );
+
+    let db_runs =
+        run::Source::runs_for(&mut db_insp.source, db_insp.info.id).expect("couldn't list runs");
+    let mem_runs =
+        run::Source::runs_for(&mut mem_insp.source, mem_insp.info.id).expect("couldn't list runs");
+    // Game-category IDs can differ between the two, so we only compare the rest of each run.
+    let items = |runs: Vec<db::util::WithID<history::run::Summary<_>>>| {
+        runs.into_iter()
+            .map(|r| r.item.with_locator(()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(items(db_runs), items(mem_runs));
+}
+
+/// Tests that the store refuses runs and lookups for categories it doesn't have.
+#[test]
+fn missing_category() {
+    let game = game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load game");
+    let mut store = setup_store(game);
+
+    let err = store
+        .info_from_short(&ShortDescriptor::new(SAMPLE_GAME_NAME, "nope"))
+        .expect_err("category shouldn't exist");
+    assert!(matches!(err, db::Error::MissingCategory(_)));
+
+    let mut run = sample_runs().remove(0);
+    run.category_locator = ShortDescriptor::new("nope", SAMPLE_CATEGORY_NAME);
+    assert!(store.add_run(&run).is_err());
+}
+
+/// Tests that giving a game more categories doesn't move the runs of games added after it.
+#[test]
+fn replacing_game_keeps_ids() {
+    let load = || game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load game");
+    let mut store = memory::Store::new();
+    store.add_game("first", load());
+    store.add_game(SAMPLE_GAME_NAME, load());
+    for run in sample_runs() {
+        store.add_run(&run).expect("couldn't add run to store");
+    }
+    let before = short_descriptor()
+        .locate_gcid(&mut store)
+        .expect("couldn't find category in store");
+
+    let mut game = load();
+    game.categories.insert(
+        "extra".into(),
+        game::config::Category {
+            name: "Beat the Game Again".to_owned(),
+            extends: Some(SAMPLE_CATEGORY_NAME.into()),
+            segments: vec![],
+            replace: short::Map::new(),
+            remove: vec![],
+            insert: vec![],
+        },
+    );
+    store.add_game("first", game);
+
+    let after = short_descriptor()
+        .locate_gcid(&mut store)
+        .expect("couldn't find category in store");
+    assert_eq!(before, after);
+    assert_eq!(
+        3,
+        run::Source::runs_for(&mut store, after)
+            .expect("couldn't list runs")
+            .len()
+    );
+}
+
+/// Tests that the store lists game-categories in the same order as the database.
+#[test]
+fn categories_sorted_by_name() {
+    let mut store = memory::Store::new();
+    // These are interned out of name order, so sorting by address would list them out of order.
+    for short in ["zz", "scd11", "aa"] {
+        let game = game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load game");
+        store.add_game(short, game);
+    }
+
+    let shorts: Vec<String> = store
+        .all_game_category_info()
+        .expect("couldn't list categories")
+        .into_iter()
+        .map(|t| t.short.to_string())
+        .collect();
+    let mut sorted = shorts.clone();
+    sorted.sort();
+    assert_eq!(sorted, shorts);
+}
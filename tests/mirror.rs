@@ -0,0 +1,115 @@
+//! Integration tests for mirroring session state from events.
+
+use zombiesplit::model::{
+    game,
+    session::{
+        action::{Action, Handler, OldDestination, Pop},
+        mirror::{self, Mirror},
+        Attempt, Session, State,
+    },
+    short,
+    timing::{
+        aggregate,
+        comparison::{self, Comparison},
+        time::Time,
+    },
+    Loadable,
+};
+
+const SAMPLE_GAME_PATH: &str = "scd11.toml";
+const SAMPLE_GAME_NAME: &str = "scd11";
+const SAMPLE_CATEGORY_NAME: &str = "btg-sonic";
+
+fn make_attempt() -> Attempt {
+    let game = game::Config::from_toml_file(SAMPLE_GAME_PATH).expect("couldn't load sample game");
+    Attempt::from_config(
+        &game,
+        game::category::ShortDescriptor::new(SAMPLE_GAME_NAME, SAMPLE_CATEGORY_NAME),
+    )
+    .expect("couldn't load game/category")
+}
+
+fn segment(millis: i64, cumulative: i64) -> comparison::Segment {
+    comparison::Segment {
+        split_pb: Time::from_millis(millis),
+        in_pb_run: aggregate::Set {
+            split: Time::from_millis(millis),
+            cumulative: Time::from_millis(cumulative),
+        },
+    }
+}
+
+/// Constructs a comparison covering Palmtree Panic in Sonic CD (2011).
+fn comparison() -> Comparison {
+    Comparison {
+        splits: short::Map::from([
+            ("pp1".into(), segment(25_060, 25_060)),
+            ("pp2".into(), segment(25_300, 50_360)),
+            ("pp3".into(), segment(24_260, 74_620)),
+        ]),
+        segments: short::Map::from([("pp".into(), segment(74_000, 74_620))]),
+        run: comparison::Run::default(),
+    }
+}
+
+/// Runs a session observed by a mirror, checking the mirror against the session after each action.
+#[test]
+fn consistent() {
+    let attempt = make_attempt();
+    let mirror = Mirror::new(State::new(attempt.clone(), Comparison::default()));
+    let mut session = Session::builder(attempt, &mirror)
+        .comparison_provider(Box::new(Some(comparison())))
+        .build()
+        .expect("couldn't build session");
+    assert_eq!(
+        Vec::<mirror::Mismatch>::new(),
+        mirror.check(session.state())
+    );
+
+    for action in [
+        Action::Push(0, Time::from_millis(24_500)),
+        Action::Push(1, Time::from_millis(26_000)),
+        Action::Push(1, Time::from_millis(1_000)),
+        Action::Pop(1, Pop::One),
+        Action::Push(2, Time::from_millis(23_900)),
+        Action::Pop(0, Pop::All),
+        Action::Push(0, Time::from_millis(25_100)),
+        Action::NewRun(OldDestination::Save),
+        Action::Push(0, Time::from_millis(25_700)),
+        Action::NewRun(OldDestination::Discard),
+    ] {
+        session.handle(action).expect("action failed");
+        assert_eq!(
+            Vec::<mirror::Mismatch>::new(),
+            mirror.check(session.state()),
+            "mirror inconsistent after {action:?}"
+        );
+    }
+}
+
+/// Tests that the checker notices a mirror that missed an event, and that resyncing fixes it.
+#[test]
+fn missed_event() {
+    let attempt = make_attempt();
+    let mirror = Mirror::new(State::new(attempt.clone(), Comparison::default()));
+    let mut session = Session::builder(attempt, &mirror)
+        .comparison_provider(Box::new(Some(comparison())))
+        .build()
+        .expect("couldn't build session");
+    session
+        .handle(Action::Push(0, Time::from_millis(24_500)))
+        .expect("action failed");
+
+    // Simulate missing the push by undoing it on the mirror's side.
+    let _ = mirror
+        .state()
+        .attempt
+        .splits
+        .get_mut(0)
+        .and_then(|s| s.pop());
+    let mismatches = mirror.check(session.state());
+    assert!(mismatches.contains(&mirror::Mismatch::Times("pp1".into())));
+
+    mirror.resync(session.state().clone());
+    assert!(mirror.check(session.state()).is_empty());
+}
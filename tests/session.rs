@@ -7,7 +7,7 @@ use zombiesplit::model::{
     timing::{
         aggregate,
         comparison::{self, delta, Comparison},
-        time::human,
+        Time,
     },
     Loadable,
 };
@@ -29,15 +29,20 @@ fn make_attempt() -> Attempt {
     .expect("couldn't load game/category")
 }
 
-fn split(name: &str, h: u32, m: u32, s: u32, ms: u32) -> (short::Name, comparison::Segment) {
+/// Makes a time from hours, minutes, seconds, and milliseconds.
+fn time(h: i64, m: i64, s: i64, ms: i64) -> Time {
+    Time::from_millis((((h * 60) + m) * 60 + s) * 1000 + ms)
+}
+
+fn split(name: &str, h: i64, m: i64, s: i64, ms: i64) -> (short::Name, comparison::Segment) {
     let in_pb_run = aggregate::Set {
-        split: human::Time::new(h, m, s, ms).expect("time overflowed"),
-        cumulative: Default::default(),
+        split: time(h, m, s, ms),
+        cumulative: Time::default(),
     };
     (
         name.into(),
         comparison::Segment {
-            split_pb: human::Time::default(),
+            split_pb: Time::default(),
             in_pb_run,
         },
     )
@@ -70,7 +75,7 @@ fn comparison() -> Comparison {
     ];
 
     // Fix up the cumulatives
-    let mut accum = human::Time::default();
+    let mut accum = Time::default();
     for (_, split) in &mut splits {
         accum += split.in_pb_run.split;
         split.in_pb_run.cumulative = accum;
@@ -78,23 +83,21 @@ fn comparison() -> Comparison {
 
     Comparison {
         splits: short::Map::from(splits),
+        segments: Default::default(),
         run: Default::default(),
     }
 }
 
 fn make_session<'o, T: event::observer::Observer>(obs: &'o T) -> Session<'static, 'o, T> {
-    let mut s = Session::new(make_attempt(), obs);
-
-    s.set_comparison_provider(Box::new(Some(comparison())));
-
-    s
-}
-
-fn delta(string: &str) -> delta::Delta {
-    string.parse().expect("couldn't parse delta")
+    Session::builder(make_attempt(), obs)
+        .comparison_provider(Box::new(Some(comparison())))
+        .build()
+        .expect("couldn't build session")
 }
 
-fn split_delta(split: &str, run: &str) -> delta::Split {
+/// Makes a split delta from split and run deltas given in seconds.
+fn split_delta(split: i64, run: i64) -> delta::Split {
+    let delta = |secs| delta::Delta::new(Time::from_millis(secs * 1000));
     delta::Split::new(delta(split), delta(run))
 }
 
@@ -106,29 +109,28 @@ fn test_session_deltas() {
 
     // 1 second ahead of previous time
     push(&mut s, "pp1", 0, 0, 24, 60);
-    assert_eq!(Some(split_delta("-1s", "-1s")), obs.delta("pp1"));
+    assert_eq!(Some(split_delta(-1, -1)), obs.delta("pp1"));
 
     // 2 seconds behind previous time
     push(&mut s, "pp2", 0, 0, 27, 300);
-    assert_eq!(Some(split_delta("-1s", "-1s")), obs.delta("pp1"));
-    assert_eq!(Some(split_delta("+2s", "+1s")), obs.delta("pp2"));
+    assert_eq!(Some(split_delta(-1, -1)), obs.delta("pp1"));
+    assert_eq!(Some(split_delta(2, 1)), obs.delta("pp2"));
 
     // No change in time
     push(&mut s, "pp3", 0, 0, 24, 260);
-    assert_eq!(Some(split_delta("-1s", "-1s")), obs.delta("pp1"));
-    assert_eq!(Some(split_delta("+2s", "+1s")), obs.delta("pp2"));
-    assert_eq!(Some(split_delta("-0s", "+1s")), obs.delta("pp3"));
+    assert_eq!(Some(split_delta(-1, -1)), obs.delta("pp1"));
+    assert_eq!(Some(split_delta(2, 1)), obs.delta("pp2"));
+    assert_eq!(Some(split_delta(0, 1)), obs.delta("pp3"));
 
     // Recalculate
     push(&mut s, "pp1", 0, 0, 1, 0);
-    assert_eq!(Some(split_delta("-0s", "-0s")), obs.delta("pp1"));
-    assert_eq!(Some(split_delta("+2s", "+2s")), obs.delta("pp2"));
-    assert_eq!(Some(split_delta("-0s", "+2s")), obs.delta("pp3"));
+    assert_eq!(Some(split_delta(0, 0)), obs.delta("pp1"));
+    assert_eq!(Some(split_delta(2, 2)), obs.delta("pp2"));
+    assert_eq!(Some(split_delta(0, 2)), obs.delta("pp3"));
 }
 
-fn push(session: &mut Session<DeltaLogger>, name: &str, h: u32, m: u32, s: u32, ms: u32) {
-    let time = human::Time::new(h, m, s, ms).expect("time construction error");
-    session.push_to(short::Name::from(name), time);
+fn push(session: &mut Session<DeltaLogger>, name: &str, h: i64, m: i64, s: i64, ms: i64) {
+    session.push_to(short::Name::from(name), time(h, m, s, ms));
 }
 
 #[derive(Default)]
@@ -145,9 +147,14 @@ impl DeltaLogger {
 
 impl event::observer::Observer for DeltaLogger {
     fn observe(&self, evt: event::Event) {
-        if let event::Event::Split(n, event::Split::Delta(d)) = evt {
+        if let event::Event::Notes(notes) = evt {
             let mut log = self.log.lock().expect("couldn't lock log");
-            log.insert(n, d);
+            log.extend(
+                notes
+                    .splits
+                    .into_iter()
+                    .filter_map(|(n, note)| Some((n, note.delta?))),
+            );
         }
     }
 }